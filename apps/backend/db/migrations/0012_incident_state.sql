@@ -0,0 +1,13 @@
+-- Migration number: 0012 	 2025-11-27T16:42:08.517Z
+PRAGMA defer_foreign_keys = true;
+
+ALTER TABLE monitor_dispatch_hot ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE monitor_dispatch_hot ADD COLUMN consecutive_successes INTEGER NOT NULL DEFAULT 0;
+
+-- At most one open or acknowledged incident per monitor.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_incidents_monitor_active
+  ON incidents (monitor_id) WHERE status != 'closed';
+
+-- 0004 rebuilt `incidents` without the 0001 index, so this is its only live definition.
+CREATE INDEX IF NOT EXISTS idx_incidents_monitor_opened
+  ON incidents (monitor_id, opened_ts);
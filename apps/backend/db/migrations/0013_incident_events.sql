@@ -0,0 +1,18 @@
+-- Migration number: 0013 	 2025-11-28T10:15:42.061Z
+PRAGMA defer_foreign_keys = true;
+
+CREATE TABLE incident_events (
+  id TEXT PRIMARY KEY,
+  incident_id TEXT NOT NULL REFERENCES incidents(id) ON DELETE CASCADE,
+  kind TEXT NOT NULL, -- opened, acknowledged, resolved, closed, note
+  actor_id TEXT, -- identity sub; NULL for events raised by the incident engine
+  actor_email TEXT,
+  message TEXT,
+  created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_incident_events_incident_created
+  ON incident_events (incident_id, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_incidents_status_opened
+  ON incidents (status, opened_ts);
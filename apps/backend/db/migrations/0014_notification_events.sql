@@ -0,0 +1,25 @@
+-- Migration number: 0014 	 2025-11-29T09:03:27.884Z
+PRAGMA defer_foreign_keys = true;
+
+-- One row per webhook attempt; attempts of the same logical delivery share delivery_id.
+CREATE TABLE notification_events (
+  id TEXT PRIMARY KEY,
+  delivery_id TEXT NOT NULL,
+  notification_id TEXT NOT NULL REFERENCES notifications(id) ON DELETE CASCADE,
+  incident_id TEXT REFERENCES incidents(id) ON DELETE SET NULL,
+  event TEXT NOT NULL,
+  attempt INTEGER NOT NULL,
+  status TEXT NOT NULL, -- delivered, retrying, failed
+  http_status INTEGER,
+  latency_ms INTEGER,
+  error TEXT,
+  next_retry_at INTEGER,
+  payload TEXT NOT NULL,
+  created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_events_notification_created
+  ON notification_events (notification_id, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_notification_events_delivery
+  ON notification_events (delivery_id, attempt);
@@ -0,0 +1,26 @@
+-- Migration number: 0015 	 2025-12-02T09:41:17.305Z
+PRAGMA defer_foreign_keys = true;
+
+CREATE TABLE status_pages (
+  id TEXT PRIMARY KEY,
+  org_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  slug TEXT NOT NULL UNIQUE, -- public path segment: /status/{slug}
+  title TEXT NOT NULL,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE status_page_monitors (
+  status_page_id TEXT NOT NULL REFERENCES status_pages(id) ON DELETE CASCADE,
+  monitor_id TEXT NOT NULL REFERENCES monitors(id) ON DELETE CASCADE,
+  display_name TEXT NOT NULL, -- shown publicly instead of the monitor name
+  position INTEGER NOT NULL DEFAULT 0,
+
+  PRIMARY KEY (status_page_id, monitor_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_status_pages_org
+  ON status_pages (org_id);
+
+CREATE INDEX IF NOT EXISTS idx_status_page_monitors_page_position
+  ON status_page_monitors (status_page_id, position);
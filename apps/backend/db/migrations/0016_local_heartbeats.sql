@@ -0,0 +1,22 @@
+-- Migration number: 0016 	 2025-12-04T10:12:43.518Z
+PRAGMA defer_foreign_keys = true;
+
+-- Stand-in for the Analytics Engine heartbeat dataset when ANALYTICS_BACKEND=local.
+-- Column names mirror AE (see src/analytics/schema.rs) so the same queries run on both.
+CREATE TABLE local_heartbeats (
+    index1 TEXT NOT NULL,
+    blob1 TEXT NOT NULL,
+    blob2 TEXT NOT NULL,
+    blob3 TEXT NOT NULL,
+    blob4 TEXT NOT NULL,
+    blob5 TEXT NOT NULL,
+    blob6 TEXT NOT NULL,
+    blob7 TEXT NOT NULL,
+    double1 REAL NOT NULL,
+    double2 REAL NOT NULL,
+    double3 REAL NOT NULL,
+    double4 REAL NOT NULL
+);
+
+CREATE INDEX idx_local_heartbeats_org_time ON local_heartbeats (blob1, double1);
+CREATE INDEX idx_local_heartbeats_monitor_time ON local_heartbeats (index1, double1);
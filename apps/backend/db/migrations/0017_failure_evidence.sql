@@ -0,0 +1,9 @@
+-- Migration number: 0017 	 2025-12-05T08:27:51.204Z
+PRAGMA defer_foreign_keys = true;
+
+-- Failed HTTP checks store their evidence in R2 under org/{org}/evidence/{dispatch_id}.json.
+-- The incident keeps the dispatch that opened it so the postmortem can start from there.
+ALTER TABLE incidents ADD COLUMN evidence_dispatch_id TEXT;
+
+-- Mirrors the new AE evidence blob (src/analytics/schema.rs).
+ALTER TABLE local_heartbeats ADD COLUMN blob8 TEXT NOT NULL DEFAULT '';
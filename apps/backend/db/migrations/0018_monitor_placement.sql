@@ -0,0 +1,54 @@
+-- Migration number: 0018 	 2025-12-06T10:14:37.582Z
+PRAGMA defer_foreign_keys = true;
+
+-- single: one relay. round_robin: one relay per run, rotating. all: every relay, every run.
+ALTER TABLE monitors ADD COLUMN placement TEXT NOT NULL DEFAULT 'single'
+  CHECK (placement IN ('single', 'round_robin', 'all'));
+
+CREATE TABLE monitor_relays (
+  monitor_id TEXT NOT NULL REFERENCES monitors(id) ON DELETE CASCADE,
+  relay_id TEXT NOT NULL REFERENCES relays(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL DEFAULT 0, -- round-robin order
+
+  PRIMARY KEY (monitor_id, relay_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_monitor_relays_relay ON monitor_relays(relay_id);
+
+INSERT INTO monitor_relays (monitor_id, relay_id, position)
+SELECT id, relay_id, 0 FROM monitors WHERE relay_id IS NOT NULL;
+
+-- One hot row per fan-out slot. `all` placement runs every relay in parallel, so each relay
+-- gets its own row (and incident counters); single and round_robin runs never overlap and
+-- share the monitor's row under relay_id ''.
+CREATE TABLE monitor_dispatch_hot_next (
+  monitor_id TEXT NOT NULL REFERENCES monitors(id) ON DELETE CASCADE,
+  relay_id TEXT NOT NULL DEFAULT '',
+  dispatch_id TEXT NOT NULL,
+  org_id TEXT NOT NULL,
+  status TEXT NOT NULL,
+  scheduled_for_ts INTEGER NOT NULL,
+  dispatched_at_ts INTEGER,
+  completed_at_ts INTEGER,
+  runner_colo TEXT,
+  error TEXT,
+  updated_at INTEGER NOT NULL,
+  consecutive_failures INTEGER NOT NULL DEFAULT 0,
+  consecutive_successes INTEGER NOT NULL DEFAULT 0,
+
+  PRIMARY KEY (monitor_id, relay_id)
+);
+
+INSERT INTO monitor_dispatch_hot_next (
+  monitor_id, relay_id, dispatch_id, org_id, status, scheduled_for_ts, dispatched_at_ts,
+  completed_at_ts, runner_colo, error, updated_at, consecutive_failures, consecutive_successes
+)
+SELECT monitor_id, '', dispatch_id, org_id, status, scheduled_for_ts, dispatched_at_ts,
+       completed_at_ts, runner_colo, error, updated_at, consecutive_failures, consecutive_successes
+FROM monitor_dispatch_hot;
+
+DROP TABLE monitor_dispatch_hot;
+ALTER TABLE monitor_dispatch_hot_next RENAME TO monitor_dispatch_hot;
+
+CREATE INDEX idx_monitor_dispatch_hot_org_status
+  ON monitor_dispatch_hot (org_id, status);
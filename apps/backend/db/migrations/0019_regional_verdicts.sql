@@ -0,0 +1,13 @@
+-- Migration number: 0019 	 2025-12-07T09:41:12.306Z
+PRAGMA defer_foreign_keys = true;
+
+-- Last result reported by each fan-out slot, kept next to the aggregate status on `monitors`.
+-- verdict_window_ts is the run (scheduled_for_ts) the verdict belongs to; regions reporting the
+-- same window are counted together for the down quorum.
+ALTER TABLE monitor_dispatch_hot ADD COLUMN verdict TEXT;
+ALTER TABLE monitor_dispatch_hot ADD COLUMN verdict_window_ts INTEGER;
+ALTER TABLE monitor_dispatch_hot ADD COLUMN verdict_region TEXT;
+ALTER TABLE monitor_dispatch_hot ADD COLUMN verdict_colo TEXT;
+ALTER TABLE monitor_dispatch_hot ADD COLUMN verdict_latency_ms INTEGER;
+ALTER TABLE monitor_dispatch_hot ADD COLUMN verdict_error TEXT;
+ALTER TABLE monitor_dispatch_hot ADD COLUMN verdict_at INTEGER;
@@ -0,0 +1,7 @@
+-- Migration number: 0020 	 2025-12-08T08:52:19.447Z
+PRAGMA defer_foreign_keys = true;
+
+-- Mirrors the new AE attempt columns (src/analytics/schema.rs): blob9 holds the failed
+-- attempts' errors as a JSON array, double5 the attempt count.
+ALTER TABLE local_heartbeats ADD COLUMN blob9 TEXT NOT NULL DEFAULT '';
+ALTER TABLE local_heartbeats ADD COLUMN double5 REAL NOT NULL DEFAULT 0;
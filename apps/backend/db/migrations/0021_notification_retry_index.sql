@@ -0,0 +1,7 @@
+-- Migration number: 0021 	 2025-12-09T09:12:44.071Z
+PRAGMA defer_foreign_keys = true;
+
+-- Lets the delivery retry cron find due attempts without scanning the event log.
+CREATE INDEX IF NOT EXISTS idx_notification_events_retry_due
+  ON notification_events (next_retry_at)
+  WHERE next_retry_at IS NOT NULL;
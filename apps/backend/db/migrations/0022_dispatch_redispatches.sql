@@ -0,0 +1,10 @@
+-- Migration number: 0022 	 2025-12-10T08:41:17.526Z
+PRAGMA defer_foreign_keys = true;
+
+-- How many times the reaper has re-sent the current run; capped before it falls back to fail.
+ALTER TABLE monitor_dispatch_hot ADD COLUMN redispatches INTEGER NOT NULL DEFAULT 0;
+
+-- The ticker now stamps dispatched_at_ts when it sends, and the reaper filters on it alone.
+UPDATE monitor_dispatch_hot
+SET dispatched_at_ts = scheduled_for_ts
+WHERE dispatched_at_ts IS NULL;
@@ -0,0 +1,163 @@
+-- name: record_monitor_failure :one
+-- params: updated_at i64
+-- params: monitor_id String
+-- params: relay_id String
+UPDATE monitor_dispatch_hot
+SET consecutive_failures = consecutive_failures + 1,
+    consecutive_successes = 0,
+    updated_at = :updated_at
+WHERE monitor_id = :monitor_id
+  AND relay_id = :relay_id
+RETURNING consecutive_failures, consecutive_successes;
+
+-- name: record_monitor_success :one
+-- params: updated_at i64
+-- params: monitor_id String
+-- params: relay_id String
+UPDATE monitor_dispatch_hot
+SET consecutive_successes = consecutive_successes + 1,
+    consecutive_failures = 0,
+    updated_at = :updated_at
+WHERE monitor_id = :monitor_id
+  AND relay_id = :relay_id
+RETURNING consecutive_failures, consecutive_successes;
+
+-- name: count_other_failing_relays :scalar
+-- params: monitor_id String
+-- params: relay_id String
+-- params: failure_threshold i64
+SELECT COUNT(*) AS total
+FROM monitor_dispatch_hot h
+WHERE h.monitor_id = :monitor_id
+  AND h.relay_id != :relay_id
+  AND h.consecutive_failures >= :failure_threshold
+  AND h.relay_id IN (
+        SELECT mr.relay_id
+        FROM monitor_relays mr
+        JOIN relays r ON r.id = mr.relay_id
+        WHERE mr.monitor_id = :monitor_id
+          AND r.enabled = 1
+      );
+
+-- name: get_active_incident_for_monitor :one
+-- params: monitor_id String
+SELECT id, monitor_id, opened_ts, closed_ts, reason, status, created_at, updated_at
+FROM incidents
+WHERE monitor_id = :monitor_id
+  AND status != 'closed'
+LIMIT 1;
+
+-- name: open_incident :exec
+-- params: id String
+-- params: monitor_id String
+-- params: opened_ts i64
+-- params: reason Option<String>
+-- params: evidence_dispatch_id Option<String>
+-- params: created_at i64
+-- params: updated_at i64
+INSERT OR IGNORE INTO incidents (id, monitor_id, opened_ts, closed_ts, reason, evidence_dispatch_id, status, created_at, updated_at)
+VALUES (:id, :monitor_id, :opened_ts, NULL, :reason, :evidence_dispatch_id, 'open', :created_at, :updated_at);
+
+-- name: close_incident :exec
+-- params: closed_ts i64
+-- params: updated_at i64
+-- params: id String
+UPDATE incidents
+SET status = 'closed',
+    closed_ts = :closed_ts,
+    updated_at = :updated_at
+WHERE id = :id
+  AND status != 'closed';
+
+-- name: list_incidents_for_org :many
+-- params: org_id String
+-- params: status Option<String>
+-- params: monitor_id Option<String>
+-- params: since Option<i64>
+-- params: until Option<i64>
+-- params: limit i64
+-- params: offset i64
+SELECT i.id, i.monitor_id, m.name AS monitor_name, i.opened_ts, i.closed_ts, i.reason, i.evidence_dispatch_id, i.status, i.created_at, i.updated_at
+FROM incidents i
+JOIN monitors m ON m.id = i.monitor_id
+WHERE m.org_id = :org_id
+  AND (:status IS NULL OR i.status = :status)
+  AND (:monitor_id IS NULL OR i.monitor_id = :monitor_id)
+  AND (:since IS NULL OR i.opened_ts >= :since)
+  AND (:until IS NULL OR i.opened_ts < :until)
+ORDER BY i.opened_ts DESC
+LIMIT :limit OFFSET :offset;
+
+-- name: count_incidents_for_org :scalar
+-- params: org_id String
+-- params: status Option<String>
+-- params: monitor_id Option<String>
+-- params: since Option<i64>
+-- params: until Option<i64>
+SELECT COUNT(*) AS total
+FROM incidents i
+JOIN monitors m ON m.id = i.monitor_id
+WHERE m.org_id = :org_id
+  AND (:status IS NULL OR i.status = :status)
+  AND (:monitor_id IS NULL OR i.monitor_id = :monitor_id)
+  AND (:since IS NULL OR i.opened_ts >= :since)
+  AND (:until IS NULL OR i.opened_ts < :until);
+
+-- name: get_incident_for_org :one
+-- params: id String
+-- params: org_id String
+SELECT i.id, i.monitor_id, m.name AS monitor_name, i.opened_ts, i.closed_ts, i.reason, i.evidence_dispatch_id, i.status, i.created_at, i.updated_at
+FROM incidents i
+JOIN monitors m ON m.id = i.monitor_id
+WHERE i.id = :id
+  AND m.org_id = :org_id
+LIMIT 1;
+
+-- name: acknowledge_incident :one
+-- params: updated_at i64
+-- params: id String
+UPDATE incidents
+SET status = 'acknowledged',
+    updated_at = :updated_at
+WHERE id = :id
+  AND status = 'open'
+RETURNING id;
+
+-- name: resolve_incident :one
+-- params: closed_ts i64
+-- params: updated_at i64
+-- params: id String
+UPDATE incidents
+SET status = 'closed',
+    closed_ts = :closed_ts,
+    updated_at = :updated_at
+WHERE id = :id
+  AND status != 'closed'
+RETURNING id;
+
+-- name: reset_monitor_counters :exec
+-- params: updated_at i64
+-- params: monitor_id String
+UPDATE monitor_dispatch_hot
+SET consecutive_failures = 0,
+    consecutive_successes = 0,
+    updated_at = :updated_at
+WHERE monitor_id = :monitor_id;
+
+-- name: insert_incident_event :exec
+-- params: id String
+-- params: incident_id String
+-- params: kind String
+-- params: actor_id Option<String>
+-- params: actor_email Option<String>
+-- params: message Option<String>
+-- params: created_at i64
+INSERT INTO incident_events (id, incident_id, kind, actor_id, actor_email, message, created_at)
+VALUES (:id, :incident_id, :kind, :actor_id, :actor_email, :message, :created_at);
+
+-- name: list_incident_events :many
+-- params: incident_id String
+SELECT id, incident_id, kind, actor_id, actor_email, message, created_at
+FROM incident_events
+WHERE incident_id = :incident_id
+ORDER BY created_at ASC, id ASC;
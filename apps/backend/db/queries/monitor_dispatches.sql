@@ -1,11 +1,14 @@
 -- name: upsert_dispatch_pending :exec
 -- params: monitor_id String
+-- params: relay_id String
 -- params: dispatch_id String
 -- params: org_id String
 -- params: scheduled_for_ts i64
+-- params: redispatches i64
 -- params: updated_at i64
 INSERT INTO monitor_dispatch_hot (
   monitor_id,
+  relay_id,
   dispatch_id,
   org_id,
   status,
@@ -14,28 +17,33 @@ INSERT INTO monitor_dispatch_hot (
   completed_at_ts,
   runner_colo,
   error,
+  redispatches,
   updated_at
 ) VALUES (
   :monitor_id,
+  :relay_id,
   :dispatch_id,
   :org_id,
   'pending',
   :scheduled_for_ts,
+  :updated_at,
   NULL,
   NULL,
   NULL,
-  NULL,
+  :redispatches,
   :updated_at
 )
-ON CONFLICT(monitor_id) DO UPDATE SET
+ON CONFLICT(monitor_id, relay_id) DO UPDATE SET
   dispatch_id = excluded.dispatch_id,
   org_id = excluded.org_id,
   status = 'pending',
   scheduled_for_ts = excluded.scheduled_for_ts,
-  dispatched_at_ts = NULL,
+  -- Sent now; the runner overwrites it when it starts.
+  dispatched_at_ts = excluded.dispatched_at_ts,
   completed_at_ts = NULL,
   runner_colo = NULL,
   error = NULL,
+  redispatches = excluded.redispatches,
   updated_at = excluded.updated_at;
 
 -- name: mark_dispatch_running :exec
@@ -65,4 +73,105 @@ SET status = :status,
     error = :error,
     updated_at = :updated_at
 WHERE monitor_id = :monitor_id
-  AND dispatch_id = :dispatch_id;
\ No newline at end of file
+  AND dispatch_id = :dispatch_id;
+-- name: list_stale_dispatches :many
+-- params: org_id String
+-- params: started_before i64
+-- params: limit i64
+SELECT h.monitor_id, h.relay_id, h.dispatch_id, h.status AS dispatch_status, h.scheduled_for_ts,
+       h.dispatched_at_ts, h.redispatches, h.runner_colo, m.kind, m.config_json, m.status, m.first_checked_at, m.last_failed_at,
+       m.placement,
+       -- Enabled relays only, as [{relay_id, object_id, position}]; the ticker sorts by position.
+       (SELECT json_group_array(json_object(
+                 'relay_id', mr.relay_id, 'object_id', r.durable_object_id, 'position', mr.position))
+          FROM monitor_relays mr
+          JOIN relays r ON r.id = mr.relay_id
+         WHERE mr.monitor_id = m.id AND r.enabled = 1) AS relays_json
+FROM monitor_dispatch_hot h
+JOIN monitors m ON m.id = h.monitor_id
+WHERE h.org_id = :org_id
+  AND h.status IN ('pending', 'running')
+  -- Stamped by the ticker when it sends and again by the runner when it starts, so a
+  -- redispatch restarts the clock while scheduled_for_ts keeps naming the original run.
+  AND h.dispatched_at_ts < :started_before
+ORDER BY h.scheduled_for_ts ASC
+LIMIT :limit;
+
+-- name: mark_dispatch_timed_out :one
+-- params: completed_at_ts i64
+-- params: error String
+-- params: updated_at i64
+-- params: monitor_id String
+-- params: dispatch_id String
+UPDATE monitor_dispatch_hot
+SET status = 'timed_out',
+    completed_at_ts = :completed_at_ts,
+    error = :error,
+    updated_at = :updated_at
+WHERE monitor_id = :monitor_id
+  AND dispatch_id = :dispatch_id
+  AND status IN ('pending', 'running')
+RETURNING monitor_id;
+
+-- name: mark_dispatch_failed :exec
+-- params: completed_at_ts i64
+-- params: error String
+-- params: updated_at i64
+-- params: monitor_id String
+-- params: dispatch_id String
+UPDATE monitor_dispatch_hot
+SET status = 'failed',
+    completed_at_ts = :completed_at_ts,
+    error = :error,
+    updated_at = :updated_at
+WHERE monitor_id = :monitor_id
+  AND dispatch_id = :dispatch_id
+  AND status IN ('pending', 'running');
+
+-- name: record_dispatch_verdict :exec
+-- params: verdict String
+-- params: verdict_window_ts i64
+-- params: verdict_region String
+-- params: verdict_colo String
+-- params: verdict_latency_ms i64
+-- params: verdict_error Option<String>
+-- params: verdict_at i64
+-- params: monitor_id String
+-- params: relay_id String
+UPDATE monitor_dispatch_hot
+SET verdict = :verdict,
+    verdict_window_ts = :verdict_window_ts,
+    verdict_region = :verdict_region,
+    verdict_colo = :verdict_colo,
+    verdict_latency_ms = :verdict_latency_ms,
+    verdict_error = :verdict_error,
+    verdict_at = :verdict_at
+WHERE monitor_id = :monitor_id
+  AND relay_id = :relay_id;
+
+-- name: count_down_verdicts :scalar
+-- params: monitor_id String
+-- params: verdict_window_ts i64
+SELECT COUNT(*) AS total
+FROM monitor_dispatch_hot
+WHERE monitor_id = :monitor_id
+  AND verdict_window_ts = :verdict_window_ts
+  AND verdict = 'down';
+
+-- name: list_region_verdicts_for_org :many
+-- params: org_id String
+SELECT h.monitor_id, h.relay_id, h.verdict, h.verdict_window_ts, h.verdict_region, h.verdict_colo,
+       h.verdict_latency_ms, h.verdict_error, h.verdict_at
+FROM monitor_dispatch_hot h
+WHERE h.org_id = :org_id
+  AND h.verdict IS NOT NULL
+ORDER BY h.monitor_id, h.relay_id;
+
+-- name: list_region_verdicts_for_monitor :many
+-- params: monitor_id String
+SELECT h.monitor_id, h.relay_id, h.verdict, h.verdict_window_ts, h.verdict_region, h.verdict_colo,
+       h.verdict_latency_ms, h.verdict_error, h.verdict_at
+FROM monitor_dispatch_hot h
+WHERE h.monitor_id = :monitor_id
+  AND h.verdict IS NOT NULL
+ORDER BY h.relay_id;
@@ -31,25 +31,32 @@ INSERT INTO monitors (
 );
 
 -- name: get_monitor_by_id :one
-SELECT id, org_id, name, kind, enabled, config_json, status, last_checked_at, last_failed_at, first_checked_at, rt_ms, region, relay_id, last_error, next_run_at, created_at, updated_at FROM monitors WHERE id = :id AND org_id = :org_id;
+SELECT id, org_id, name, kind, enabled, config_json, status, last_checked_at, last_failed_at, first_checked_at, rt_ms, region, relay_id, placement, last_error, next_run_at, created_at, updated_at FROM monitors WHERE id = :id AND org_id = :org_id;
 
 -- name: get_monitors_by_org_id :many
-SELECT id, org_id, name, kind, enabled, config_json, status, last_checked_at, last_failed_at, first_checked_at, rt_ms, region, relay_id, last_error, next_run_at, created_at, updated_at FROM monitors WHERE org_id = :org_id ORDER BY created_at DESC;
+SELECT id, org_id, name, kind, enabled, config_json, status, last_checked_at, last_failed_at, first_checked_at, rt_ms, region, relay_id, placement, last_error, next_run_at, created_at, updated_at FROM monitors WHERE org_id = :org_id ORDER BY created_at DESC;
 
 -- name: list_due_monitors :many
 -- params: org_id String
 -- params: next_run_at Option<i64>
 -- params: limit i64
-SELECT id, kind, config_json, status, first_checked_at, last_failed_at, next_run_at, relay_id
-FROM monitors
-WHERE org_id = :org_id
-  AND enabled = 1
+SELECT m.id, m.kind, m.config_json, m.status, m.first_checked_at, m.last_failed_at,
+       m.next_run_at, m.placement,
+       -- Enabled relays only, as [{relay_id, object_id, position}]; the ticker sorts by position.
+       (SELECT json_group_array(json_object(
+                 'relay_id', mr.relay_id, 'object_id', r.durable_object_id, 'position', mr.position))
+          FROM monitor_relays mr
+          JOIN relays r ON r.id = mr.relay_id
+         WHERE mr.monitor_id = m.id AND r.enabled = 1) AS relays_json
+FROM monitors m
+WHERE m.org_id = :org_id
+  AND m.enabled = 1
   AND (
         :next_run_at IS NULL
-        OR next_run_at IS NULL
-        OR next_run_at <= :next_run_at
+        OR m.next_run_at IS NULL
+        OR m.next_run_at <= :next_run_at
       )
-ORDER BY COALESCE(next_run_at, 0) ASC
+ORDER BY COALESCE(m.next_run_at, 0) ASC
 LIMIT :limit;
 
 -- name: delete_monitor :exec
@@ -75,9 +82,43 @@ UPDATE monitors SET status = :status, last_checked_at = :last_checked_at, last_f
 -- params: updated_at i64
 UPDATE monitors SET next_run_at = :next_run_at, last_checked_at = :last_checked_at, updated_at = :updated_at WHERE id = :id AND org_id = :org_id;
 
--- name: set_monitor_relay :exec
+-- name: defer_monitor_next_run_at :exec :stmt
 -- params: id String
 -- params: org_id String
+-- params: next_run_at i64
+-- params: updated_at i64
+UPDATE monitors SET next_run_at = :next_run_at, updated_at = :updated_at WHERE id = :id AND org_id = :org_id;
+
+-- name: set_monitor_placement :exec :stmt
+-- params: id String
+-- params: org_id String
+-- params: placement String
 -- params: relay_id String
 -- params: updated_at i64
-UPDATE monitors SET relay_id = :relay_id, updated_at = :updated_at WHERE id = :id AND org_id = :org_id;
\ No newline at end of file
+UPDATE monitors SET placement = :placement, relay_id = :relay_id, updated_at = :updated_at WHERE id = :id AND org_id = :org_id;
+
+-- name: list_monitor_relays :many
+-- params: monitor_id String
+SELECT monitor_id, relay_id, position FROM monitor_relays WHERE monitor_id = :monitor_id ORDER BY position ASC;
+
+-- name: list_monitor_relays_for_org :many
+-- params: org_id String
+SELECT mr.monitor_id, mr.relay_id, mr.position
+FROM monitor_relays mr
+JOIN monitors m ON m.id = mr.monitor_id
+WHERE m.org_id = :org_id
+ORDER BY mr.monitor_id, mr.position ASC;
+
+-- name: delete_monitor_relays :exec :stmt
+-- params: monitor_id String
+DELETE FROM monitor_relays WHERE monitor_id = :monitor_id;
+
+-- name: insert_monitor_relay :exec :stmt
+-- params: monitor_id String
+-- params: relay_id String
+-- params: position i64
+INSERT INTO monitor_relays (monitor_id, relay_id, position) VALUES (:monitor_id, :relay_id, :position);
+
+-- name: delete_monitor_hot_rows :exec :stmt
+-- params: monitor_id String
+DELETE FROM monitor_dispatch_hot WHERE monitor_id = :monitor_id;
\ No newline at end of file
@@ -0,0 +1,136 @@
+-- name: list_notifications_for_monitor :many
+-- params: monitor_id String
+-- params: org_id String
+SELECT n.id, n.monitor_id, n.kind, n.target, n.config_json, n.created_at, n.updated_at
+FROM notifications n
+JOIN monitors m ON m.id = n.monitor_id
+WHERE n.monitor_id = :monitor_id
+  AND m.org_id = :org_id
+ORDER BY n.created_at ASC;
+
+-- name: get_notification_for_monitor :one
+-- params: id String
+-- params: monitor_id String
+-- params: org_id String
+SELECT n.id, n.monitor_id, n.kind, n.target, n.config_json, n.created_at, n.updated_at
+FROM notifications n
+JOIN monitors m ON m.id = n.monitor_id
+WHERE n.id = :id
+  AND n.monitor_id = :monitor_id
+  AND m.org_id = :org_id
+LIMIT 1;
+
+-- name: list_notifications_by_kind :many
+-- params: monitor_id String
+-- params: kind String
+SELECT id, monitor_id, kind, target, config_json, created_at, updated_at
+FROM notifications
+WHERE monitor_id = :monitor_id
+  AND kind = :kind;
+
+-- name: insert_notification :exec
+-- params: id String
+-- params: monitor_id String
+-- params: kind String
+-- params: target String
+-- params: config_json String
+-- params: created_at i64
+-- params: updated_at i64
+INSERT INTO notifications (id, monitor_id, kind, target, config_json, created_at, updated_at)
+VALUES (:id, :monitor_id, :kind, :target, :config_json, :created_at, :updated_at);
+
+-- name: update_notification :exec
+-- params: target String
+-- params: config_json String
+-- params: updated_at i64
+-- params: id String
+UPDATE notifications
+SET target = :target,
+    config_json = :config_json,
+    updated_at = :updated_at
+WHERE id = :id;
+
+-- name: delete_notification :exec
+-- params: id String
+DELETE FROM notifications WHERE id = :id;
+
+-- name: insert_notification_event :exec
+-- params: id String
+-- params: delivery_id String
+-- params: notification_id String
+-- params: incident_id Option<String>
+-- params: event String
+-- params: attempt i64
+-- params: status String
+-- params: http_status Option<i64>
+-- params: latency_ms i64
+-- params: error Option<String>
+-- params: next_retry_at Option<i64>
+-- params: payload String
+-- params: created_at i64
+INSERT INTO notification_events (id, delivery_id, notification_id, incident_id, event, attempt, status, http_status, latency_ms, error, next_retry_at, payload, created_at)
+VALUES (:id, :delivery_id, :notification_id, :incident_id, :event, :attempt, :status, :http_status, :latency_ms, :error, :next_retry_at, :payload, :created_at);
+
+-- name: list_notification_events_for_org :many
+-- params: notification_id String
+-- params: org_id String
+-- params: limit i64
+-- params: offset i64
+SELECT e.id, e.delivery_id, e.notification_id, e.incident_id, e.event, e.attempt, e.status, e.http_status, e.latency_ms, e.error, e.next_retry_at, e.created_at
+FROM notification_events e
+JOIN notifications n ON n.id = e.notification_id
+JOIN monitors m ON m.id = n.monitor_id
+WHERE e.notification_id = :notification_id
+  AND m.org_id = :org_id
+ORDER BY e.created_at DESC, e.attempt DESC
+LIMIT :limit OFFSET :offset;
+
+-- name: get_notification_event_for_org :one
+-- params: id String
+-- params: notification_id String
+-- params: org_id String
+SELECT e.id, e.delivery_id, e.notification_id, e.incident_id, e.event, e.attempt, e.status, e.http_status, e.latency_ms, e.error, e.next_retry_at, e.payload, e.created_at, n.target, n.config_json
+FROM notification_events e
+JOIN notifications n ON n.id = e.notification_id
+JOIN monitors m ON m.id = n.monitor_id
+WHERE e.id = :id
+  AND e.notification_id = :notification_id
+  AND m.org_id = :org_id
+LIMIT 1;
+
+-- name: get_latest_delivery_attempt :one
+-- params: delivery_id String
+SELECT attempt, status, next_retry_at
+FROM notification_events
+WHERE delivery_id = :delivery_id
+ORDER BY attempt DESC
+LIMIT 1;
+
+-- name: get_notification_owner :one
+-- params: id String
+-- params: org_id String
+SELECT n.id, n.monitor_id
+FROM notifications n
+JOIN monitors m ON m.id = n.monitor_id
+WHERE n.id = :id
+  AND m.org_id = :org_id
+LIMIT 1;
+
+-- name: list_due_delivery_retries :many
+-- params: now i64
+-- params: limit i64
+SELECT e.id, e.delivery_id, e.notification_id, e.incident_id, e.event, e.attempt, e.payload, n.target, n.config_json
+FROM notification_events e
+JOIN notifications n ON n.id = e.notification_id
+WHERE e.next_retry_at IS NOT NULL
+  AND e.next_retry_at <= :now
+ORDER BY e.next_retry_at
+LIMIT :limit;
+
+-- name: claim_delivery_retry :one
+-- params: id String
+UPDATE notification_events
+SET next_retry_at = NULL
+WHERE id = :id
+  AND next_retry_at IS NOT NULL
+RETURNING id;
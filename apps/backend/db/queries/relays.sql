@@ -43,3 +43,75 @@ SELECT * FROM relays WHERE id = :id LIMIT 1;
 
 -- name: list_relays :many
 SELECT * FROM relays ORDER BY created_at DESC;
+
+-- name: update_relay :one :stmt
+-- params: name Option<String>
+-- params: enabled Option<i64>
+-- params: updated_at i64
+-- params: id String
+UPDATE relays
+SET name = COALESCE(:name, name),
+    enabled = COALESCE(:enabled, enabled),
+    updated_at = :updated_at
+WHERE id = :id
+RETURNING *;
+
+-- name: record_relay_bootstrapped :exec
+-- params: last_bootstrapped_at i64
+-- params: updated_at i64
+-- params: id String
+UPDATE relays
+SET last_bootstrapped_at = :last_bootstrapped_at,
+    last_error = NULL,
+    updated_at = :updated_at
+WHERE id = :id;
+
+-- name: record_relay_health :exec
+-- params: last_error Option<String>
+-- params: updated_at i64
+-- params: id String
+UPDATE relays
+SET last_error = :last_error,
+    updated_at = :updated_at
+WHERE id = :id;
+
+-- name: count_relay_monitors :scalar
+-- params: relay_id String
+SELECT COUNT(*) AS total
+FROM monitors
+WHERE relay_id = :relay_id
+   OR id IN (SELECT monitor_id FROM monitor_relays WHERE relay_id = :relay_id);
+
+-- name: count_relay_open_incidents :scalar
+-- params: relay_id String
+SELECT COUNT(*) AS total
+FROM incidents
+WHERE status != 'closed'
+  AND monitor_id IN (SELECT monitor_id FROM monitor_dispatch_hot WHERE relay_id = :relay_id);
+
+-- name: reassign_monitor_relays :exec :stmt
+-- params: to_relay_id String
+-- params: from_relay_id String
+INSERT OR IGNORE INTO monitor_relays (monitor_id, relay_id, position)
+SELECT monitor_id, :to_relay_id, position FROM monitor_relays WHERE relay_id = :from_relay_id;
+
+-- name: reassign_primary_relay :exec :stmt
+-- params: to_relay_id String
+-- params: updated_at i64
+-- params: from_relay_id String
+UPDATE monitors
+SET relay_id = :to_relay_id,
+    updated_at = :updated_at
+WHERE relay_id = :from_relay_id;
+
+-- name: delete_relay_assignments :exec :stmt
+-- params: relay_id String
+DELETE FROM monitor_relays WHERE relay_id = :relay_id;
+
+-- name: delete_relay_hot_rows :exec :stmt
+-- params: relay_id String
+DELETE FROM monitor_dispatch_hot WHERE relay_id = :relay_id;
+
+-- name: delete_relay :exec :stmt
+-- params: id String
+DELETE FROM relays WHERE id = :id;
@@ -1,6 +1,24 @@
+CREATE INDEX idx_incident_events_incident_created
+  ON incident_events (incident_id, created_at)
+
+CREATE UNIQUE INDEX idx_incidents_monitor_active
+  ON incidents (monitor_id) WHERE status != 'closed'
+
+CREATE INDEX idx_incidents_monitor_opened
+  ON incidents (monitor_id, opened_ts)
+
+CREATE INDEX idx_incidents_status_opened
+  ON incidents (status, opened_ts)
+
+CREATE INDEX idx_local_heartbeats_monitor_time ON local_heartbeats (index1, double1)
+
+CREATE INDEX idx_local_heartbeats_org_time ON local_heartbeats (blob1, double1)
+
 CREATE INDEX idx_monitor_dispatch_hot_org_status
   ON monitor_dispatch_hot (org_id, status)
 
+CREATE INDEX idx_monitor_relays_relay ON monitor_relays(relay_id)
+
 CREATE INDEX idx_monitors_org_enabled_next_run
   ON monitors (org_id, enabled, next_run_at)
 
@@ -9,6 +27,16 @@ CREATE INDEX idx_monitors_relay ON monitors(relay_id)
 CREATE INDEX idx_monitors_status
   ON monitors (status)
 
+CREATE INDEX idx_notification_events_delivery
+  ON notification_events (delivery_id, attempt)
+
+CREATE INDEX idx_notification_events_notification_created
+  ON notification_events (notification_id, created_at)
+
+CREATE INDEX idx_notification_events_retry_due
+  ON notification_events (next_retry_at)
+  WHERE next_retry_at IS NOT NULL
+
 CREATE INDEX idx_notifications_monitor_kind
   ON notifications (monitor_id, kind)
 
@@ -18,6 +46,22 @@ CREATE INDEX idx_relays_enabled ON relays (enabled)
 
 CREATE INDEX idx_relays_location ON relays (location_hint)
 
+CREATE INDEX idx_status_page_monitors_page_position
+  ON status_page_monitors (status_page_id, position)
+
+CREATE INDEX idx_status_pages_org
+  ON status_pages (org_id)
+
+CREATE TABLE incident_events (
+  id TEXT PRIMARY KEY,
+  incident_id TEXT NOT NULL REFERENCES incidents(id) ON DELETE CASCADE,
+  kind TEXT NOT NULL, -- opened, acknowledged, resolved, closed, note
+  actor_id TEXT, -- identity sub; NULL for events raised by the incident engine
+  actor_email TEXT,
+  message TEXT,
+  created_at INTEGER NOT NULL
+)
+
 CREATE TABLE incidents (
   id TEXT PRIMARY KEY,
   monitor_id TEXT NOT NULL REFERENCES monitors(id) ON DELETE CASCADE,
@@ -27,7 +71,22 @@ CREATE TABLE incidents (
   status TEXT NOT NULL DEFAULT 'open', -- open, acknowledged, closed
   created_at INTEGER NOT NULL,
   updated_at INTEGER NOT NULL
-)
+, evidence_dispatch_id TEXT)
+
+CREATE TABLE local_heartbeats (
+    index1 TEXT NOT NULL,
+    blob1 TEXT NOT NULL,
+    blob2 TEXT NOT NULL,
+    blob3 TEXT NOT NULL,
+    blob4 TEXT NOT NULL,
+    blob5 TEXT NOT NULL,
+    blob6 TEXT NOT NULL,
+    blob7 TEXT NOT NULL,
+    double1 REAL NOT NULL,
+    double2 REAL NOT NULL,
+    double3 REAL NOT NULL,
+    double4 REAL NOT NULL
+, blob8 TEXT NOT NULL DEFAULT '', blob9 TEXT NOT NULL DEFAULT '', double5 REAL NOT NULL DEFAULT 0)
 
 CREATE TABLE members (
   identity_id TEXT NOT NULL UNIQUE PRIMARY KEY, -- references sub from CF Access JWT
@@ -37,8 +96,9 @@ CREATE TABLE members (
   updated_at INTEGER NOT NULL
 )
 
-CREATE TABLE monitor_dispatch_hot (
-  monitor_id TEXT PRIMARY KEY REFERENCES monitors(id) ON DELETE CASCADE,
+CREATE TABLE "monitor_dispatch_hot" (
+  monitor_id TEXT NOT NULL REFERENCES monitors(id) ON DELETE CASCADE,
+  relay_id TEXT NOT NULL DEFAULT '',
   dispatch_id TEXT NOT NULL,
   org_id TEXT NOT NULL,
   status TEXT NOT NULL,
@@ -47,7 +107,19 @@ CREATE TABLE monitor_dispatch_hot (
   completed_at_ts INTEGER,
   runner_colo TEXT,
   error TEXT,
-  updated_at INTEGER NOT NULL
+  updated_at INTEGER NOT NULL,
+  consecutive_failures INTEGER NOT NULL DEFAULT 0,
+  consecutive_successes INTEGER NOT NULL DEFAULT 0, verdict TEXT, verdict_window_ts INTEGER, verdict_region TEXT, verdict_colo TEXT, verdict_latency_ms INTEGER, verdict_error TEXT, verdict_at INTEGER, redispatches INTEGER NOT NULL DEFAULT 0,
+
+  PRIMARY KEY (monitor_id, relay_id)
+)
+
+CREATE TABLE monitor_relays (
+  monitor_id TEXT NOT NULL REFERENCES monitors(id) ON DELETE CASCADE,
+  relay_id TEXT NOT NULL REFERENCES relays(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL DEFAULT 0, -- round-robin order
+
+  PRIMARY KEY (monitor_id, relay_id)
 )
 
 CREATE TABLE "monitors" (
@@ -67,7 +139,24 @@ CREATE TABLE "monitors" (
   next_run_at INTEGER,
   created_at INTEGER NOT NULL,
   updated_at INTEGER NOT NULL
-, relay_id TEXT REFERENCES relays(id))
+, relay_id TEXT REFERENCES relays(id), placement TEXT NOT NULL DEFAULT 'single'
+  CHECK (placement IN ('single', 'round_robin', 'all')))
+
+CREATE TABLE notification_events (
+  id TEXT PRIMARY KEY,
+  delivery_id TEXT NOT NULL,
+  notification_id TEXT NOT NULL REFERENCES notifications(id) ON DELETE CASCADE,
+  incident_id TEXT REFERENCES incidents(id) ON DELETE SET NULL,
+  event TEXT NOT NULL,
+  attempt INTEGER NOT NULL,
+  status TEXT NOT NULL, -- delivered, retrying, failed
+  http_status INTEGER,
+  latency_ms INTEGER,
+  error TEXT,
+  next_retry_at INTEGER,
+  payload TEXT NOT NULL,
+  created_at INTEGER NOT NULL
+)
 
 CREATE TABLE notifications (
   id TEXT PRIMARY KEY,
@@ -116,3 +205,21 @@ CREATE TABLE settings (
   value TEXT NOT NULL,
   updated_at INTEGER NOT NULL
 )
+
+CREATE TABLE status_page_monitors (
+  status_page_id TEXT NOT NULL REFERENCES status_pages(id) ON DELETE CASCADE,
+  monitor_id TEXT NOT NULL REFERENCES monitors(id) ON DELETE CASCADE,
+  display_name TEXT NOT NULL, -- shown publicly instead of the monitor name
+  position INTEGER NOT NULL DEFAULT 0,
+
+  PRIMARY KEY (status_page_id, monitor_id)
+)
+
+CREATE TABLE status_pages (
+  id TEXT PRIMARY KEY,
+  org_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  slug TEXT NOT NULL UNIQUE, -- public path segment: /status/{slug}
+  title TEXT NOT NULL,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+)
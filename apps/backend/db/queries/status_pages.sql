@@ -0,0 +1,70 @@
+-- name: get_status_page_by_slug :one
+-- params: slug String
+SELECT id, org_id, slug, title, created_at, updated_at
+FROM status_pages
+WHERE slug = :slug
+LIMIT 1;
+
+-- name: get_status_page_for_org :one
+-- params: id String
+-- params: org_id String
+SELECT id, org_id, slug, title, created_at, updated_at
+FROM status_pages
+WHERE id = :id
+  AND org_id = :org_id
+LIMIT 1;
+
+-- name: list_status_pages_for_org :many
+-- params: org_id String
+SELECT id, org_id, slug, title, created_at, updated_at
+FROM status_pages
+WHERE org_id = :org_id
+ORDER BY created_at ASC;
+
+-- name: list_status_page_components :many
+-- params: status_page_id String
+SELECT spm.monitor_id, spm.display_name, spm.position, m.status, m.last_checked_at
+FROM status_page_monitors spm
+JOIN monitors m ON m.id = spm.monitor_id
+WHERE spm.status_page_id = :status_page_id
+ORDER BY spm.position ASC, spm.display_name ASC;
+
+-- name: insert_status_page :exec
+-- params: id String
+-- params: org_id String
+-- params: slug String
+-- params: title String
+-- params: created_at i64
+-- params: updated_at i64
+INSERT INTO status_pages (id, org_id, slug, title, created_at, updated_at)
+VALUES (:id, :org_id, :slug, :title, :created_at, :updated_at);
+
+-- name: update_status_page :exec
+-- params: slug String
+-- params: title String
+-- params: updated_at i64
+-- params: id String
+-- params: org_id String
+UPDATE status_pages
+SET slug = :slug,
+    title = :title,
+    updated_at = :updated_at
+WHERE id = :id
+  AND org_id = :org_id;
+
+-- name: delete_status_page :exec
+-- params: id String
+-- params: org_id String
+DELETE FROM status_pages WHERE id = :id AND org_id = :org_id;
+
+-- name: insert_status_page_monitor :exec
+-- params: status_page_id String
+-- params: monitor_id String
+-- params: display_name String
+-- params: position i64
+INSERT INTO status_page_monitors (status_page_id, monitor_id, display_name, position)
+VALUES (:status_page_id, :monitor_id, :display_name, :position);
+
+-- name: clear_status_page_monitors :exec
+-- params: status_page_id String
+DELETE FROM status_page_monitors WHERE status_page_id = :status_page_id;
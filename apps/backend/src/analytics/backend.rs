@@ -0,0 +1,115 @@
+use std::{cell::OnceCell, str::FromStr};
+
+use serde::de::DeserializeOwned;
+use strum::{Display, EnumString};
+use worker::{Env, Result};
+
+use crate::cloudflare::d1::get_d1;
+use crate::monitors::types::HeartbeatResult;
+
+use super::client::AeQueryClient;
+use super::local::LocalHeartbeatStore;
+use super::query::AeQuery;
+use super::schema::{heartbeat_data_point, heartbeat_values};
+
+/// Env var selecting the heartbeat store; unset means Analytics Engine.
+pub const ANALYTICS_BACKEND_VAR: &str = "ANALYTICS_BACKEND";
+
+const HEARTBEATS_BINDING: &str = "AE_HEARTBEATS";
+
+/// Where heartbeat history lives. Dispatch writes through `write_heartbeat` and every
+/// analytics read is an `AeQuery` handed to `fetch`, so callers never see which backend runs.
+#[allow(async_fn_in_trait)]
+pub trait HeartbeatStore {
+    async fn write_heartbeat(&self, event: &HeartbeatResult, relay_id: Option<&str>) -> Result<()>;
+
+    async fn fetch<T: DeserializeOwned>(&self, query: &AeQuery) -> Result<Vec<T>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum BackendKind {
+    AnalyticsEngine,
+    Local,
+}
+
+/// Cloudflare Analytics Engine: writes go to the `AE_HEARTBEATS` binding, reads to the SQL API.
+/// The query client is built on first read so dispatch never loads the API token.
+pub struct AnalyticsEngineStore {
+    env: Env,
+    client: OnceCell<AeQueryClient>,
+}
+
+impl AnalyticsEngineStore {
+    pub fn new(env: &Env) -> Self {
+        Self {
+            env: env.clone(),
+            client: OnceCell::new(),
+        }
+    }
+
+    async fn client(&self) -> Result<&AeQueryClient> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+        let client = AeQueryClient::from_env(&self.env).await?;
+        Ok(self.client.get_or_init(|| client))
+    }
+}
+
+impl HeartbeatStore for AnalyticsEngineStore {
+    async fn write_heartbeat(&self, event: &HeartbeatResult, relay_id: Option<&str>) -> Result<()> {
+        let dataset = self.env.analytics_engine(HEARTBEATS_BINDING)?;
+        dataset.write_data_point(&heartbeat_data_point(&heartbeat_values(event, relay_id)))
+    }
+
+    async fn fetch<T: DeserializeOwned>(&self, query: &AeQuery) -> Result<Vec<T>> {
+        self.client().await?.fetch(query).await
+    }
+}
+
+/// The store chosen by `ANALYTICS_BACKEND` (`analytics_engine` or `local`).
+pub enum AnalyticsBackend {
+    AnalyticsEngine(AnalyticsEngineStore),
+    Local(LocalHeartbeatStore),
+}
+
+impl AnalyticsBackend {
+    pub fn from_env(env: &Env) -> Result<Self> {
+        let kind = match env.var(ANALYTICS_BACKEND_VAR) {
+            Ok(value) => BackendKind::from_str(value.to_string().trim()).map_err(|_| {
+                worker::Error::RustError(format!(
+                    "Unknown {ANALYTICS_BACKEND_VAR} value {value}; expected analytics_engine or local"
+                ))
+            })?,
+            Err(_) => BackendKind::AnalyticsEngine,
+        };
+
+        match kind {
+            BackendKind::AnalyticsEngine => Ok(AnalyticsBackend::AnalyticsEngine(
+                AnalyticsEngineStore::new(env),
+            )),
+            BackendKind::Local => Ok(AnalyticsBackend::Local(LocalHeartbeatStore::new(get_d1(
+                env,
+            )?))),
+        }
+    }
+}
+
+impl HeartbeatStore for AnalyticsBackend {
+    async fn write_heartbeat(&self, event: &HeartbeatResult, relay_id: Option<&str>) -> Result<()> {
+        match self {
+            AnalyticsBackend::AnalyticsEngine(store) => {
+                store.write_heartbeat(event, relay_id).await
+            }
+            AnalyticsBackend::Local(store) => store.write_heartbeat(event, relay_id).await,
+        }
+    }
+
+    async fn fetch<T: DeserializeOwned>(&self, query: &AeQuery) -> Result<Vec<T>> {
+        match self {
+            AnalyticsBackend::AnalyticsEngine(store) => store.fetch(query).await,
+            AnalyticsBackend::Local(store) => store.fetch(query).await,
+        }
+    }
+}
@@ -1,8 +1,15 @@
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Deserialize};
 use worker::{
     console_log, wasm_bindgen::JsValue, Env, Fetch, Method, Request, RequestInit, Result,
 };
 
+use super::query::AeQuery;
+
+#[derive(Deserialize)]
+struct SqlResponse<T> {
+    data: Vec<T>,
+}
+
 #[derive(Clone)]
 pub struct AeQueryClient {
     endpoint: String,
@@ -31,6 +38,13 @@ impl AeQueryClient {
         &self.dataset
     }
 
+    /// Renders `query` against this client's dataset and returns the decoded rows.
+    pub async fn fetch<T: DeserializeOwned>(&self, query: &AeQuery) -> Result<Vec<T>> {
+        let sql = query.build(&self.dataset)?;
+        let response: SqlResponse<T> = self.query(&sql).await?;
+        Ok(response.data)
+    }
+
     #[tracing::instrument(
         name = "analytics.client.query",
         skip(self, sql),
@@ -1,23 +1,22 @@
 use axum::extract::FromRequestParts;
 use axum::http::{request::Parts, StatusCode};
 
-use crate::{analytics::client::AeQueryClient, router::AppState};
+use crate::{analytics::backend::AnalyticsBackend, router::AppState};
 
-#[derive(Clone)]
-pub struct AppAeClient(pub AeQueryClient);
+/// Heartbeat store selected by `ANALYTICS_BACKEND`, for both dispatch writes and reads.
+pub struct AppAnalytics(pub AnalyticsBackend);
 
-impl FromRequestParts<AppState> for AppAeClient {
+impl FromRequestParts<AppState> for AppAnalytics {
     type Rejection = StatusCode;
 
     async fn from_request_parts(
         _parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        AeQueryClient::from_env(&state.env())
-            .await
-            .map(AppAeClient)
+        AnalyticsBackend::from_env(&state.env())
+            .map(AppAnalytics)
             .map_err(|err| {
-                worker::console_error!("analytics.client: {err:?}");
+                worker::console_error!("analytics.backend: {err:?}");
                 StatusCode::INTERNAL_SERVER_ERROR
             })
     }
@@ -0,0 +1,409 @@
+//! D1-backed stand-in for Analytics Engine, used under `wrangler dev` and in tests. Heartbeats
+//! land in `local_heartbeats` under their AE column names. `AeQuery` filters are pushed down to
+//! SQLite as bound parameters; selects, grouping and ordering are evaluated here because
+//! SQLite has no `quantileExactWeighted` or `intDiv`.
+
+use std::{cmp::Ordering, collections::HashMap};
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use worker::{wasm_bindgen::JsValue, D1Database, Result};
+
+use crate::monitors::types::HeartbeatResult;
+
+use super::backend::HeartbeatStore;
+use super::query::{AeQuery, BinaryOp, Expr, Literal, Order};
+use super::schema::{heartbeat_values, HeartbeatColumn, BLOBS, DOUBLES};
+
+const TABLE: &str = "local_heartbeats";
+
+type Row = Map<String, Value>;
+
+pub struct LocalHeartbeatStore {
+    d1: D1Database,
+}
+
+impl LocalHeartbeatStore {
+    pub fn new(d1: D1Database) -> Self {
+        Self { d1 }
+    }
+}
+
+impl HeartbeatStore for LocalHeartbeatStore {
+    async fn write_heartbeat(&self, event: &HeartbeatResult, relay_id: Option<&str>) -> Result<()> {
+        let values = heartbeat_values(event, relay_id);
+        let columns = std::iter::once(HeartbeatColumn::MonitorId)
+            .chain(BLOBS)
+            .chain(DOUBLES)
+            .map(|column| column.physical())
+            .collect::<Vec<_>>();
+        let placeholders = (1..=columns.len())
+            .map(|position| format!("?{position}"))
+            .collect::<Vec<_>>();
+
+        let mut params = vec![JsValue::from_str(&values.index)];
+        params.extend(values.blobs.iter().map(|blob| JsValue::from_str(blob)));
+        params.extend(
+            values
+                .doubles
+                .iter()
+                .map(|double| JsValue::from_f64(*double)),
+        );
+
+        self.d1
+            .prepare(format!(
+                "INSERT INTO {TABLE} ({}) VALUES ({})",
+                columns.join(", "),
+                placeholders.join(", ")
+            ))
+            .bind(&params)?
+            .run()
+            .await?;
+        Ok(())
+    }
+
+    async fn fetch<T: DeserializeOwned>(&self, query: &AeQuery) -> Result<Vec<T>> {
+        let mut params = Vec::new();
+        let mut sql = format!("SELECT * FROM {TABLE}");
+        if !query.filters.is_empty() {
+            let filters = query
+                .filters
+                .iter()
+                .map(|filter| sqlite_filter(filter, &mut params))
+                .collect::<Result<Vec<_>>>()?;
+            sql.push_str(&format!(" WHERE {}", filters.join(" AND ")));
+        }
+
+        let rows = self
+            .d1
+            .prepare(sql)
+            .bind(&params)?
+            .all()
+            .await?
+            .results::<Row>()?;
+
+        evaluate(query, rows)?
+            .into_iter()
+            .map(|row| {
+                serde_json::from_value(Value::Object(row))
+                    .map_err(|err| local_error(&format!("failed to decode row: {err}")))
+            })
+            .collect()
+    }
+}
+
+/// Renders a filter as SQLite, binding every literal instead of inlining it.
+fn sqlite_filter(expr: &Expr, params: &mut Vec<JsValue>) -> Result<String> {
+    match expr {
+        Expr::Column(column) => Ok(column.physical()),
+        Expr::Literal(literal) => Ok(bind(literal, params)),
+        Expr::Binary(left, op, right) => Ok(format!(
+            "({} {} {})",
+            sqlite_filter(left, params)?,
+            op.sql(),
+            sqlite_filter(right, params)?
+        )),
+        Expr::InList(expr, values) => {
+            if values.is_empty() {
+                return Err(local_error("IN list must not be empty"));
+            }
+            let expr = sqlite_filter(expr, params)?;
+            let values = values
+                .iter()
+                .map(|value| bind(value, params))
+                .collect::<Vec<_>>();
+            Ok(format!("{expr} IN ({})", values.join(", ")))
+        }
+        Expr::Between(expr, low, high) => {
+            let expr = sqlite_filter(expr, params)?;
+            Ok(format!(
+                "{expr} BETWEEN {} AND {}",
+                bind(low, params),
+                bind(high, params)
+            ))
+        }
+        Expr::Call(name, _) => Err(local_error(&format!(
+            "{name}() is not supported in filters"
+        ))),
+        Expr::Quantile(..) => Err(local_error("quantiles are not supported in filters")),
+    }
+}
+
+fn bind(literal: &Literal, params: &mut Vec<JsValue>) -> String {
+    params.push(match literal {
+        Literal::Str(value) => JsValue::from_str(value),
+        Literal::Int(value) => JsValue::from_f64(*value as f64),
+        Literal::Float(value) => JsValue::from_f64(*value),
+    });
+    format!("?{}", params.len())
+}
+
+/// Applies the select list, `GROUP BY`, `ORDER BY` and `LIMIT` to rows that already passed
+/// the filters. Like AE, a query with aggregates and no grouping returns exactly one row.
+fn evaluate(query: &AeQuery, rows: Vec<Row>) -> Result<Vec<Row>> {
+    let aggregated =
+        !query.group_by.is_empty() || query.select.iter().any(|(expr, _)| is_aggregate(expr));
+    let groups = if !aggregated {
+        rows.into_iter().map(|row| vec![row]).collect::<Vec<_>>()
+    } else if query.group_by.is_empty() {
+        vec![rows]
+    } else {
+        let keys = query
+            .group_by
+            .iter()
+            .map(|alias| selected(query, alias))
+            .collect::<Result<Vec<_>>>()?;
+        let mut groups: Vec<Vec<Row>> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        for row in rows {
+            let key = keys
+                .iter()
+                .map(|expr| eval(expr, std::slice::from_ref(&row)))
+                .collect::<Result<Vec<_>>>()?;
+            let key = Value::Array(key).to_string();
+            match positions.get(&key) {
+                Some(&position) => groups[position].push(row),
+                None => {
+                    positions.insert(key, groups.len());
+                    groups.push(vec![row]);
+                }
+            }
+        }
+        groups
+    };
+
+    let mut output = groups
+        .iter()
+        .map(|group| {
+            query
+                .select
+                .iter()
+                .map(|(expr, alias)| Ok((alias.to_string(), eval(expr, group)?)))
+                .collect::<Result<Row>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for (alias, _) in &query.order_by {
+        selected(query, alias)?;
+    }
+    output.sort_by(|a, b| {
+        query
+            .order_by
+            .iter()
+            .map(|(alias, order)| {
+                let ordering = compare(
+                    a.get(*alias).unwrap_or(&Value::Null),
+                    b.get(*alias).unwrap_or(&Value::Null),
+                );
+                match order {
+                    Order::Asc => ordering,
+                    Order::Desc => ordering.reverse(),
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+    if let Some(limit) = query.limit {
+        output.truncate(limit.max(1));
+    }
+
+    Ok(output)
+}
+
+fn selected<'a>(query: &'a AeQuery, alias: &str) -> Result<&'a Expr> {
+    query
+        .select
+        .iter()
+        .find(|(_, selected)| *selected == alias)
+        .map(|(expr, _)| expr)
+        .ok_or_else(|| local_error(&format!("{alias} is not in the select list")))
+}
+
+fn is_aggregate(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call("SUM" | "max", _) | Expr::Quantile(..) => true,
+        Expr::Call(_, args) => args.iter().any(is_aggregate),
+        Expr::Binary(left, _, right) => is_aggregate(left) || is_aggregate(right),
+        Expr::InList(expr, _) | Expr::Between(expr, _, _) => is_aggregate(expr),
+        Expr::Column(_) | Expr::Literal(_) => false,
+    }
+}
+
+/// Evaluates `expr` over a group. Aggregates fold every row; anything else reads the first
+/// row, which is only meaningful for grouped or constant expressions (as in SQL).
+fn eval(expr: &Expr, rows: &[Row]) -> Result<Value> {
+    match expr {
+        Expr::Column(column) => Ok(rows
+            .first()
+            .and_then(|row| row.get(&column.physical()))
+            .cloned()
+            .unwrap_or(Value::Null)),
+        Expr::Literal(Literal::Str(value)) => Ok(Value::from(value.as_str())),
+        Expr::Literal(Literal::Int(value)) => Ok(Value::from(*value)),
+        Expr::Literal(Literal::Float(value)) => Ok(Value::from(*value)),
+        Expr::Binary(left, op, right) => {
+            let (left, right) = (eval(left, rows)?, eval(right, rows)?);
+            Ok(match op {
+                BinaryOp::Eq => flag(loose_eq(&left, &right)),
+                BinaryOp::NotEq => flag(!loose_eq(&left, &right)),
+                BinaryOp::Gt => flag(compare(&left, &right) == Ordering::Greater),
+                BinaryOp::And => flag(truthy(&left) && truthy(&right)),
+                BinaryOp::Div => match (number(&left), number(&right)) {
+                    (Some(left), Some(right)) => Value::from(left / right),
+                    _ => Value::Null,
+                },
+            })
+        }
+        Expr::InList(expr, values) => {
+            let value = eval(expr, rows)?;
+            let found = values
+                .iter()
+                .map(|literal| eval(&Expr::Literal(literal.clone()), rows))
+                .collect::<Result<Vec<_>>>()?
+                .iter()
+                .any(|candidate| loose_eq(&value, candidate));
+            Ok(flag(found))
+        }
+        Expr::Between(expr, low, high) => {
+            let value = eval(expr, rows)?;
+            let low = eval(&Expr::Literal(low.clone()), rows)?;
+            let high = eval(&Expr::Literal(high.clone()), rows)?;
+            Ok(flag(
+                compare(&value, &low).is_ge() && compare(&value, &high).is_le(),
+            ))
+        }
+        Expr::Call("SUM", args) => {
+            let arg = single_arg("SUM", args)?;
+            let mut total = 0.0;
+            for row in rows {
+                total += number(&eval(arg, std::slice::from_ref(row))?).unwrap_or_default();
+            }
+            Ok(Value::from(total))
+        }
+        Expr::Call("max", args) => {
+            let arg = single_arg("max", args)?;
+            let mut max: Option<f64> = None;
+            for row in rows {
+                if let Some(value) = number(&eval(arg, std::slice::from_ref(row))?) {
+                    max = Some(max.map_or(value, |max| max.max(value)));
+                }
+            }
+            Ok(max.map(Value::from).unwrap_or(Value::Null))
+        }
+        Expr::Call("if", args) => match args.as_slice() {
+            [condition, then, otherwise] => {
+                if truthy(&eval(condition, rows)?) {
+                    eval(then, rows)
+                } else {
+                    eval(otherwise, rows)
+                }
+            }
+            _ => Err(local_error("if() takes three arguments")),
+        },
+        Expr::Call("intDiv", args) => match args.as_slice() {
+            [value, divisor] => {
+                match (number(&eval(value, rows)?), number(&eval(divisor, rows)?)) {
+                    (Some(value), Some(divisor)) if divisor != 0.0 => {
+                        Ok(Value::from((value / divisor).floor() as i64))
+                    }
+                    _ => Ok(Value::Null),
+                }
+            }
+            _ => Err(local_error("intDiv() takes two arguments")),
+        },
+        Expr::Call("toUInt32", args) => {
+            let value = number(&eval(single_arg("toUInt32", args)?, rows)?);
+            Ok(value
+                .map(|value| Value::from(value.trunc().clamp(0.0, u32::MAX as f64) as u32))
+                .unwrap_or(Value::Null))
+        }
+        Expr::Call(name, _) => Err(local_error(&format!("{name}() is not supported"))),
+        Expr::Quantile(quantile, expr, weight) => {
+            let mut points = Vec::with_capacity(rows.len());
+            for row in rows {
+                let row = std::slice::from_ref(row);
+                let value = number(&eval(expr, row)?);
+                let weight = number(&eval(weight, row)?).unwrap_or_default();
+                if let Some(value) = value.filter(|_| weight > 0.0) {
+                    points.push((value, weight));
+                }
+            }
+            Ok(weighted_quantile(*quantile, points)
+                .map(Value::from)
+                .unwrap_or(Value::Null))
+        }
+    }
+}
+
+/// Same rule as AE's `quantileExactWeighted`: the smallest value whose cumulative weight
+/// reaches `quantile` of the total.
+fn weighted_quantile(quantile: f64, mut points: Vec<(f64, f64)>) -> Option<f64> {
+    let total = points.iter().map(|(_, weight)| weight).sum::<f64>();
+    if total <= 0.0 {
+        return None;
+    }
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let threshold = quantile * total;
+    let mut cumulative = 0.0;
+    for (value, weight) in &points {
+        cumulative += weight;
+        if cumulative >= threshold {
+            return Some(*value);
+        }
+    }
+    points.last().map(|(value, _)| *value)
+}
+
+fn single_arg<'a>(name: &str, args: &'a [Expr]) -> Result<&'a Expr> {
+    match args {
+        [arg] => Ok(arg),
+        _ => Err(local_error(&format!("{name}() takes one argument"))),
+    }
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.parse().ok(),
+        Value::Bool(flag) => Some(if *flag { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+fn flag(value: bool) -> Value {
+    Value::from(u8::from(value))
+}
+
+fn truthy(value: &Value) -> bool {
+    number(value).is_some_and(|value| value != 0.0)
+}
+
+fn loose_eq(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::String(left), Value::String(right)) => left == right,
+        _ => match (number(left), number(right)) {
+            (Some(left), Some(right)) => left == right,
+            _ => false,
+        },
+    }
+}
+
+/// Total order used for `ORDER BY`: nulls first, then numbers, then strings.
+fn compare(left: &Value, right: &Value) -> Ordering {
+    match (left, right) {
+        (Value::String(left), Value::String(right)) => left.cmp(right),
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Less,
+        (_, Value::Null) => Ordering::Greater,
+        _ => match (number(left), number(right)) {
+            (Some(left), Some(right)) => left.total_cmp(&right),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+    }
+}
+
+fn local_error(reason: &str) -> worker::Error {
+    worker::Error::RustError(format!("analytics.local: {reason}"))
+}
@@ -1,3 +1,8 @@
+pub mod backend;
 pub mod client;
 pub mod extractor;
+pub mod local;
 pub mod monitor_health;
+pub mod query;
+pub mod schema;
+pub mod stats;
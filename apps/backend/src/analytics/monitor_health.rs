@@ -3,9 +3,12 @@ use std::{f64, str::FromStr};
 use serde::{Deserialize, Serialize};
 use worker::Result;
 
+use crate::archive::types::evidence_path;
 use crate::monitors::types::MonitorStatus;
 
-use super::client::AeQueryClient;
+use super::backend::HeartbeatStore;
+use super::query::{AeQuery, Expr, Order};
+use super::schema::HeartbeatColumn;
 
 #[derive(Debug, Clone, Copy)]
 pub struct TimeWindow {
@@ -35,11 +38,13 @@ pub struct HeartbeatSample {
     pub code: Option<u16>,
     pub sample_rate: f64,
     pub dispatch_id: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct SqlResponse<T> {
-    data: Vec<T>,
+    pub relay_id: Option<String>,
+    /// Where to fetch what the checker saw, for failed HTTP checks that captured evidence.
+    pub evidence_url: Option<String>,
+    /// Attempts the check took, retries included.
+    pub attempts: u32,
+    /// Error of each failed attempt, in order.
+    pub attempt_errors: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -53,53 +58,105 @@ struct HeartbeatRow {
     code: Option<f64>,
     sample_rate: Option<f64>,
     dispatch_id: Option<String>,
+    relay_id: Option<String>,
+    evidence: Option<String>,
+    attempts: Option<f64>,
+    attempt_errors: Option<String>,
 }
 
 #[tracing::instrument(
     name = "analytics.monitor_health.recent_heartbeats",
-    skip(client),
+    skip(store),
     fields(monitor_id = %monitor_id, org_id = %org_id, since_ms = %window.since_ms, until_ms = %window.until_ms, limit = %limit)
 )]
 pub async fn recent_heartbeats(
-    client: &AeQueryClient,
+    store: &impl HeartbeatStore,
+    monitor_id: &str,
+    org_id: &str,
+    window: &TimeWindow,
+    limit: usize,
+) -> Result<Vec<HeartbeatSample>> {
+    let query = heartbeat_rows_query(monitor_id, org_id, window)
+        .order_by(HeartbeatColumn::TimestampMs.alias(), Order::Desc)
+        .limit(limit);
+
+    let rows: Vec<HeartbeatRow> = store.fetch(&query).await?;
+    Ok(rows.into_iter().map(HeartbeatSample::from).collect())
+}
+
+/// Every heartbeat for one monitor in `window`, oldest first, capped at `limit` rows.
+#[tracing::instrument(
+    name = "analytics.monitor_health.heartbeats_in_window",
+    skip(store),
+    fields(monitor_id = %monitor_id, org_id = %org_id, since_ms = %window.since_ms, until_ms = %window.until_ms, limit = %limit)
+)]
+pub async fn heartbeats_in_window(
+    store: &impl HeartbeatStore,
     monitor_id: &str,
     org_id: &str,
     window: &TimeWindow,
     limit: usize,
 ) -> Result<Vec<HeartbeatSample>> {
-    let dataset = client.dataset();
-    let sql = format!(
-        r#"SELECT
-            index1 as monitor_id,
-            blob1 as org_id,
-            blob2 as dispatch_id,
-            double1 as timestamp_ms,
-            blob3 as status,
-            blob4 as region,
-            blob5 as colo,
-            blob6 as error,
-            double2 as latency_ms,
-            double3 as code,
-            double4 as sample_rate
-        FROM {dataset}
-        WHERE monitor_id = '{monitor}'
-          AND org_id = '{org}'
-          AND timestamp_ms BETWEEN {since} AND {until}
-        ORDER BY timestamp_ms DESC
-        LIMIT {limit}
-        FORMAT JSON"#,
-        dataset = dataset,
-        monitor = escape_literal(monitor_id),
-        org = escape_literal(org_id),
-        since = window.since_ms,
-        until = window.until_ms,
-        limit = limit.max(1),
-    );
-
-    let response: SqlResponse<HeartbeatRow> = client.query(&sql).await?;
-    let mut samples = Vec::with_capacity(response.data.len());
-
-    for row in response.data {
+    let query = heartbeat_rows_query(monitor_id, org_id, window)
+        .order_by(HeartbeatColumn::TimestampMs.alias(), Order::Asc)
+        .limit(limit);
+
+    let rows: Vec<HeartbeatRow> = store.fetch(&query).await?;
+    Ok(rows.into_iter().map(HeartbeatSample::from).collect())
+}
+
+#[derive(Deserialize)]
+struct MonitorIdRow {
+    monitor_id: String,
+}
+
+/// Monitors with at least one heartbeat in `window`, including ones since deleted from D1.
+#[tracing::instrument(
+    name = "analytics.monitor_health.monitors_with_heartbeats",
+    skip(store),
+    fields(org_id = %org_id, since_ms = %window.since_ms, until_ms = %window.until_ms)
+)]
+pub async fn monitors_with_heartbeats(
+    store: &impl HeartbeatStore,
+    org_id: &str,
+    window: &TimeWindow,
+) -> Result<Vec<String>> {
+    let query = AeQuery::new()
+        .column(HeartbeatColumn::MonitorId)
+        .select(Expr::sum(Expr::lit(1)), "rows")
+        .filter(Expr::col(HeartbeatColumn::OrgId).eq(Expr::lit(org_id)))
+        .filter(Expr::col(HeartbeatColumn::TimestampMs).between(window.since_ms, window.until_ms))
+        .group_by(HeartbeatColumn::MonitorId.alias())
+        .order_by(HeartbeatColumn::MonitorId.alias(), Order::Asc);
+
+    let rows: Vec<MonitorIdRow> = store.fetch(&query).await?;
+    Ok(rows.into_iter().map(|row| row.monitor_id).collect())
+}
+
+fn heartbeat_rows_query(monitor_id: &str, org_id: &str, window: &TimeWindow) -> AeQuery {
+    AeQuery::new()
+        .column(HeartbeatColumn::MonitorId)
+        .column(HeartbeatColumn::OrgId)
+        .column(HeartbeatColumn::DispatchId)
+        .column(HeartbeatColumn::TimestampMs)
+        .column(HeartbeatColumn::Status)
+        .column(HeartbeatColumn::Region)
+        .column(HeartbeatColumn::Colo)
+        .column(HeartbeatColumn::Error)
+        .column(HeartbeatColumn::RelayId)
+        .column(HeartbeatColumn::Evidence)
+        .column(HeartbeatColumn::AttemptErrors)
+        .column(HeartbeatColumn::LatencyMs)
+        .column(HeartbeatColumn::Code)
+        .column(HeartbeatColumn::SampleRate)
+        .column(HeartbeatColumn::Attempts)
+        .filter(Expr::col(HeartbeatColumn::MonitorId).eq(Expr::lit(monitor_id)))
+        .filter(Expr::col(HeartbeatColumn::OrgId).eq(Expr::lit(org_id)))
+        .filter(Expr::col(HeartbeatColumn::TimestampMs).between(window.since_ms, window.until_ms))
+}
+
+impl From<HeartbeatRow> for HeartbeatSample {
+    fn from(row: HeartbeatRow) -> Self {
         let status = MonitorStatus::from_str(&row.status).unwrap_or(MonitorStatus::Down);
         let code = row.code.and_then(|value| {
             let rounded = value.round() as i64;
@@ -110,8 +167,19 @@ pub async fn recent_heartbeats(
             }
         });
         let sample_rate = row.sample_rate.unwrap_or(1.0).max(f64::MIN_POSITIVE);
+        let dispatch_id = normalize_string(row.dispatch_id);
+        let evidence_url = normalize_string(row.evidence)
+            .and(dispatch_id.as_deref())
+            .map(evidence_path);
+        // Rows written before retries existed have no attempt count.
+        let attempts = row
+            .attempts
+            .map_or(1, |value| value.round().max(1.0) as u32);
+        let attempt_errors = normalize_string(row.attempt_errors)
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
 
-        samples.push(HeartbeatSample {
+        HeartbeatSample {
             timestamp_ms: row.timestamp_ms.round() as i64,
             status,
             latency_ms: row.latency_ms.unwrap_or_default().round() as i64,
@@ -120,18 +188,274 @@ pub async fn recent_heartbeats(
             error: normalize_string(row.error),
             code,
             sample_rate,
-            dispatch_id: normalize_string(row.dispatch_id),
+            dispatch_id,
+            relay_id: normalize_string(row.relay_id),
+            evidence_url,
+            attempts,
+            attempt_errors,
+        }
+    }
+}
+
+pub const DAY_MS: i64 = 86_400_000;
+
+/// Sample-weighted check counts for one monitor over one UTC day.
+#[derive(Debug, Clone)]
+pub struct DailyUptime {
+    pub monitor_id: String,
+    pub day_start_ms: i64,
+    pub checks: f64,
+    pub up: f64,
+}
+
+#[derive(Deserialize)]
+struct DailyUptimeRow {
+    monitor_id: String,
+    #[serde(deserialize_with = "number_or_string")]
+    day: f64,
+    checks: Option<f64>,
+    up: Option<f64>,
+}
+
+/// Each heartbeat counts `1 / sample_rate` times so sampled orgs still report true ratios.
+#[tracing::instrument(
+    name = "analytics.monitor_health.daily_uptime",
+    skip(store, monitor_ids),
+    fields(org_id = %org_id, monitors = %monitor_ids.len(), since_ms = %window.since_ms, until_ms = %window.until_ms)
+)]
+pub async fn daily_uptime(
+    store: &impl HeartbeatStore,
+    org_id: &str,
+    monitor_ids: &[String],
+    window: &TimeWindow,
+) -> Result<Vec<DailyUptime>> {
+    if monitor_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let query = AeQuery::new()
+        .column(HeartbeatColumn::MonitorId)
+        .select(
+            Expr::int_div(Expr::col(HeartbeatColumn::TimestampMs), DAY_MS),
+            "day",
+        )
+        .select(Expr::sum(Expr::weight()), "checks")
+        .select(
+            Expr::sum(Expr::when(
+                Expr::status_is(MonitorStatus::Up),
+                Expr::weight(),
+                Expr::lit(0),
+            )),
+            "up",
+        )
+        .filter(
+            Expr::col(HeartbeatColumn::MonitorId).in_list(monitor_ids.iter().map(String::as_str)),
+        )
+        .filter(Expr::col(HeartbeatColumn::OrgId).eq(Expr::lit(org_id)))
+        .filter(Expr::col(HeartbeatColumn::TimestampMs).between(window.since_ms, window.until_ms))
+        .filter(Expr::col(HeartbeatColumn::SampleRate).gt(Expr::lit(0)))
+        .group_by(HeartbeatColumn::MonitorId.alias())
+        .group_by("day")
+        .order_by(HeartbeatColumn::MonitorId.alias(), Order::Asc)
+        .order_by("day", Order::Asc);
+
+    let rows: Vec<DailyUptimeRow> = store.fetch(&query).await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| DailyUptime {
+            monitor_id: row.monitor_id,
+            day_start_ms: row.day.round() as i64 * DAY_MS,
+            checks: row.checks.unwrap_or_default(),
+            up: row.up.unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Fixed chart bucket widths accepted by the series endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BucketWidth {
+    #[serde(rename = "1m")]
+    Minute,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "1h")]
+    Hour,
+    #[serde(rename = "1d")]
+    Day,
+}
+
+impl BucketWidth {
+    pub fn ms(&self) -> i64 {
+        match self {
+            BucketWidth::Minute => 60_000,
+            BucketWidth::FiveMinutes => 300_000,
+            BucketWidth::Hour => 3_600_000,
+            BucketWidth::Day => DAY_MS,
+        }
+    }
+}
+
+/// One chart bucket. Counts are sample-weighted like the stats queries; latency figures
+/// ignore `down` rows and are `None` when the bucket has no responses.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartbeatBucket {
+    pub start_ms: i64,
+    pub checks: f64,
+    pub failures: f64,
+    pub degraded: f64,
+    pub avg_latency_ms: Option<f64>,
+    pub max_latency_ms: Option<f64>,
+    pub p95_latency_ms: Option<f64>,
+}
+
+impl HeartbeatBucket {
+    fn empty(start_ms: i64) -> Self {
+        HeartbeatBucket {
+            start_ms,
+            checks: 0.0,
+            failures: 0.0,
+            degraded: 0.0,
+            avg_latency_ms: None,
+            max_latency_ms: None,
+            p95_latency_ms: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct HeartbeatBucketRow {
+    #[serde(deserialize_with = "number_or_string")]
+    bucket: f64,
+    checks: Option<f64>,
+    failures: Option<f64>,
+    degraded: Option<f64>,
+    responded: Option<f64>,
+    latency_sum: Option<f64>,
+    max_latency: Option<f64>,
+    p95_latency: Option<f64>,
+}
+
+/// Buckets keyed on `double1`, zero-filled so every bucket in the window is present.
+#[tracing::instrument(
+    name = "analytics.monitor_health.heartbeat_series",
+    skip(store),
+    fields(monitor_id = %monitor_id, org_id = %org_id, since_ms = %window.since_ms, until_ms = %window.until_ms, bucket_ms = %bucket.ms())
+)]
+pub async fn heartbeat_series(
+    store: &impl HeartbeatStore,
+    monitor_id: &str,
+    org_id: &str,
+    window: &TimeWindow,
+    bucket: BucketWidth,
+) -> Result<Vec<HeartbeatBucket>> {
+    let bucket_ms = bucket.ms();
+    let down = || Expr::status_is(MonitorStatus::Down);
+    let latency = || Expr::col(HeartbeatColumn::LatencyMs);
+    let query = AeQuery::new()
+        .select(
+            Expr::int_div(Expr::col(HeartbeatColumn::TimestampMs), bucket_ms),
+            "bucket",
+        )
+        .select(Expr::sum(Expr::weight()), "checks")
+        .select(
+            Expr::sum(Expr::when(down(), Expr::weight(), Expr::lit(0))),
+            "failures",
+        )
+        .select(
+            Expr::sum(Expr::when(
+                Expr::status_is(MonitorStatus::Degraded),
+                Expr::weight(),
+                Expr::lit(0),
+            )),
+            "degraded",
+        )
+        .select(
+            Expr::sum(Expr::when(down(), Expr::lit(0), Expr::weight())),
+            "responded",
+        )
+        .select(
+            Expr::sum(Expr::when(
+                down(),
+                Expr::lit(0),
+                latency().divided_by(Expr::col(HeartbeatColumn::SampleRate)),
+            )),
+            "latency_sum",
+        )
+        .select(
+            Expr::max(Expr::when(down(), Expr::lit(0), latency())),
+            "max_latency",
+        )
+        .select(
+            Expr::quantile_weighted(0.95, latency(), Expr::response_weight()),
+            "p95_latency",
+        )
+        .filter(Expr::col(HeartbeatColumn::MonitorId).eq(Expr::lit(monitor_id)))
+        .filter(Expr::col(HeartbeatColumn::OrgId).eq(Expr::lit(org_id)))
+        .filter(Expr::col(HeartbeatColumn::TimestampMs).between(window.since_ms, window.until_ms))
+        .filter(Expr::col(HeartbeatColumn::SampleRate).gt(Expr::lit(0)))
+        .group_by("bucket")
+        .order_by("bucket", Order::Asc);
+
+    let rows: Vec<HeartbeatBucketRow> = store.fetch(&query).await?;
+    let mut rows = rows.into_iter().peekable();
+    let first = window.since_ms / bucket_ms;
+    let last = window.until_ms / bucket_ms;
+    let mut buckets = Vec::with_capacity((last - first + 1).max(0) as usize);
+
+    for index in first..=last {
+        let start_ms = index * bucket_ms;
+        while rows
+            .peek()
+            .is_some_and(|row| (row.bucket.round() as i64) < index)
+        {
+            rows.next();
+        }
+        let row = match rows.next_if(|row| row.bucket.round() as i64 == index) {
+            Some(row) => row,
+            None => {
+                buckets.push(HeartbeatBucket::empty(start_ms));
+                continue;
+            }
+        };
+
+        let responded = row.responded.unwrap_or_default();
+        let has_latency = responded > 0.0;
+        buckets.push(HeartbeatBucket {
+            start_ms,
+            checks: row.checks.unwrap_or_default(),
+            failures: row.failures.unwrap_or_default(),
+            degraded: row.degraded.unwrap_or_default(),
+            avg_latency_ms: has_latency.then(|| row.latency_sum.unwrap_or_default() / responded),
+            max_latency_ms: row.max_latency.filter(|_| has_latency),
+            p95_latency_ms: row
+                .p95_latency
+                .filter(|value| has_latency && value.is_finite()),
         });
     }
 
-    Ok(samples)
+    Ok(buckets)
 }
 
-fn escape_literal(value: &str) -> String {
-    value.replace('\'', "''")
+/// AE quotes 64-bit integer results (e.g. `intDiv`) in JSON output.
+pub(crate) fn number_or_string<'de, D>(deserializer: D) -> std::result::Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Number(f64),
+        Text(String),
+    }
+
+    match Raw::deserialize(deserializer)? {
+        Raw::Number(value) => Ok(value),
+        Raw::Text(value) => value.parse().map_err(serde::de::Error::custom),
+    }
 }
 
-fn normalize_string(value: Option<String>) -> Option<String> {
+pub(crate) fn normalize_string(value: Option<String>) -> Option<String> {
     value.and_then(|s| {
         let trimmed = s.trim();
         if trimmed.is_empty() {
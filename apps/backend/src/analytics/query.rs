@@ -0,0 +1,353 @@
+//! Small typed builder for Analytics Engine SQL. Columns come from `schema`, aliases are
+//! `&'static str`, and user-supplied values only reach the SQL text through `Literal`, which
+//! is validated and escaped here and nowhere else.
+
+use worker::Result;
+
+use crate::monitors::types::MonitorStatus;
+
+use super::schema::HeartbeatColumn;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Str(String),
+    Int(i64),
+    Float(f64),
+}
+
+impl Literal {
+    fn render(&self) -> Result<String> {
+        match self {
+            Literal::Str(value) => {
+                if value.chars().any(char::is_control) {
+                    return Err(query_error("string literal contains control characters"));
+                }
+                Ok(format!(
+                    "'{}'",
+                    value.replace('\\', "\\\\").replace('\'', "\\'")
+                ))
+            }
+            Literal::Int(value) => Ok(value.to_string()),
+            Literal::Float(value) if value.is_finite() => Ok(format!("{value:?}")),
+            Literal::Float(value) => Err(query_error(&format!(
+                "numeric literal must be finite, got {value}"
+            ))),
+        }
+    }
+}
+
+impl From<&str> for Literal {
+    fn from(value: &str) -> Self {
+        Literal::Str(value.to_string())
+    }
+}
+
+impl From<String> for Literal {
+    fn from(value: String) -> Self {
+        Literal::Str(value)
+    }
+}
+
+impl From<i32> for Literal {
+    fn from(value: i32) -> Self {
+        Literal::Int(value.into())
+    }
+}
+
+impl From<i64> for Literal {
+    fn from(value: i64) -> Self {
+        Literal::Int(value)
+    }
+}
+
+impl From<f64> for Literal {
+    fn from(value: f64) -> Self {
+        Literal::Float(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Eq,
+    NotEq,
+    Gt,
+    Div,
+    And,
+}
+
+impl BinaryOp {
+    pub(super) fn sql(&self) -> &'static str {
+        match self {
+            BinaryOp::Eq => "=",
+            BinaryOp::NotEq => "!=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Div => "/",
+            BinaryOp::And => "AND",
+        }
+    }
+}
+
+/// An expression over heartbeat columns. Rendered lazily so literal validation errors surface
+/// from `AeQuery::build` rather than at every call site.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Column(HeartbeatColumn),
+    Literal(Literal),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    Call(&'static str, Vec<Expr>),
+    Quantile(f64, Box<Expr>, Box<Expr>),
+    InList(Box<Expr>, Vec<Literal>),
+    Between(Box<Expr>, Literal, Literal),
+}
+
+impl Expr {
+    pub fn col(column: HeartbeatColumn) -> Self {
+        Expr::Column(column)
+    }
+
+    pub fn lit(value: impl Into<Literal>) -> Self {
+        Expr::Literal(value.into())
+    }
+
+    /// How many real checks one sampled row stands for: `1 / sample_rate`.
+    pub fn weight() -> Self {
+        Expr::lit(1).divided_by(Expr::col(HeartbeatColumn::SampleRate))
+    }
+
+    /// `blob3 = '<status>'`, using the same spelling the writer stores.
+    pub fn status_is(status: MonitorStatus) -> Self {
+        Expr::col(HeartbeatColumn::Status).eq(Expr::lit(status.to_string()))
+    }
+
+    /// Integer quantile weight that drops `down` rows, which carry no meaningful latency.
+    pub fn response_weight() -> Self {
+        Expr::when(
+            Expr::status_is(MonitorStatus::Down),
+            Expr::lit(0),
+            Expr::to_uint32(Expr::weight()),
+        )
+    }
+
+    pub fn eq(self, other: Expr) -> Self {
+        self.binary(BinaryOp::Eq, other)
+    }
+
+    pub fn not_eq(self, other: Expr) -> Self {
+        self.binary(BinaryOp::NotEq, other)
+    }
+
+    pub fn gt(self, other: Expr) -> Self {
+        self.binary(BinaryOp::Gt, other)
+    }
+
+    pub fn divided_by(self, other: Expr) -> Self {
+        self.binary(BinaryOp::Div, other)
+    }
+
+    pub fn and(self, other: Expr) -> Self {
+        self.binary(BinaryOp::And, other)
+    }
+
+    pub fn in_list<L: Into<Literal>>(self, values: impl IntoIterator<Item = L>) -> Self {
+        Expr::InList(Box::new(self), values.into_iter().map(Into::into).collect())
+    }
+
+    pub fn between(self, low: impl Into<Literal>, high: impl Into<Literal>) -> Self {
+        Expr::Between(Box::new(self), low.into(), high.into())
+    }
+
+    pub fn sum(expr: Expr) -> Self {
+        Expr::Call("SUM", vec![expr])
+    }
+
+    pub fn max(expr: Expr) -> Self {
+        Expr::Call("max", vec![expr])
+    }
+
+    pub fn when(condition: Expr, then: Expr, otherwise: Expr) -> Self {
+        Expr::Call("if", vec![condition, then, otherwise])
+    }
+
+    pub fn int_div(expr: Expr, divisor: i64) -> Self {
+        Expr::Call("intDiv", vec![expr, Expr::lit(divisor)])
+    }
+
+    pub fn to_uint32(expr: Expr) -> Self {
+        Expr::Call("toUInt32", vec![expr])
+    }
+
+    /// `quantileExactWeighted`; AE requires integer weights.
+    pub fn quantile_weighted(quantile: f64, expr: Expr, weight: Expr) -> Self {
+        Expr::Quantile(quantile, Box::new(expr), Box::new(weight))
+    }
+
+    fn binary(self, op: BinaryOp, other: Expr) -> Self {
+        Expr::Binary(Box::new(self), op, Box::new(other))
+    }
+
+    fn render(&self) -> Result<String> {
+        match self {
+            Expr::Column(column) => Ok(column.physical()),
+            Expr::Literal(literal) => literal.render(),
+            Expr::Binary(left, op, right) => Ok(format!(
+                "({} {} {})",
+                left.render()?,
+                op.sql(),
+                right.render()?
+            )),
+            Expr::Call(name, args) => {
+                let args = args.iter().map(Expr::render).collect::<Result<Vec<_>>>()?;
+                Ok(format!("{name}({})", args.join(", ")))
+            }
+            Expr::Quantile(quantile, expr, weight) => {
+                if !(0.0..=1.0).contains(quantile) {
+                    return Err(query_error(&format!(
+                        "quantile must be between 0 and 1, got {quantile}"
+                    )));
+                }
+                Ok(format!(
+                    "quantileExactWeighted({}, {}, {})",
+                    Literal::Float(*quantile).render()?,
+                    expr.render()?,
+                    weight.render()?
+                ))
+            }
+            Expr::InList(expr, values) => {
+                if values.is_empty() {
+                    return Err(query_error("IN list must not be empty"));
+                }
+                let values = values
+                    .iter()
+                    .map(Literal::render)
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(format!("{} IN ({})", expr.render()?, values.join(", ")))
+            }
+            Expr::Between(expr, low, high) => Ok(format!(
+                "{} BETWEEN {} AND {}",
+                expr.render()?,
+                low.render()?,
+                high.render()?
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+/// Output formats `AeQueryClient` knows how to parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Json,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AeQuery {
+    pub(super) select: Vec<(Expr, &'static str)>,
+    pub(super) filters: Vec<Expr>,
+    pub(super) group_by: Vec<&'static str>,
+    pub(super) order_by: Vec<(&'static str, Order)>,
+    pub(super) limit: Option<usize>,
+    format: Format,
+}
+
+impl AeQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selects a raw column under its schema alias.
+    pub fn column(self, column: HeartbeatColumn) -> Self {
+        self.select(Expr::col(column), column.alias())
+    }
+
+    pub fn select(mut self, expr: Expr, alias: &'static str) -> Self {
+        self.select.push((expr, alias));
+        self
+    }
+
+    /// Filters are joined with `AND`.
+    pub fn filter(mut self, condition: Expr) -> Self {
+        self.filters.push(condition);
+        self
+    }
+
+    pub fn group_by(mut self, alias: &'static str) -> Self {
+        self.group_by.push(alias);
+        self
+    }
+
+    pub fn order_by(mut self, alias: &'static str, order: Order) -> Self {
+        self.order_by.push((alias, order));
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn build(&self, dataset: &str) -> Result<String> {
+        if dataset.is_empty()
+            || !dataset
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(query_error(&format!("invalid dataset name: {dataset}")));
+        }
+        if self.select.is_empty() {
+            return Err(query_error("query selects no columns"));
+        }
+
+        let select = self
+            .select
+            .iter()
+            .map(|(expr, alias)| Ok(format!("{} as {alias}", expr.render()?)))
+            .collect::<Result<Vec<_>>>()?;
+        let mut sql = format!("SELECT {} FROM {dataset}", select.join(", "));
+
+        if !self.filters.is_empty() {
+            let filters = self
+                .filters
+                .iter()
+                .map(Expr::render)
+                .collect::<Result<Vec<_>>>()?;
+            sql.push_str(&format!(" WHERE {}", filters.join(" AND ")));
+        }
+        if !self.group_by.is_empty() {
+            sql.push_str(&format!(" GROUP BY {}", self.group_by.join(", ")));
+        }
+        if !self.order_by.is_empty() {
+            let order = self
+                .order_by
+                .iter()
+                .map(|(alias, order)| match order {
+                    Order::Asc => format!("{alias} ASC"),
+                    Order::Desc => format!("{alias} DESC"),
+                })
+                .collect::<Vec<_>>();
+            sql.push_str(&format!(" ORDER BY {}", order.join(", ")));
+        }
+        if let Some(limit) = self.limit {
+            sql.push_str(&format!(" LIMIT {}", limit.max(1)));
+        }
+        match self.format {
+            Format::Json => sql.push_str(" FORMAT JSON"),
+        }
+
+        Ok(sql)
+    }
+}
+
+fn query_error(reason: &str) -> worker::Error {
+    worker::Error::RustError(format!("analytics.query: {reason}"))
+}
@@ -0,0 +1,139 @@
+//! Column layout of the heartbeat dataset. AE columns are positional (`blob1`, `double2`, …),
+//! so the writer and every query derive positions from `BLOBS` / `DOUBLES` here instead of
+//! hard-coding them. New fields must be appended: reordering breaks existing rows.
+
+use worker::{AnalyticsEngineDataPoint, AnalyticsEngineDataPointBuilder};
+
+use crate::monitors::types::HeartbeatResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeartbeatColumn {
+    MonitorId,
+    OrgId,
+    DispatchId,
+    Status,
+    Region,
+    Colo,
+    Error,
+    RelayId,
+    Evidence,
+    AttemptErrors,
+    TimestampMs,
+    LatencyMs,
+    Code,
+    SampleRate,
+    Attempts,
+}
+
+/// Blob columns in write order: `BLOBS[0]` is `blob1`.
+pub const BLOBS: [HeartbeatColumn; 9] = [
+    HeartbeatColumn::OrgId,
+    HeartbeatColumn::DispatchId,
+    HeartbeatColumn::Status,
+    HeartbeatColumn::Region,
+    HeartbeatColumn::Colo,
+    HeartbeatColumn::Error,
+    HeartbeatColumn::RelayId,
+    HeartbeatColumn::Evidence,
+    HeartbeatColumn::AttemptErrors,
+];
+
+/// Double columns in write order: `DOUBLES[0]` is `double1`.
+pub const DOUBLES: [HeartbeatColumn; 5] = [
+    HeartbeatColumn::TimestampMs,
+    HeartbeatColumn::LatencyMs,
+    HeartbeatColumn::Code,
+    HeartbeatColumn::SampleRate,
+    HeartbeatColumn::Attempts,
+];
+
+impl HeartbeatColumn {
+    /// Physical AE column name, e.g. `blob3`. The monitor id is the dataset's only index.
+    pub fn physical(&self) -> String {
+        let position = |columns: &[HeartbeatColumn]| {
+            columns
+                .iter()
+                .position(|column| column == self)
+                .map(|index| index + 1)
+        };
+        match (position(&BLOBS), position(&DOUBLES)) {
+            (Some(blob), _) => format!("blob{blob}"),
+            (None, Some(double)) => format!("double{double}"),
+            (None, None) => "index1".to_string(),
+        }
+    }
+
+    /// Name used for the column in query results.
+    pub fn alias(&self) -> &'static str {
+        match self {
+            HeartbeatColumn::MonitorId => "monitor_id",
+            HeartbeatColumn::OrgId => "org_id",
+            HeartbeatColumn::DispatchId => "dispatch_id",
+            HeartbeatColumn::Status => "status",
+            HeartbeatColumn::Region => "region",
+            HeartbeatColumn::Colo => "colo",
+            HeartbeatColumn::Error => "error",
+            HeartbeatColumn::RelayId => "relay_id",
+            HeartbeatColumn::Evidence => "evidence",
+            HeartbeatColumn::AttemptErrors => "attempt_errors",
+            HeartbeatColumn::TimestampMs => "timestamp_ms",
+            HeartbeatColumn::LatencyMs => "latency_ms",
+            HeartbeatColumn::Code => "code",
+            HeartbeatColumn::SampleRate => "sample_rate",
+            HeartbeatColumn::Attempts => "attempts",
+        }
+    }
+}
+
+/// One heartbeat laid out by column: the index, then `BLOBS` and `DOUBLES` in order.
+pub struct HeartbeatValues {
+    pub index: String,
+    pub blobs: [String; BLOBS.len()],
+    pub doubles: [f64; DOUBLES.len()],
+}
+
+pub fn heartbeat_values(event: &HeartbeatResult, relay_id: Option<&str>) -> HeartbeatValues {
+    let blob = |column: HeartbeatColumn| -> String {
+        match column {
+            HeartbeatColumn::OrgId => event.org_id.clone(),
+            HeartbeatColumn::DispatchId => event.dispatch_id.clone(),
+            HeartbeatColumn::Status => event.status.to_string(),
+            HeartbeatColumn::Region => event.region.clone(),
+            HeartbeatColumn::Colo => event.colo.clone(),
+            HeartbeatColumn::Error => event.error.clone().unwrap_or_default(),
+            HeartbeatColumn::RelayId => relay_id.unwrap_or_default().to_string(),
+            HeartbeatColumn::Evidence => event.evidence_key.clone().unwrap_or_default(),
+            // JSON array, empty when the first attempt passed.
+            HeartbeatColumn::AttemptErrors if event.attempt_errors.is_empty() => String::new(),
+            HeartbeatColumn::AttemptErrors => {
+                serde_json::to_string(&event.attempt_errors).unwrap_or_default()
+            }
+            _ => String::new(),
+        }
+    };
+    let double = |column: HeartbeatColumn| -> f64 {
+        match column {
+            HeartbeatColumn::TimestampMs => event.timestamp as f64,
+            HeartbeatColumn::LatencyMs => event.latency_ms as f64,
+            HeartbeatColumn::Code => event.code.unwrap_or(0) as f64,
+            HeartbeatColumn::SampleRate => event.sample_rate,
+            HeartbeatColumn::Attempts => event.attempts as f64,
+            _ => 0.0,
+        }
+    };
+
+    HeartbeatValues {
+        index: event.monitor_id.clone(),
+        blobs: BLOBS.map(blob),
+        doubles: DOUBLES.map(double),
+    }
+}
+
+/// Builds the Analytics Engine data point for a heartbeat, in schema order.
+pub fn heartbeat_data_point(values: &HeartbeatValues) -> AnalyticsEngineDataPoint {
+    AnalyticsEngineDataPointBuilder::new()
+        .indexes([values.index.as_str()])
+        .blobs(values.blobs.iter().map(String::as_str))
+        .doubles(values.doubles)
+        .build()
+}
@@ -0,0 +1,305 @@
+use futures::future::try_join_all;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+use worker::Result;
+
+use crate::monitors::types::MonitorStatus;
+
+use super::backend::HeartbeatStore;
+use super::monitor_health::{normalize_string, TimeWindow};
+use super::query::{AeQuery, Expr, Order};
+use super::schema::HeartbeatColumn;
+
+/// Rolling windows reported on dashboard cards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Display)]
+pub enum StatsWindow {
+    #[serde(rename = "24h")]
+    #[strum(serialize = "24h")]
+    Day,
+    #[serde(rename = "7d")]
+    #[strum(serialize = "7d")]
+    Week,
+    #[serde(rename = "30d")]
+    #[strum(serialize = "30d")]
+    Month,
+}
+
+impl StatsWindow {
+    pub fn all() -> [StatsWindow; 3] {
+        [StatsWindow::Day, StatsWindow::Week, StatsWindow::Month]
+    }
+
+    pub fn hours(&self) -> i64 {
+        match self {
+            StatsWindow::Day => 24,
+            StatsWindow::Week => 24 * 7,
+            StatsWindow::Month => 24 * 30,
+        }
+    }
+}
+
+/// Sample-weighted aggregates for one window. `checks` estimates how many checks actually ran,
+/// so it is fractional when the org samples below 1.0.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowStats {
+    pub window: StatsWindow,
+    pub since_ms: i64,
+    pub until_ms: i64,
+    pub checks: f64,
+    pub uptime_pct: Option<f64>,
+    /// Latency percentiles over checks that got a response (`down` rows excluded).
+    pub p50_ms: Option<f64>,
+    pub p90_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+}
+
+impl WindowStats {
+    fn empty(window: StatsWindow, range: &TimeWindow) -> Self {
+        WindowStats {
+            window,
+            since_ms: range.since_ms,
+            until_ms: range.until_ms,
+            checks: 0.0,
+            uptime_pct: None,
+            p50_ms: None,
+            p90_ms: None,
+            p99_ms: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorStats {
+    pub monitor_id: String,
+    pub windows: Vec<WindowStats>,
+}
+
+/// Which rows a stats query aggregates and how they are grouped.
+#[derive(Debug, Clone, Copy)]
+pub enum StatsScope<'a> {
+    /// A single monitor.
+    Monitor(&'a str),
+    /// Every monitor in the org, one row per monitor.
+    PerMonitor,
+    /// Every monitor in the org folded into one row.
+    Org,
+}
+
+#[derive(Deserialize)]
+struct StatsRow {
+    monitor_id: Option<String>,
+    checks: Option<f64>,
+    up: Option<f64>,
+    p50: Option<f64>,
+    p90: Option<f64>,
+    p99: Option<f64>,
+}
+
+/// Runs one query per window and returns `(monitor_id, stats)` pairs in window order.
+/// `monitor_id` is `None` for `StatsScope::Org`.
+#[tracing::instrument(
+    name = "analytics.stats.window_stats",
+    skip(store, scope),
+    fields(org_id = %org_id, now_ms = %now_ms)
+)]
+pub async fn window_stats(
+    store: &impl HeartbeatStore,
+    org_id: &str,
+    scope: StatsScope<'_>,
+    now_ms: i64,
+) -> Result<Vec<(Option<String>, WindowStats)>> {
+    let queries = StatsWindow::all()
+        .into_iter()
+        .map(|window| query_window(store, org_id, scope, window, now_ms));
+    Ok(try_join_all(queries).await?.into_iter().flatten().collect())
+}
+
+/// Stats for a single monitor, always returning every window even when AE has no rows.
+pub async fn monitor_stats(
+    store: &impl HeartbeatStore,
+    org_id: &str,
+    monitor_id: &str,
+    now_ms: i64,
+) -> Result<MonitorStats> {
+    let rows = window_stats(store, org_id, StatsScope::Monitor(monitor_id), now_ms).await?;
+    Ok(MonitorStats {
+        monitor_id: monitor_id.to_string(),
+        windows: fill_windows(rows.into_iter().map(|(_, stats)| stats), now_ms),
+    })
+}
+
+/// Pads missing windows with empty stats so callers always get 24h, 7d and 30d in order.
+pub fn fill_windows(stats: impl IntoIterator<Item = WindowStats>, now_ms: i64) -> Vec<WindowStats> {
+    let stats = stats.into_iter().collect::<Vec<_>>();
+    StatsWindow::all()
+        .into_iter()
+        .map(|window| {
+            stats
+                .iter()
+                .find(|stats| stats.window == window)
+                .cloned()
+                .unwrap_or_else(|| {
+                    WindowStats::empty(window, &TimeWindow::last_hours(window.hours(), now_ms))
+                })
+        })
+        .collect()
+}
+
+async fn query_window(
+    store: &impl HeartbeatStore,
+    org_id: &str,
+    scope: StatsScope<'_>,
+    window: StatsWindow,
+    now_ms: i64,
+) -> Result<Vec<(Option<String>, WindowStats)>> {
+    let range = TimeWindow::last_hours(window.hours(), now_ms);
+    let query = AeQuery::new();
+    let query = match scope {
+        StatsScope::Monitor(monitor_id) => query
+            .column(HeartbeatColumn::MonitorId)
+            .filter(Expr::col(HeartbeatColumn::MonitorId).eq(Expr::lit(monitor_id)))
+            .group_by(HeartbeatColumn::MonitorId.alias()),
+        StatsScope::PerMonitor => query
+            .column(HeartbeatColumn::MonitorId)
+            .group_by(HeartbeatColumn::MonitorId.alias()),
+        StatsScope::Org => query,
+    };
+    let query = query.select(Expr::sum(Expr::weight()), "checks").select(
+        Expr::sum(Expr::when(
+            Expr::status_is(MonitorStatus::Up),
+            Expr::weight(),
+            Expr::lit(0),
+        )),
+        "up",
+    );
+    let query = weighted_latency(query)
+        .filter(Expr::col(HeartbeatColumn::OrgId).eq(Expr::lit(org_id)))
+        .filter(Expr::col(HeartbeatColumn::TimestampMs).between(range.since_ms, range.until_ms))
+        .filter(Expr::col(HeartbeatColumn::SampleRate).gt(Expr::lit(0)));
+
+    let rows: Vec<StatsRow> = store.fetch(&query).await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let checks = row.checks.unwrap_or_default();
+            let mut stats = WindowStats::empty(window, &range);
+            if checks > 0.0 {
+                stats.checks = checks;
+                stats.uptime_pct =
+                    Some((row.up.unwrap_or_default() / checks * 100.0).clamp(0.0, 100.0));
+                stats.p50_ms = row.p50.filter(|value| value.is_finite());
+                stats.p90_ms = row.p90.filter(|value| value.is_finite());
+                stats.p99_ms = row.p99.filter(|value| value.is_finite());
+            }
+            (row.monitor_id, stats)
+        })
+        .collect())
+}
+
+/// Sample-weighted aggregates for one region / colo / relay group. Heartbeats written before
+/// relays were recorded have no `relay_id`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationStats {
+    pub region: Option<String>,
+    pub colo: Option<String>,
+    pub relay_id: Option<String>,
+    pub checks: f64,
+    pub failures: f64,
+    pub failure_rate: Option<f64>,
+    pub p50_ms: Option<f64>,
+    pub p90_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct LocationRow {
+    region: Option<String>,
+    colo: Option<String>,
+    relay_id: Option<String>,
+    checks: Option<f64>,
+    failures: Option<f64>,
+    p50: Option<f64>,
+    p90: Option<f64>,
+    p99: Option<f64>,
+}
+
+/// Groups heartbeats by where they ran, for one monitor or (with `None`) the whole org.
+#[tracing::instrument(
+    name = "analytics.stats.location_stats",
+    skip(store),
+    fields(org_id = %org_id, monitor_id = ?monitor_id, since_ms = %window.since_ms, until_ms = %window.until_ms)
+)]
+pub async fn location_stats(
+    store: &impl HeartbeatStore,
+    org_id: &str,
+    monitor_id: Option<&str>,
+    window: &TimeWindow,
+) -> Result<Vec<LocationStats>> {
+    let mut query = AeQuery::new()
+        .column(HeartbeatColumn::Region)
+        .column(HeartbeatColumn::Colo)
+        .column(HeartbeatColumn::RelayId)
+        .select(Expr::sum(Expr::weight()), "checks")
+        .select(
+            Expr::sum(Expr::when(
+                Expr::status_is(MonitorStatus::Down),
+                Expr::weight(),
+                Expr::lit(0),
+            )),
+            "failures",
+        );
+    query = weighted_latency(query)
+        .filter(Expr::col(HeartbeatColumn::OrgId).eq(Expr::lit(org_id)))
+        .filter(Expr::col(HeartbeatColumn::TimestampMs).between(window.since_ms, window.until_ms))
+        .filter(Expr::col(HeartbeatColumn::SampleRate).gt(Expr::lit(0)))
+        .group_by(HeartbeatColumn::Region.alias())
+        .group_by(HeartbeatColumn::Colo.alias())
+        .group_by(HeartbeatColumn::RelayId.alias())
+        .order_by("checks", Order::Desc);
+    if let Some(monitor_id) = monitor_id {
+        query = query.filter(Expr::col(HeartbeatColumn::MonitorId).eq(Expr::lit(monitor_id)));
+    }
+
+    let rows: Vec<LocationRow> = store.fetch(&query).await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let checks = row.checks.unwrap_or_default();
+            let failures = row.failures.unwrap_or_default();
+            let responded = checks - failures > 0.0;
+            LocationStats {
+                region: normalize_string(row.region),
+                colo: normalize_string(row.colo),
+                relay_id: normalize_string(row.relay_id),
+                checks,
+                failures,
+                failure_rate: (checks > 0.0).then(|| (failures / checks).clamp(0.0, 1.0)),
+                p50_ms: row.p50.filter(|value| responded && value.is_finite()),
+                p90_ms: row.p90.filter(|value| responded && value.is_finite()),
+                p99_ms: row.p99.filter(|value| responded && value.is_finite()),
+            }
+        })
+        .collect())
+}
+
+/// Adds `p50`, `p90` and `p99` latency columns. Each row stands in for `1 / sample_rate`
+/// checks; quantile weights must be integers, so they are truncated.
+fn weighted_latency(query: AeQuery) -> AeQuery {
+    let latency = || Expr::col(HeartbeatColumn::LatencyMs);
+    query
+        .select(
+            Expr::quantile_weighted(0.5, latency(), Expr::response_weight()),
+            "p50",
+        )
+        .select(
+            Expr::quantile_weighted(0.9, latency(), Expr::response_weight()),
+            "p90",
+        )
+        .select(
+            Expr::quantile_weighted(0.99, latency(), Expr::response_weight()),
+            "p99",
+        )
+}
@@ -0,0 +1,19 @@
+use crate::router::AppState;
+use axum::{routing::get, Router};
+
+pub mod handlers;
+pub mod service;
+pub mod types;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/manifest", get(handlers::get_archive_manifest_handler))
+        .route(
+            "/evidence/{dispatch_id}",
+            get(handlers::get_failure_evidence_handler),
+        )
+        .route(
+            "/monitors/{id}",
+            get(handlers::stream_monitor_archive_handler),
+        )
+}
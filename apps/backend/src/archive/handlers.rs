@@ -0,0 +1,76 @@
+use crate::archive::service::{
+    archive_bucket, failure_evidence, monitor_archive_stream, org_manifest,
+};
+use crate::archive::types::{ArchiveError, ArchiveRangeQuery, FailureEvidence, OrgManifest};
+use crate::auth::membership::load_membership;
+use crate::cloudflare::d1::AppDb;
+use crate::router::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{Response, Result},
+    Json,
+};
+use hb_auth::User;
+
+#[worker::send]
+#[tracing::instrument(name = "archive.http.manifest", skip(state, d1), fields(subject = %auth.sub()))]
+pub async fn get_archive_manifest_handler(
+    State(state): State<AppState>,
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<Json<OrgManifest>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    let bucket = archive_bucket(&state.env())?;
+    match org_manifest(&bucket, &org_id).await {
+        Ok(manifest) => Ok(Json(manifest)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Streams archived heartbeats as NDJSON, oldest first, for `?from=YYYY-MM-DD&to=YYYY-MM-DD`.
+#[worker::send]
+#[tracing::instrument(
+    name = "archive.http.monitor",
+    skip(state, d1, range),
+    fields(monitor_id = %id, from = %range.from, to = %range.to)
+)]
+pub async fn stream_monitor_archive_handler(
+    Path(id): Path<String>,
+    Query(range): Query<ArchiveRangeQuery>,
+    State(state): State<AppState>,
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<Response, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    let bucket = archive_bucket(&state.env())?;
+    let stream = monitor_archive_stream(bucket, org_id, id, &range)?;
+
+    let mut response = worker::Response::from_stream(stream).map_err(ArchiveError::Storage)?;
+    response
+        .headers_mut()
+        .set("Content-Type", "application/x-ndjson")
+        .map_err(ArchiveError::Storage)?;
+
+    Ok(response.into())
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "archive.http.evidence",
+    skip(state, d1),
+    fields(dispatch_id = %dispatch_id, subject = %auth.sub())
+)]
+pub async fn get_failure_evidence_handler(
+    Path(dispatch_id): Path<String>,
+    State(state): State<AppState>,
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<Json<FailureEvidence>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    let bucket = archive_bucket(&state.env())?;
+    match failure_evidence(&bucket, &org_id, &dispatch_id).await {
+        Ok(evidence) => Ok(Json(evidence)),
+        Err(err) => Err(err.into()),
+    }
+}
@@ -0,0 +1,277 @@
+use futures::{Stream, StreamExt, TryStreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use worker::{console_error, Bucket, Env, HttpMetadata};
+
+use crate::analytics::backend::{AnalyticsBackend, HeartbeatStore};
+use crate::analytics::monitor_health::{heartbeats_in_window, monitors_with_heartbeats};
+use crate::archive::types::{
+    day_manifest_key, evidence_key, object_key, org_manifest_key, ArchiveDay, ArchiveError,
+    ArchiveObject, ArchiveRangeQuery, ArchiveRun, DayManifest, FailureEvidence, OrgManifest,
+    ARCHIVE_BUCKET_BINDING, MAX_READ_DAYS,
+};
+use crate::cloudflare::d1::get_d1;
+use crate::d1c::queries::organizations::select_all_org_ids;
+use crate::utils::date::now_ms;
+use crate::utils::gzip::{gunzip, gzip};
+
+/// Analytics Engine keeps roughly three months; older days can no longer be exported.
+const AE_RETENTION_DAYS: i64 = 90;
+/// Days exported per org per run, so a first run's backfill fits within one invocation.
+const MAX_DAYS_PER_RUN: usize = 14;
+/// Row cap for one monitor-day; a 10s interval is 8,640 rows.
+const MAX_ROWS_PER_OBJECT: usize = 50_000;
+
+pub fn archive_bucket(env: &Env) -> Result<Bucket, ArchiveError> {
+    env.bucket(ARCHIVE_BUCKET_BINDING)
+        .map_err(ArchiveError::Storage)
+}
+
+/// Cron entry point: archives every org through yesterday (UTC). A failing org is logged and
+/// retried on the next run without blocking the others.
+#[tracing::instrument(name = "archive.run_scheduled", skip(env))]
+pub async fn run_scheduled_archive(env: &Env) -> Result<ArchiveRun, ArchiveError> {
+    let d1 = get_d1(env)?;
+    let bucket = archive_bucket(env)?;
+    let analytics = AnalyticsBackend::from_env(env).map_err(ArchiveError::Analytics)?;
+    let through = ArchiveDay::from_ms(now_ms()).offset(-1);
+
+    let mut run = ArchiveRun::default();
+    for row in select_all_org_ids(&d1).await? {
+        let Some(org_id) = row.id else {
+            continue;
+        };
+        run.orgs += 1;
+        match archive_org(&bucket, &analytics, &org_id, through).await {
+            Ok(days) => {
+                run.days += days.len();
+                run.objects += days.iter().map(|day| day.objects.len()).sum::<usize>();
+            }
+            Err(err) => {
+                run.failed_orgs += 1;
+                console_error!("archive.org {org_id}: {err:?}");
+            }
+        }
+    }
+
+    Ok(run)
+}
+
+/// Exports the days after the org's cursor, oldest first, advancing the cursor after each
+/// day so an interrupted run resumes where it stopped.
+#[tracing::instrument(
+    name = "archive.archive_org",
+    skip(bucket, analytics),
+    fields(org_id = %org_id, through = %through)
+)]
+pub async fn archive_org(
+    bucket: &Bucket,
+    analytics: &impl HeartbeatStore,
+    org_id: &str,
+    through: ArchiveDay,
+) -> Result<Vec<DayManifest>, ArchiveError> {
+    let mut manifest = get_json::<OrgManifest>(bucket, &org_manifest_key(org_id))
+        .await?
+        .unwrap_or_else(|| OrgManifest::empty(org_id));
+    let oldest = through.offset(1 - AE_RETENTION_DAYS);
+    let mut day = manifest
+        .last_day
+        .map_or(oldest, |last| last.offset(1))
+        .max(oldest);
+
+    let mut archived = Vec::new();
+    while day <= through && archived.len() < MAX_DAYS_PER_RUN {
+        let day_manifest = archive_day(bucket, analytics, org_id, day).await?;
+        manifest.record(&day_manifest);
+        put_json(bucket, &org_manifest_key(org_id), &manifest).await?;
+        archived.push(day_manifest);
+        day = day.offset(1);
+    }
+
+    Ok(archived)
+}
+
+/// Writes one gzipped NDJSON object per monitor with heartbeats that day, then the day
+/// manifest. Re-running a day overwrites the same keys.
+async fn archive_day(
+    bucket: &Bucket,
+    analytics: &impl HeartbeatStore,
+    org_id: &str,
+    day: ArchiveDay,
+) -> Result<DayManifest, ArchiveError> {
+    let window = day.window();
+    let monitor_ids = monitors_with_heartbeats(analytics, org_id, &window)
+        .await
+        .map_err(ArchiveError::Analytics)?;
+
+    let mut objects = Vec::with_capacity(monitor_ids.len());
+    for monitor_id in monitor_ids {
+        let samples =
+            heartbeats_in_window(analytics, &monitor_id, org_id, &window, MAX_ROWS_PER_OBJECT)
+                .await
+                .map_err(ArchiveError::Analytics)?;
+        if samples.is_empty() {
+            continue;
+        }
+
+        let mut ndjson = Vec::new();
+        for sample in &samples {
+            serde_json::to_writer(&mut ndjson, sample)
+                .map_err(|err| ArchiveError::Storage(err.into()))?;
+            ndjson.push(b'\n');
+        }
+        let body = gzip(&ndjson).await.map_err(ArchiveError::Storage)?;
+        let bytes = body.len();
+
+        let key = object_key(org_id, &monitor_id, day);
+        bucket
+            .put(&key, body)
+            .http_metadata(HttpMetadata {
+                content_type: Some("application/x-ndjson".to_string()),
+                content_encoding: Some("gzip".to_string()),
+                ..Default::default()
+            })
+            .execute()
+            .await
+            .map_err(ArchiveError::Storage)?;
+
+        objects.push(ArchiveObject {
+            monitor_id,
+            key,
+            rows: samples.len(),
+            bytes,
+            truncated: samples.len() >= MAX_ROWS_PER_OBJECT,
+        });
+    }
+
+    let manifest = DayManifest {
+        org_id: org_id.to_string(),
+        day,
+        archived_at: now_ms(),
+        objects,
+    };
+    put_json(bucket, &day_manifest_key(org_id, day), &manifest).await?;
+
+    Ok(manifest)
+}
+
+#[tracing::instrument(name = "archive.org_manifest", skip(bucket), fields(org_id = %org_id))]
+pub async fn org_manifest(bucket: &Bucket, org_id: &str) -> Result<OrgManifest, ArchiveError> {
+    Ok(get_json::<OrgManifest>(bucket, &org_manifest_key(org_id))
+        .await?
+        .unwrap_or_else(|| OrgManifest::empty(org_id)))
+}
+
+/// Stores the evidence for a failed check and returns its key for the heartbeat to carry.
+#[tracing::instrument(
+    name = "archive.put_failure_evidence",
+    skip(bucket, evidence),
+    fields(org_id = %evidence.org_id, dispatch_id = %evidence.dispatch_id)
+)]
+pub async fn put_failure_evidence(
+    bucket: &Bucket,
+    evidence: &FailureEvidence,
+) -> Result<String, ArchiveError> {
+    let key = evidence_key(&evidence.org_id, &evidence.dispatch_id);
+    put_json(bucket, &key, evidence).await?;
+    Ok(key)
+}
+
+#[tracing::instrument(
+    name = "archive.failure_evidence",
+    skip(bucket),
+    fields(org_id = %org_id, dispatch_id = %dispatch_id)
+)]
+pub async fn failure_evidence(
+    bucket: &Bucket,
+    org_id: &str,
+    dispatch_id: &str,
+) -> Result<FailureEvidence, ArchiveError> {
+    // Dispatch ids are cuids; slashes or dots could step outside the org's key prefix.
+    if dispatch_id.is_empty()
+        || !dispatch_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(ArchiveError::NotFound);
+    }
+    get_json::<FailureEvidence>(bucket, &evidence_key(org_id, dispatch_id))
+        .await?
+        .ok_or(ArchiveError::NotFound)
+}
+
+/// Decompressed NDJSON for one monitor over an inclusive day range, one chunk per archived
+/// day. Days without an object are skipped.
+pub fn monitor_archive_stream(
+    bucket: Bucket,
+    org_id: String,
+    monitor_id: String,
+    range: &ArchiveRangeQuery,
+) -> Result<impl Stream<Item = worker::Result<Vec<u8>>> + 'static, ArchiveError> {
+    let days = range.from.days_until(range.to);
+    if days < 1 {
+        return Err(ArchiveError::InvalidRange(format!(
+            "from {} is after to {}",
+            range.from, range.to
+        )));
+    }
+    if days > MAX_READ_DAYS {
+        return Err(ArchiveError::InvalidRange(format!(
+            "range covers {days} days, at most {MAX_READ_DAYS} allowed"
+        )));
+    }
+
+    let from = range.from;
+    Ok(futures::stream::iter(0..days)
+        .then(move |offset| {
+            let bucket = bucket.clone();
+            let key = object_key(&org_id, &monitor_id, from.offset(offset));
+            async move { read_object(&bucket, &key).await }
+        })
+        .try_filter_map(|chunk| async move { Ok(chunk) }))
+}
+
+async fn read_object(bucket: &Bucket, key: &str) -> worker::Result<Option<Vec<u8>>> {
+    let Some(object) = bucket.get(key).execute().await? else {
+        return Ok(None);
+    };
+    let Some(body) = object.body() else {
+        return Ok(None);
+    };
+    let bytes = body.bytes().await?;
+    Ok(Some(gunzip(&bytes).await?))
+}
+
+async fn get_json<T: DeserializeOwned>(
+    bucket: &Bucket,
+    key: &str,
+) -> Result<Option<T>, ArchiveError> {
+    let Some(object) = bucket
+        .get(key)
+        .execute()
+        .await
+        .map_err(ArchiveError::Storage)?
+    else {
+        return Ok(None);
+    };
+    let Some(body) = object.body() else {
+        return Ok(None);
+    };
+    let text = body.text().await.map_err(ArchiveError::Storage)?;
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|err| ArchiveError::Storage(err.into()))
+}
+
+async fn put_json<T: Serialize>(bucket: &Bucket, key: &str, value: &T) -> Result<(), ArchiveError> {
+    let body = serde_json::to_string(value).map_err(|err| ArchiveError::Storage(err.into()))?;
+    bucket
+        .put(key, body)
+        .http_metadata(HttpMetadata {
+            content_type: Some("application/json".to_string()),
+            ..Default::default()
+        })
+        .execute()
+        .await
+        .map_err(ArchiveError::Storage)?;
+    Ok(())
+}
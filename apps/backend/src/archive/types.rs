@@ -0,0 +1,279 @@
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
+use worker::console_error;
+
+use crate::analytics::monitor_health::{TimeWindow, DAY_MS};
+
+pub const ARCHIVE_BUCKET_BINDING: &str = "ARCHIVE_BUCKET";
+
+/// Longest range one read request may stream, enough for a yearly SLA report.
+pub const MAX_READ_DAYS: i64 = 366;
+
+/// A UTC calendar day, `YYYY-MM-DD` on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct ArchiveDay(i64);
+
+impl ArchiveDay {
+    pub fn from_ms(ms: i64) -> Self {
+        ArchiveDay(ms.div_euclid(DAY_MS))
+    }
+
+    pub fn offset(&self, days: i64) -> Self {
+        ArchiveDay(self.0 + days)
+    }
+
+    /// Inclusive number of days from `self` to `until`.
+    pub fn days_until(&self, until: ArchiveDay) -> i64 {
+        until.0 - self.0 + 1
+    }
+
+    /// `[00:00, 23:59:59.999]` of the day in epoch milliseconds.
+    pub fn window(&self) -> TimeWindow {
+        TimeWindow {
+            since_ms: self.0 * DAY_MS,
+            until_ms: (self.0 + 1) * DAY_MS - 1,
+        }
+    }
+
+    /// `YYYY/MM/DD`, the date part of object keys.
+    pub fn path(&self) -> String {
+        let (year, month, day) = civil_from_days(self.0);
+        format!("{year:04}/{month:02}/{day:02}")
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.trim().splitn(3, '-');
+        let year = parts.next()?.parse::<i64>().ok()?;
+        let month = parts.next()?.parse::<i64>().ok()?;
+        let day = parts.next()?.parse::<i64>().ok()?;
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+
+        // Rejects dates like 2025-02-30 that would silently roll into the next month.
+        let days = days_from_civil(year, month, day);
+        (civil_from_days(days) == (year, month, day)).then_some(ArchiveDay(days))
+    }
+}
+
+impl fmt::Display for ArchiveDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = civil_from_days(self.0);
+        write!(f, "{year:04}-{month:02}-{day:02}")
+    }
+}
+
+impl From<ArchiveDay> for String {
+    fn from(day: ArchiveDay) -> Self {
+        day.to_string()
+    }
+}
+
+impl TryFrom<String> for ArchiveDay {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        ArchiveDay::parse(&value)
+            .ok_or_else(|| format!("invalid date {value}, expected YYYY-MM-DD"))
+    }
+}
+
+// Proleptic Gregorian conversions, after Howard Hinnant's `days_from_civil` / `civil_from_days`.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// `org/{org}/monitor/{monitor}/YYYY/MM/DD.ndjson.gz`
+pub fn object_key(org_id: &str, monitor_id: &str, day: ArchiveDay) -> String {
+    format!("org/{org_id}/monitor/{monitor_id}/{}.ndjson.gz", day.path())
+}
+
+/// `org/{org}/manifests/YYYY/MM/DD.json`
+pub fn day_manifest_key(org_id: &str, day: ArchiveDay) -> String {
+    format!("org/{org_id}/manifests/{}.json", day.path())
+}
+
+/// `org/{org}/manifest.json`
+pub fn org_manifest_key(org_id: &str) -> String {
+    format!("org/{org_id}/manifest.json")
+}
+
+/// `org/{org}/evidence/{dispatch_id}.json`
+pub fn evidence_key(org_id: &str, dispatch_id: &str) -> String {
+    format!("org/{org_id}/evidence/{dispatch_id}.json")
+}
+
+/// API path serving a dispatch's failure evidence, linked from heartbeats and incidents.
+pub fn evidence_path(dispatch_id: &str) -> String {
+    format!("/api/archive/evidence/{dispatch_id}")
+}
+
+/// One gzipped NDJSON object: a monitor's heartbeats for one day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveObject {
+    pub monitor_id: String,
+    pub key: String,
+    pub rows: usize,
+    pub bytes: usize,
+    /// True when the day hit the per-object row cap and later heartbeats were dropped.
+    pub truncated: bool,
+}
+
+/// Everything archived for one org on one day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayManifest {
+    pub org_id: String,
+    pub day: ArchiveDay,
+    pub archived_at: i64,
+    pub objects: Vec<ArchiveObject>,
+}
+
+/// Per-org cursor. Days are archived in order; a day that left AE retention before the job
+/// reached it is skipped rather than recorded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrgManifest {
+    pub org_id: String,
+    pub first_day: Option<ArchiveDay>,
+    pub last_day: Option<ArchiveDay>,
+    pub objects: usize,
+    pub rows: usize,
+    pub updated_at: i64,
+}
+
+impl OrgManifest {
+    pub fn empty(org_id: &str) -> Self {
+        OrgManifest {
+            org_id: org_id.to_string(),
+            first_day: None,
+            last_day: None,
+            objects: 0,
+            rows: 0,
+            updated_at: 0,
+        }
+    }
+
+    pub fn record(&mut self, day: &DayManifest) {
+        self.first_day = Some(self.first_day.map_or(day.day, |first| first.min(day.day)));
+        self.last_day = Some(self.last_day.map_or(day.day, |last| last.max(day.day)));
+        self.objects += day.objects.len();
+        self.rows += day.objects.iter().map(|object| object.rows).sum::<usize>();
+        self.updated_at = day.archived_at;
+    }
+}
+
+/// Outcome of one scheduled run, logged by the cron handler.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveRun {
+    pub orgs: usize,
+    pub days: usize,
+    pub objects: usize,
+    pub failed_orgs: usize,
+}
+
+/// What the checker saw when an HTTP check failed, kept for postmortems.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailureEvidence {
+    pub dispatch_id: String,
+    pub monitor_id: String,
+    pub org_id: String,
+    pub captured_at: i64,
+    pub region: String,
+    pub colo: String,
+    pub requested_url: String,
+    pub final_url: String,
+    pub redirects: Vec<RedirectHop>,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub headers: BTreeMap<String, String>,
+    /// The start of the response body (16 KiB at most), decoded lossily as UTF-8.
+    pub body: Option<String>,
+    pub body_truncated: bool,
+}
+
+/// A redirect the checker followed on the way to `final_url`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedirectHop {
+    pub url: String,
+    pub status: u16,
+    pub location: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ArchiveRangeQuery {
+    pub from: ArchiveDay,
+    pub to: ArchiveDay,
+}
+
+#[derive(Debug)]
+pub enum ArchiveError {
+    DbRun(worker::Error),
+    Storage(worker::Error),
+    Analytics(worker::Error),
+    InvalidRange(String),
+    NotFound,
+}
+
+impl From<worker::Error> for ArchiveError {
+    fn from(err: worker::Error) -> Self {
+        ArchiveError::DbRun(err)
+    }
+}
+
+impl From<ArchiveError> for axum::http::StatusCode {
+    fn from(err: ArchiveError) -> axum::http::StatusCode {
+        match err {
+            ArchiveError::DbRun(err) => {
+                console_error!("archive.db.run: {err:?}");
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+            ArchiveError::Storage(err) => {
+                console_error!("archive.r2: {err:?}");
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+            ArchiveError::Analytics(err) => {
+                console_error!("archive.analytics: {err:?}");
+                axum::http::StatusCode::BAD_GATEWAY
+            }
+            ArchiveError::InvalidRange(msg) => {
+                console_error!("archive.invalid.range: {msg}");
+                axum::http::StatusCode::BAD_REQUEST
+            }
+            ArchiveError::NotFound => {
+                console_error!("archive.not.found");
+                axum::http::StatusCode::NOT_FOUND
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
-pub mod analytics;
 pub mod d1;
 pub mod durable_objects;
+pub mod queues;
 pub mod request;
+pub mod trace;
@@ -1,6 +1,14 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
 
-use crate::{internal::types::MonitorKind, monitors::types::HttpMonitorConfig};
+use crate::{
+    internal::types::{DispatchRequest, MonitorKind},
+    monitors::types::{
+        HttpAssertion, HttpMethod, HttpRequestBody, MonitorConfig, Placement, TcpMonitorConfig,
+    },
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TickerConfig {
@@ -14,17 +22,51 @@ pub struct TickerState {
     pub config: Option<TickerConfig>,
     pub last_tick_ts: i64,
     pub consecutive_errors: u32,
+    /// Stuck dispatches the reaper closed on the last tick, and since bootstrap.
+    #[serde(default)]
+    pub reaped_last_tick: usize,
+    #[serde(default)]
+    pub reaped_total: u64,
+}
+
+/// What the reaper does with a dispatch that outlived its timeout; `DISPATCH_REAP_POLICY`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum ReapPolicy {
+    /// Record a down heartbeat, as if the check had failed.
+    Fail,
+    /// Dispatch the monitor again under a new dispatch id.
+    Redispatch,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonitorDispatchRow {
     pub id: String,
     pub kind: MonitorKind,
-    pub config: HttpMonitorConfig,
+    pub config: MonitorConfig,
     pub scheduled_for_ts: i64,
     pub status: String,
     pub first_checked_at: Option<i64>,
     pub last_failed_at: Option<i64>,
+    pub placement: Placement,
+    /// Enabled relays in `position` order; empty means the default dispatch path.
+    pub relays: Vec<PlacementRelay>,
+}
+
+/// One entry of the `relays_json` column the claim and reaper queries build.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlacementRelay {
+    pub relay_id: String,
+    /// Durable Object id of the Relay, which runs the check from its pinned colo.
+    pub object_id: String,
+    pub position: i64,
+}
+
+/// Where one run of a monitor goes and which hot row tracks it.
+#[derive(Debug, Clone)]
+pub struct DispatchTarget {
+    pub relay: Option<PlacementRelay>,
+    pub hot_relay_id: String,
 }
 
 #[derive(Serialize)]
@@ -38,11 +80,59 @@ pub struct DispatchPayload {
     pub timeout_ms: i64,
     pub follow_redirects: bool,
     pub verify_tls: bool,
+    pub method: HttpMethod,
+    pub headers: BTreeMap<String, String>,
+    pub body: Option<HttpRequestBody>,
+    pub assertions: Vec<HttpAssertion>,
+    pub degraded_after_ms: Option<i64>,
+    pub down_after_ms: Option<i64>,
+    pub tcp: Option<TcpMonitorConfig>,
+    pub failure_threshold: u32,
+    pub recovery_threshold: u32,
     pub scheduled_for_ts: i64,
     pub status: String,
     pub first_checked_at: Option<i64>,
     pub last_failed_at: Option<i64>,
     pub sample_rate: f64,
+    pub relay_id: Option<String>,
+    pub hot_relay_id: String,
+    pub down_quorum: u32,
+    pub retries: u32,
+    pub retry_delay_ms: i64,
+}
+
+impl From<DispatchPayload> for DispatchRequest {
+    fn from(payload: DispatchPayload) -> Self {
+        DispatchRequest {
+            dispatch_id: payload.dispatch_id,
+            monitor_id: payload.monitor_id,
+            org_id: payload.org_id,
+            monitor_url: payload.monitor_url,
+            kind: payload.kind,
+            scheduled_for_ts: payload.scheduled_for_ts,
+            timeout_ms: payload.timeout_ms,
+            follow_redirects: payload.follow_redirects,
+            verify_tls: payload.verify_tls,
+            method: payload.method,
+            headers: payload.headers,
+            body: payload.body,
+            assertions: payload.assertions,
+            degraded_after_ms: payload.degraded_after_ms,
+            down_after_ms: payload.down_after_ms,
+            tcp: payload.tcp,
+            failure_threshold: payload.failure_threshold,
+            recovery_threshold: payload.recovery_threshold,
+            sample_rate: payload.sample_rate,
+            status: Some(payload.status),
+            first_checked_at: payload.first_checked_at,
+            last_failed_at: payload.last_failed_at,
+            relay_id: payload.relay_id,
+            hot_relay_id: payload.hot_relay_id,
+            down_quorum: payload.down_quorum,
+            retries: payload.retries,
+            retry_delay_ms: payload.retry_delay_ms,
+        }
+    }
 }
 
 #[derive(Debug)]
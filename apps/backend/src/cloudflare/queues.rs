@@ -0,0 +1,37 @@
+use std::str::FromStr;
+
+use strum::{Display, EnumString};
+use worker::{Env, Queue, Result};
+
+/// Producer binding the Ticker enqueues dispatches on when `DISPATCH_MODE=queue`.
+pub const DISPATCH_QUEUE_BINDING: &str = "DISPATCH_QUEUE";
+
+/// Env var selecting how the Ticker hands checks to the runner; unset means `direct`.
+pub const DISPATCH_MODE_VAR: &str = "DISPATCH_MODE";
+
+/// Env var naming the dead-letter queue, so the consumer can tell its two inputs apart.
+pub const DISPATCH_DLQ_VAR: &str = "DISPATCH_DEAD_LETTER_QUEUE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum DispatchMode {
+    /// The Ticker calls the dispatch route itself, inside its alarm.
+    Direct,
+    /// The Ticker enqueues payloads; the queue consumer runs the checks.
+    Queue,
+}
+
+pub fn dispatch_mode(env: &Env) -> Result<DispatchMode> {
+    match env.var(DISPATCH_MODE_VAR) {
+        Ok(value) => DispatchMode::from_str(value.to_string().trim()).map_err(|_| {
+            worker::Error::RustError(format!(
+                "Unknown {DISPATCH_MODE_VAR} value {value}; expected direct or queue"
+            ))
+        }),
+        Err(_) => Ok(DispatchMode::Direct),
+    }
+}
+
+pub fn dispatch_queue(env: &Env) -> Result<Queue> {
+    env.queue(DISPATCH_QUEUE_BINDING)
+}
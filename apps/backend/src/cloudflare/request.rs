@@ -5,8 +5,9 @@ use worker::Cf;
 use crate::router::AppState;
 
 /// Extracts Cloudflare runtime metadata (colo, region, etc.) from the inbound request.
+/// `None` for requests that never crossed the edge, such as calls over a service binding.
 #[derive(Clone, Debug)]
-pub struct RequestCf(pub Cf);
+pub struct RequestCf(pub Option<Cf>);
 
 impl FromRequestParts<AppState> for RequestCf {
     type Rejection = StatusCode;
@@ -15,11 +16,6 @@ impl FromRequestParts<AppState> for RequestCf {
         parts: &mut Parts,
         _state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        parts
-            .extensions
-            .get::<Cf>()
-            .cloned()
-            .map(RequestCf)
-            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)
+        Ok(RequestCf(parts.extensions.get::<Cf>().cloned()))
     }
 }
@@ -0,0 +1,42 @@
+use std::cell::RefCell;
+
+use worker::{console_warn, Error, Fetch, Result, Url};
+
+/// Cloudflare's trace endpoint answers with the colo that served the request, which for a
+/// subrequest is the colo the Worker or Durable Object runs in.
+const TRACE_URL: &str = "https://www.cloudflare.com/cdn-cgi/trace";
+
+thread_local! {
+    /// An isolate never moves between colos, so one successful lookup serves all its requests.
+    static COLO: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+pub async fn resolve_colo() -> Result<String> {
+    let body = Fetch::Url(Url::parse(TRACE_URL)?)
+        .send()
+        .await?
+        .text()
+        .await?;
+    body.lines()
+        .find_map(|line| line.strip_prefix("colo="))
+        .map(|colo| colo.trim().to_string())
+        .ok_or_else(|| Error::RustError("trace response has no colo".to_string()))
+}
+
+/// The colo this isolate runs in, looked up once and cached. `"unknown"` when the trace
+/// lookup fails; the next call tries again.
+pub async fn runner_colo() -> String {
+    if let Some(colo) = COLO.with(|cached| cached.borrow().clone()) {
+        return colo;
+    }
+    match resolve_colo().await {
+        Ok(colo) => {
+            COLO.with(|cached| *cached.borrow_mut() = Some(colo.clone()));
+            colo
+        }
+        Err(err) => {
+            console_warn!("trace.colo: {err:?}");
+            "unknown".to_string()
+        }
+    }
+}
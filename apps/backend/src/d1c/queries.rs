@@ -1,7 +1,10 @@
 // Auto-generated by d1c
 
 pub mod bootstrap;
+pub mod incidents;
 pub mod monitor_dispatches;
 pub mod monitors;
+pub mod notifications;
 pub mod organizations;
 pub mod relays;
+pub mod status_pages;
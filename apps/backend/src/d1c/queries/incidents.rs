@@ -0,0 +1,372 @@
+use worker::D1Database;
+use worker::Result;
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RecordMonitorFailureRow {
+    pub consecutive_failures: i64,
+    pub consecutive_successes: i64,
+}
+#[tracing::instrument(name = "d1c.record_monitor_failure", skip(d1))]
+pub async fn record_monitor_failure(
+    d1: &D1Database,
+    updated_at: i64,
+    monitor_id: &str,
+    relay_id: &str,
+) -> Result<Option<RecordMonitorFailureRow>> {
+    let stmt = d1
+        .prepare(
+            "UPDATE monitor_dispatch_hot SET consecutive_failures = consecutive_failures + 1, consecutive_successes = 0, updated_at = ?1 WHERE monitor_id = ?2 AND relay_id = ?3 RETURNING consecutive_failures, consecutive_successes",
+        );
+    let stmt = stmt
+        .bind(&[(updated_at as f64).into(), monitor_id.into(), relay_id.into()])?;
+    let result = stmt.first::<RecordMonitorFailureRow>(None).await?;
+    Ok(result)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RecordMonitorSuccessRow {
+    pub consecutive_failures: i64,
+    pub consecutive_successes: i64,
+}
+#[tracing::instrument(name = "d1c.record_monitor_success", skip(d1))]
+pub async fn record_monitor_success(
+    d1: &D1Database,
+    updated_at: i64,
+    monitor_id: &str,
+    relay_id: &str,
+) -> Result<Option<RecordMonitorSuccessRow>> {
+    let stmt = d1
+        .prepare(
+            "UPDATE monitor_dispatch_hot SET consecutive_successes = consecutive_successes + 1, consecutive_failures = 0, updated_at = ?1 WHERE monitor_id = ?2 AND relay_id = ?3 RETURNING consecutive_failures, consecutive_successes",
+        );
+    let stmt = stmt
+        .bind(&[(updated_at as f64).into(), monitor_id.into(), relay_id.into()])?;
+    let result = stmt.first::<RecordMonitorSuccessRow>(None).await?;
+    Ok(result)
+}
+#[tracing::instrument(name = "d1c.count_other_failing_relays", skip(d1))]
+pub async fn count_other_failing_relays(
+    d1: &D1Database,
+    monitor_id: &str,
+    relay_id: &str,
+    failure_threshold: i64,
+) -> Result<Option<i64>> {
+    let stmt = d1
+        .prepare(
+            "SELECT COUNT(*) AS total FROM monitor_dispatch_hot AS h WHERE h.monitor_id = ?1 AND h.relay_id <> ?2 AND h.consecutive_failures >= ?3 AND h.relay_id IN (SELECT mr.relay_id FROM monitor_relays AS mr JOIN relays AS r ON r.id = mr.relay_id WHERE mr.monitor_id = ?1 AND r.enabled = 1)",
+        );
+    let stmt = stmt
+        .bind(&[monitor_id.into(), relay_id.into(), (failure_threshold as f64).into()])?;
+    let result = stmt.first::<i64>(Some("total")).await?;
+    Ok(result)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct GetActiveIncidentForMonitorRow {
+    pub id: Option<String>,
+    pub monitor_id: String,
+    pub opened_ts: i64,
+    pub closed_ts: Option<i64>,
+    pub reason: Option<String>,
+    pub status: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+#[tracing::instrument(name = "d1c.get_active_incident_for_monitor", skip(d1))]
+pub async fn get_active_incident_for_monitor(
+    d1: &D1Database,
+    monitor_id: &str,
+) -> Result<Option<GetActiveIncidentForMonitorRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT id, monitor_id, opened_ts, closed_ts, reason, status, created_at, updated_at FROM incidents WHERE monitor_id = ?1 AND status <> 'closed' LIMIT 1",
+        );
+    let stmt = stmt.bind(&[monitor_id.into()])?;
+    let result = stmt.first::<GetActiveIncidentForMonitorRow>(None).await?;
+    Ok(result)
+}
+#[tracing::instrument(name = "d1c.open_incident", skip(d1))]
+pub async fn open_incident(
+    d1: &D1Database,
+    id: &str,
+    monitor_id: &str,
+    opened_ts: i64,
+    reason: Option<&str>,
+    evidence_dispatch_id: Option<&str>,
+    created_at: i64,
+    updated_at: i64,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "INSERT OR IGNORE INTO incidents (id, monitor_id, opened_ts, closed_ts, reason, evidence_dispatch_id, status, created_at, updated_at) VALUES (?1, ?2, ?3, NULL, ?4, ?5, 'open', ?6, ?7)",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                id.into(),
+                monitor_id.into(),
+                (opened_ts as f64).into(),
+                match reason {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                match evidence_dispatch_id {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                (created_at as f64).into(),
+                (updated_at as f64).into(),
+            ],
+        )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.close_incident", skip(d1))]
+pub async fn close_incident(
+    d1: &D1Database,
+    closed_ts: i64,
+    updated_at: i64,
+    id: &str,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "UPDATE incidents SET status = 'closed', closed_ts = ?1, updated_at = ?2 WHERE id = ?3 AND status <> 'closed'",
+        );
+    let stmt = stmt
+        .bind(&[(closed_ts as f64).into(), (updated_at as f64).into(), id.into()])?;
+    stmt.run().await?;
+    Ok(())
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListIncidentsForOrgRow {
+    pub id: Option<String>,
+    pub monitor_id: String,
+    pub monitor_name: String,
+    pub opened_ts: i64,
+    pub closed_ts: Option<i64>,
+    pub reason: Option<String>,
+    pub evidence_dispatch_id: Option<String>,
+    pub status: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+#[tracing::instrument(name = "d1c.list_incidents_for_org", skip(d1))]
+pub async fn list_incidents_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    status: Option<&str>,
+    monitor_id: Option<&str>,
+    since: Option<i64>,
+    until: Option<i64>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<ListIncidentsForOrgRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT i.id, i.monitor_id, m.name AS monitor_name, i.opened_ts, i.closed_ts, i.reason, i.evidence_dispatch_id, i.status, i.created_at, i.updated_at FROM incidents AS i JOIN monitors AS m ON m.id = i.monitor_id WHERE m.org_id = ?1 AND (?2 IS NULL OR i.status = ?2) AND (?3 IS NULL OR i.monitor_id = ?3) AND (?4 IS NULL OR i.opened_ts >= ?4) AND (?5 IS NULL OR i.opened_ts < ?5) ORDER BY i.opened_ts DESC LIMIT ?6 OFFSET ?7",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                org_id.into(),
+                match status {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                match monitor_id {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                match since {
+                    Some(value) => (value as f64).into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                match until {
+                    Some(value) => (value as f64).into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                (limit as f64).into(),
+                (offset as f64).into(),
+            ],
+        )?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListIncidentsForOrgRow>()?;
+    Ok(rows)
+}
+#[tracing::instrument(name = "d1c.count_incidents_for_org", skip(d1))]
+pub async fn count_incidents_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    status: Option<&str>,
+    monitor_id: Option<&str>,
+    since: Option<i64>,
+    until: Option<i64>,
+) -> Result<Option<i64>> {
+    let stmt = d1
+        .prepare(
+            "SELECT COUNT(*) AS total FROM incidents AS i JOIN monitors AS m ON m.id = i.monitor_id WHERE m.org_id = ?1 AND (?2 IS NULL OR i.status = ?2) AND (?3 IS NULL OR i.monitor_id = ?3) AND (?4 IS NULL OR i.opened_ts >= ?4) AND (?5 IS NULL OR i.opened_ts < ?5)",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                org_id.into(),
+                match status {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                match monitor_id {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                match since {
+                    Some(value) => (value as f64).into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                match until {
+                    Some(value) => (value as f64).into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+            ],
+        )?;
+    let result = stmt.first::<i64>(Some("total")).await?;
+    Ok(result)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct GetIncidentForOrgRow {
+    pub id: Option<String>,
+    pub monitor_id: String,
+    pub monitor_name: String,
+    pub opened_ts: i64,
+    pub closed_ts: Option<i64>,
+    pub reason: Option<String>,
+    pub evidence_dispatch_id: Option<String>,
+    pub status: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+#[tracing::instrument(name = "d1c.get_incident_for_org", skip(d1))]
+pub async fn get_incident_for_org(
+    d1: &D1Database,
+    id: &str,
+    org_id: &str,
+) -> Result<Option<GetIncidentForOrgRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT i.id, i.monitor_id, m.name AS monitor_name, i.opened_ts, i.closed_ts, i.reason, i.evidence_dispatch_id, i.status, i.created_at, i.updated_at FROM incidents AS i JOIN monitors AS m ON m.id = i.monitor_id WHERE i.id = ?1 AND m.org_id = ?2 LIMIT 1",
+        );
+    let stmt = stmt.bind(&[id.into(), org_id.into()])?;
+    let result = stmt.first::<GetIncidentForOrgRow>(None).await?;
+    Ok(result)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct AcknowledgeIncidentRow {
+    pub id: Option<String>,
+}
+#[tracing::instrument(name = "d1c.acknowledge_incident", skip(d1))]
+pub async fn acknowledge_incident(
+    d1: &D1Database,
+    updated_at: i64,
+    id: &str,
+) -> Result<Option<AcknowledgeIncidentRow>> {
+    let stmt = d1
+        .prepare(
+            "UPDATE incidents SET status = 'acknowledged', updated_at = ?1 WHERE id = ?2 AND status = 'open' RETURNING id",
+        );
+    let stmt = stmt.bind(&[(updated_at as f64).into(), id.into()])?;
+    let result = stmt.first::<AcknowledgeIncidentRow>(None).await?;
+    Ok(result)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ResolveIncidentRow {
+    pub id: Option<String>,
+}
+#[tracing::instrument(name = "d1c.resolve_incident", skip(d1))]
+pub async fn resolve_incident(
+    d1: &D1Database,
+    closed_ts: i64,
+    updated_at: i64,
+    id: &str,
+) -> Result<Option<ResolveIncidentRow>> {
+    let stmt = d1
+        .prepare(
+            "UPDATE incidents SET status = 'closed', closed_ts = ?1, updated_at = ?2 WHERE id = ?3 AND status <> 'closed' RETURNING id",
+        );
+    let stmt = stmt
+        .bind(&[(closed_ts as f64).into(), (updated_at as f64).into(), id.into()])?;
+    let result = stmt.first::<ResolveIncidentRow>(None).await?;
+    Ok(result)
+}
+#[tracing::instrument(name = "d1c.reset_monitor_counters", skip(d1))]
+pub async fn reset_monitor_counters(
+    d1: &D1Database,
+    updated_at: i64,
+    monitor_id: &str,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "UPDATE monitor_dispatch_hot SET consecutive_failures = 0, consecutive_successes = 0, updated_at = ?1 WHERE monitor_id = ?2",
+        );
+    let stmt = stmt.bind(&[(updated_at as f64).into(), monitor_id.into()])?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.insert_incident_event", skip(d1))]
+pub async fn insert_incident_event(
+    d1: &D1Database,
+    id: &str,
+    incident_id: &str,
+    kind: &str,
+    actor_id: Option<&str>,
+    actor_email: Option<&str>,
+    message: Option<&str>,
+    created_at: i64,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "INSERT INTO incident_events (id, incident_id, kind, actor_id, actor_email, message, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                id.into(),
+                incident_id.into(),
+                kind.into(),
+                match actor_id {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                match actor_email {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                match message {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                (created_at as f64).into(),
+            ],
+        )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListIncidentEventsRow {
+    pub id: Option<String>,
+    pub incident_id: String,
+    pub kind: String,
+    pub actor_id: Option<String>,
+    pub actor_email: Option<String>,
+    pub message: Option<String>,
+    pub created_at: i64,
+}
+#[tracing::instrument(name = "d1c.list_incident_events", skip(d1))]
+pub async fn list_incident_events(
+    d1: &D1Database,
+    incident_id: &str,
+) -> Result<Vec<ListIncidentEventsRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT id, incident_id, kind, actor_id, actor_email, message, created_at FROM incident_events WHERE incident_id = ?1 ORDER BY created_at ASC, id ASC",
+        );
+    let stmt = stmt.bind(&[incident_id.into()])?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListIncidentEventsRow>()?;
+    Ok(rows)
+}
@@ -4,23 +4,27 @@ use worker::Result;
 pub async fn upsert_dispatch_pending(
     d1: &D1Database,
     monitor_id: &str,
+    relay_id: &str,
     dispatch_id: &str,
     org_id: &str,
     scheduled_for_ts: i64,
     updated_at: i64,
+    redispatches: i64,
 ) -> Result<()> {
     let stmt = d1
         .prepare(
-            "INSERT INTO monitor_dispatch_hot (monitor_id, dispatch_id, org_id, status, scheduled_for_ts, dispatched_at_ts, completed_at_ts, runner_colo, error, updated_at) VALUES (?1, ?2, ?3, 'pending', ?4, NULL, NULL, NULL, NULL, ?5) ON CONFLICT(monitor_id) DO UPDATE SET dispatch_id = excluded.dispatch_id, org_id = excluded.org_id, status = 'pending', scheduled_for_ts = excluded.scheduled_for_ts, dispatched_at_ts = NULL, completed_at_ts = NULL, runner_colo = NULL, error = NULL, updated_at = excluded.updated_at",
+            "INSERT INTO monitor_dispatch_hot (monitor_id, relay_id, dispatch_id, org_id, status, scheduled_for_ts, dispatched_at_ts, completed_at_ts, runner_colo, error, redispatches, updated_at) VALUES (?1, ?2, ?3, ?4, 'pending', ?5, ?6, NULL, NULL, NULL, ?7, ?6) ON CONFLICT(monitor_id, relay_id) DO UPDATE SET dispatch_id = excluded.dispatch_id, org_id = excluded.org_id, status = 'pending', scheduled_for_ts = excluded.scheduled_for_ts, dispatched_at_ts = excluded.dispatched_at_ts, completed_at_ts = NULL, runner_colo = NULL, error = NULL, redispatches = excluded.redispatches, updated_at = excluded.updated_at",
         );
     let stmt = stmt
         .bind(
             &[
                 monitor_id.into(),
+                relay_id.into(),
                 dispatch_id.into(),
                 org_id.into(),
                 (scheduled_for_ts as f64).into(),
                 (updated_at as f64).into(),
+                (redispatches as f64).into(),
             ],
         )?;
     stmt.run().await?;
@@ -86,3 +90,197 @@ pub async fn finalize_dispatch(
     stmt.run().await?;
     Ok(())
 }
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListStaleDispatchesRow {
+    pub monitor_id: String,
+    pub relay_id: String,
+    pub dispatch_id: String,
+    pub dispatch_status: String,
+    pub scheduled_for_ts: i64,
+    pub dispatched_at_ts: Option<i64>,
+    pub redispatches: i64,
+    pub runner_colo: Option<String>,
+    pub kind: String,
+    pub config_json: String,
+    pub status: String,
+    pub first_checked_at: Option<i64>,
+    pub last_failed_at: Option<i64>,
+    pub placement: String,
+    pub relays_json: Option<String>,
+}
+#[tracing::instrument(name = "d1c.list_stale_dispatches", skip(d1))]
+pub async fn list_stale_dispatches(
+    d1: &D1Database,
+    org_id: &str,
+    started_before: i64,
+    limit: i64,
+) -> Result<Vec<ListStaleDispatchesRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT h.monitor_id, h.relay_id, h.dispatch_id, h.status AS dispatch_status, h.scheduled_for_ts, h.dispatched_at_ts, h.redispatches, h.runner_colo, m.kind, m.config_json, m.status, m.first_checked_at, m.last_failed_at, m.placement, (SELECT json_group_array(json_object('relay_id', mr.relay_id, 'object_id', r.durable_object_id, 'position', mr.position)) FROM monitor_relays AS mr JOIN relays AS r ON r.id = mr.relay_id WHERE mr.monitor_id = m.id AND r.enabled = 1) AS relays_json FROM monitor_dispatch_hot AS h JOIN monitors AS m ON m.id = h.monitor_id WHERE h.org_id = ?1 AND h.status IN ('pending', 'running') AND h.dispatched_at_ts < ?2 ORDER BY h.scheduled_for_ts ASC LIMIT ?3",
+        );
+    let stmt = stmt
+        .bind(&[org_id.into(), (started_before as f64).into(), (limit as f64).into()])?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListStaleDispatchesRow>()?;
+    Ok(rows)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct MarkDispatchTimedOutRow {
+    pub monitor_id: String,
+}
+#[tracing::instrument(name = "d1c.mark_dispatch_timed_out", skip(d1))]
+pub async fn mark_dispatch_timed_out(
+    d1: &D1Database,
+    completed_at_ts: i64,
+    error: &str,
+    updated_at: i64,
+    monitor_id: &str,
+    dispatch_id: &str,
+) -> Result<Option<MarkDispatchTimedOutRow>> {
+    let stmt = d1
+        .prepare(
+            "UPDATE monitor_dispatch_hot SET status = 'timed_out', completed_at_ts = ?1, error = ?2, updated_at = ?3 WHERE monitor_id = ?4 AND dispatch_id = ?5 AND status IN ('pending', 'running') RETURNING monitor_id",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                (completed_at_ts as f64).into(),
+                error.into(),
+                (updated_at as f64).into(),
+                monitor_id.into(),
+                dispatch_id.into(),
+            ],
+        )?;
+    let result = stmt.first::<MarkDispatchTimedOutRow>(None).await?;
+    Ok(result)
+}
+#[tracing::instrument(name = "d1c.mark_dispatch_failed", skip(d1))]
+pub async fn mark_dispatch_failed(
+    d1: &D1Database,
+    completed_at_ts: i64,
+    error: &str,
+    updated_at: i64,
+    monitor_id: &str,
+    dispatch_id: &str,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "UPDATE monitor_dispatch_hot SET status = 'failed', completed_at_ts = ?1, error = ?2, updated_at = ?3 WHERE monitor_id = ?4 AND dispatch_id = ?5 AND status IN ('pending', 'running')",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                (completed_at_ts as f64).into(),
+                error.into(),
+                (updated_at as f64).into(),
+                monitor_id.into(),
+                dispatch_id.into(),
+            ],
+        )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.record_dispatch_verdict", skip(d1))]
+pub async fn record_dispatch_verdict(
+    d1: &D1Database,
+    verdict: &str,
+    verdict_window_ts: i64,
+    verdict_region: &str,
+    verdict_colo: &str,
+    verdict_latency_ms: i64,
+    verdict_error: Option<&str>,
+    verdict_at: i64,
+    monitor_id: &str,
+    relay_id: &str,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "UPDATE monitor_dispatch_hot SET verdict = ?1, verdict_window_ts = ?2, verdict_region = ?3, verdict_colo = ?4, verdict_latency_ms = ?5, verdict_error = ?6, verdict_at = ?7 WHERE monitor_id = ?8 AND relay_id = ?9",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                verdict.into(),
+                (verdict_window_ts as f64).into(),
+                verdict_region.into(),
+                verdict_colo.into(),
+                (verdict_latency_ms as f64).into(),
+                match verdict_error {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                (verdict_at as f64).into(),
+                monitor_id.into(),
+                relay_id.into(),
+            ],
+        )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.count_down_verdicts", skip(d1))]
+pub async fn count_down_verdicts(
+    d1: &D1Database,
+    monitor_id: &str,
+    verdict_window_ts: i64,
+) -> Result<Option<i64>> {
+    let stmt = d1
+        .prepare(
+            "SELECT COUNT(*) AS total FROM monitor_dispatch_hot WHERE monitor_id = ?1 AND verdict_window_ts = ?2 AND verdict = 'down'",
+        );
+    let stmt = stmt.bind(&[monitor_id.into(), (verdict_window_ts as f64).into()])?;
+    let result = stmt.first::<i64>(Some("total")).await?;
+    Ok(result)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListRegionVerdictsForOrgRow {
+    pub monitor_id: String,
+    pub relay_id: String,
+    pub verdict: Option<String>,
+    pub verdict_window_ts: Option<i64>,
+    pub verdict_region: Option<String>,
+    pub verdict_colo: Option<String>,
+    pub verdict_latency_ms: Option<i64>,
+    pub verdict_error: Option<String>,
+    pub verdict_at: Option<i64>,
+}
+#[tracing::instrument(name = "d1c.list_region_verdicts_for_org", skip(d1))]
+pub async fn list_region_verdicts_for_org(
+    d1: &D1Database,
+    org_id: &str,
+) -> Result<Vec<ListRegionVerdictsForOrgRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT h.monitor_id, h.relay_id, h.verdict, h.verdict_window_ts, h.verdict_region, h.verdict_colo, h.verdict_latency_ms, h.verdict_error, h.verdict_at FROM monitor_dispatch_hot AS h WHERE h.org_id = ?1 AND h.verdict IS NOT NULL ORDER BY h.monitor_id, h.relay_id",
+        );
+    let stmt = stmt.bind(&[org_id.into()])?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListRegionVerdictsForOrgRow>()?;
+    Ok(rows)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListRegionVerdictsForMonitorRow {
+    pub monitor_id: String,
+    pub relay_id: String,
+    pub verdict: Option<String>,
+    pub verdict_window_ts: Option<i64>,
+    pub verdict_region: Option<String>,
+    pub verdict_colo: Option<String>,
+    pub verdict_latency_ms: Option<i64>,
+    pub verdict_error: Option<String>,
+    pub verdict_at: Option<i64>,
+}
+#[tracing::instrument(name = "d1c.list_region_verdicts_for_monitor", skip(d1))]
+pub async fn list_region_verdicts_for_monitor(
+    d1: &D1Database,
+    monitor_id: &str,
+) -> Result<Vec<ListRegionVerdictsForMonitorRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT h.monitor_id, h.relay_id, h.verdict, h.verdict_window_ts, h.verdict_region, h.verdict_colo, h.verdict_latency_ms, h.verdict_error, h.verdict_at FROM monitor_dispatch_hot AS h WHERE h.monitor_id = ?1 AND h.verdict IS NOT NULL ORDER BY h.relay_id",
+        );
+    let stmt = stmt.bind(&[monitor_id.into()])?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListRegionVerdictsForMonitorRow>()?;
+    Ok(rows)
+}
@@ -49,6 +49,7 @@ pub struct GetMonitorByIdRow {
     pub rt_ms: Option<i64>,
     pub region: Option<String>,
     pub relay_id: Option<String>,
+    pub placement: String,
     pub last_error: Option<String>,
     pub next_run_at: Option<i64>,
     pub created_at: i64,
@@ -62,7 +63,7 @@ pub async fn get_monitor_by_id(
 ) -> Result<Option<GetMonitorByIdRow>> {
     let stmt = d1
         .prepare(
-            "SELECT id, org_id, name, kind, enabled, config_json, status, last_checked_at, last_failed_at, first_checked_at, rt_ms, region, relay_id, last_error, next_run_at, created_at, updated_at FROM monitors WHERE id = ?1 AND org_id = ?2",
+            "SELECT id, org_id, name, kind, enabled, config_json, status, last_checked_at, last_failed_at, first_checked_at, rt_ms, region, relay_id, placement, last_error, next_run_at, created_at, updated_at FROM monitors WHERE id = ?1 AND org_id = ?2",
         );
     let stmt = stmt.bind(&[id.into(), org_id.into()])?;
     let result = stmt.first::<GetMonitorByIdRow>(None).await?;
@@ -83,6 +84,7 @@ pub struct GetMonitorsByOrgIdRow {
     pub rt_ms: Option<i64>,
     pub region: Option<String>,
     pub relay_id: Option<String>,
+    pub placement: String,
     pub last_error: Option<String>,
     pub next_run_at: Option<i64>,
     pub created_at: i64,
@@ -95,7 +97,7 @@ pub async fn get_monitors_by_org_id(
 ) -> Result<Vec<GetMonitorsByOrgIdRow>> {
     let stmt = d1
         .prepare(
-            "SELECT id, org_id, name, kind, enabled, config_json, status, last_checked_at, last_failed_at, first_checked_at, rt_ms, region, relay_id, last_error, next_run_at, created_at, updated_at FROM monitors WHERE org_id = ?1 ORDER BY created_at DESC",
+            "SELECT id, org_id, name, kind, enabled, config_json, status, last_checked_at, last_failed_at, first_checked_at, rt_ms, region, relay_id, placement, last_error, next_run_at, created_at, updated_at FROM monitors WHERE org_id = ?1 ORDER BY created_at DESC",
         );
     let stmt = stmt.bind(&[org_id.into()])?;
     let result = stmt.all().await?;
@@ -111,7 +113,8 @@ pub struct ListDueMonitorsRow {
     pub first_checked_at: Option<i64>,
     pub last_failed_at: Option<i64>,
     pub next_run_at: Option<i64>,
-    pub relay_id: Option<String>,
+    pub placement: String,
+    pub relays_json: Option<String>,
 }
 #[tracing::instrument(name = "d1c.list_due_monitors", skip(d1))]
 pub async fn list_due_monitors(
@@ -122,7 +125,7 @@ pub async fn list_due_monitors(
 ) -> Result<Vec<ListDueMonitorsRow>> {
     let stmt = d1
         .prepare(
-            "SELECT id, kind, config_json, status, first_checked_at, last_failed_at, next_run_at, relay_id FROM monitors WHERE org_id = ?1 AND enabled = 1 AND (?2 IS NULL OR next_run_at IS NULL OR next_run_at <= ?2) ORDER BY COALESCE(next_run_at, 0) ASC LIMIT ?3",
+            "SELECT m.id, m.kind, m.config_json, m.status, m.first_checked_at, m.last_failed_at, m.next_run_at, m.placement, (SELECT json_group_array(json_object('relay_id', mr.relay_id, 'object_id', r.durable_object_id, 'position', mr.position)) FROM monitor_relays AS mr JOIN relays AS r ON r.id = mr.relay_id WHERE mr.monitor_id = m.id AND r.enabled = 1) AS relays_json FROM monitors AS m WHERE m.org_id = ?1 AND m.enabled = 1 AND (?2 IS NULL OR m.next_run_at IS NULL OR m.next_run_at <= ?2) ORDER BY COALESCE(m.next_run_at, 0) ASC LIMIT ?3",
         );
     let stmt = stmt
         .bind(
@@ -224,20 +227,174 @@ pub async fn update_monitor_next_run_at(
     stmt.run().await?;
     Ok(())
 }
-#[tracing::instrument(name = "d1c.set_monitor_relay", skip(d1))]
-pub async fn set_monitor_relay(
+pub fn defer_monitor_next_run_at_stmt(
+    d1: &D1Database,
+    next_run_at: i64,
+    updated_at: i64,
+    id: &str,
+    org_id: &str,
+) -> Result<worker::D1PreparedStatement> {
+    let stmt = d1
+        .prepare(
+            "UPDATE monitors SET next_run_at = ?1, updated_at = ?2 WHERE id = ?3 AND org_id = ?4",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                (next_run_at as f64).into(),
+                (updated_at as f64).into(),
+                id.into(),
+                org_id.into(),
+            ],
+        )?;
+    Ok(stmt)
+}
+#[tracing::instrument(name = "d1c.defer_monitor_next_run_at", skip(d1))]
+pub async fn defer_monitor_next_run_at(
+    d1: &D1Database,
+    next_run_at: i64,
+    updated_at: i64,
+    id: &str,
+    org_id: &str,
+) -> Result<()> {
+    let stmt = defer_monitor_next_run_at_stmt(d1, next_run_at, updated_at, id, org_id)?;
+    stmt.run().await?;
+    Ok(())
+}
+pub fn set_monitor_placement_stmt(
     d1: &D1Database,
+    placement: &str,
+    relay_id: &str,
+    updated_at: i64,
+    id: &str,
+    org_id: &str,
+) -> Result<worker::D1PreparedStatement> {
+    let stmt = d1
+        .prepare(
+            "UPDATE monitors SET placement = ?1, relay_id = ?2, updated_at = ?3 WHERE id = ?4 AND org_id = ?5",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                placement.into(),
+                relay_id.into(),
+                (updated_at as f64).into(),
+                id.into(),
+                org_id.into(),
+            ],
+        )?;
+    Ok(stmt)
+}
+#[tracing::instrument(name = "d1c.set_monitor_placement", skip(d1))]
+pub async fn set_monitor_placement(
+    d1: &D1Database,
+    placement: &str,
     relay_id: &str,
     updated_at: i64,
     id: &str,
     org_id: &str,
 ) -> Result<()> {
+    let stmt = set_monitor_placement_stmt(
+        d1,
+        placement,
+        relay_id,
+        updated_at,
+        id,
+        org_id,
+    )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListMonitorRelaysRow {
+    pub monitor_id: String,
+    pub relay_id: String,
+    pub position: i64,
+}
+#[tracing::instrument(name = "d1c.list_monitor_relays", skip(d1))]
+pub async fn list_monitor_relays(
+    d1: &D1Database,
+    monitor_id: &str,
+) -> Result<Vec<ListMonitorRelaysRow>> {
     let stmt = d1
         .prepare(
-            "UPDATE monitors SET relay_id = ?1, updated_at = ?2 WHERE id = ?3 AND org_id = ?4",
+            "SELECT monitor_id, relay_id, position FROM monitor_relays WHERE monitor_id = ?1 ORDER BY position ASC",
+        );
+    let stmt = stmt.bind(&[monitor_id.into()])?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListMonitorRelaysRow>()?;
+    Ok(rows)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListMonitorRelaysForOrgRow {
+    pub monitor_id: String,
+    pub relay_id: String,
+    pub position: i64,
+}
+#[tracing::instrument(name = "d1c.list_monitor_relays_for_org", skip(d1))]
+pub async fn list_monitor_relays_for_org(
+    d1: &D1Database,
+    org_id: &str,
+) -> Result<Vec<ListMonitorRelaysForOrgRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT mr.monitor_id, mr.relay_id, mr.position FROM monitor_relays AS mr JOIN monitors AS m ON m.id = mr.monitor_id WHERE m.org_id = ?1 ORDER BY mr.monitor_id, mr.position ASC",
+        );
+    let stmt = stmt.bind(&[org_id.into()])?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListMonitorRelaysForOrgRow>()?;
+    Ok(rows)
+}
+pub fn delete_monitor_relays_stmt(
+    d1: &D1Database,
+    monitor_id: &str,
+) -> Result<worker::D1PreparedStatement> {
+    let stmt = d1.prepare("DELETE FROM monitor_relays WHERE monitor_id = ?1");
+    let stmt = stmt.bind(&[monitor_id.into()])?;
+    Ok(stmt)
+}
+#[tracing::instrument(name = "d1c.delete_monitor_relays", skip(d1))]
+pub async fn delete_monitor_relays(d1: &D1Database, monitor_id: &str) -> Result<()> {
+    let stmt = delete_monitor_relays_stmt(d1, monitor_id)?;
+    stmt.run().await?;
+    Ok(())
+}
+pub fn insert_monitor_relay_stmt(
+    d1: &D1Database,
+    monitor_id: &str,
+    relay_id: &str,
+    position: i64,
+) -> Result<worker::D1PreparedStatement> {
+    let stmt = d1
+        .prepare(
+            "INSERT INTO monitor_relays (monitor_id, relay_id, position) VALUES (?1, ?2, ?3)",
         );
     let stmt = stmt
-        .bind(&[relay_id.into(), (updated_at as f64).into(), id.into(), org_id.into()])?;
+        .bind(&[monitor_id.into(), relay_id.into(), (position as f64).into()])?;
+    Ok(stmt)
+}
+#[tracing::instrument(name = "d1c.insert_monitor_relay", skip(d1))]
+pub async fn insert_monitor_relay(
+    d1: &D1Database,
+    monitor_id: &str,
+    relay_id: &str,
+    position: i64,
+) -> Result<()> {
+    let stmt = insert_monitor_relay_stmt(d1, monitor_id, relay_id, position)?;
+    stmt.run().await?;
+    Ok(())
+}
+pub fn delete_monitor_hot_rows_stmt(
+    d1: &D1Database,
+    monitor_id: &str,
+) -> Result<worker::D1PreparedStatement> {
+    let stmt = d1.prepare("DELETE FROM monitor_dispatch_hot WHERE monitor_id = ?1");
+    let stmt = stmt.bind(&[monitor_id.into()])?;
+    Ok(stmt)
+}
+#[tracing::instrument(name = "d1c.delete_monitor_hot_rows", skip(d1))]
+pub async fn delete_monitor_hot_rows(d1: &D1Database, monitor_id: &str) -> Result<()> {
+    let stmt = delete_monitor_hot_rows_stmt(d1, monitor_id)?;
     stmt.run().await?;
     Ok(())
 }
@@ -0,0 +1,342 @@
+use worker::D1Database;
+use worker::Result;
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListNotificationsForMonitorRow {
+    pub id: Option<String>,
+    pub monitor_id: String,
+    pub kind: String,
+    pub target: String,
+    pub config_json: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+#[tracing::instrument(name = "d1c.list_notifications_for_monitor", skip(d1))]
+pub async fn list_notifications_for_monitor(
+    d1: &D1Database,
+    monitor_id: &str,
+    org_id: &str,
+) -> Result<Vec<ListNotificationsForMonitorRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT n.id, n.monitor_id, n.kind, n.target, n.config_json, n.created_at, n.updated_at FROM notifications AS n JOIN monitors AS m ON m.id = n.monitor_id WHERE n.monitor_id = ?1 AND m.org_id = ?2 ORDER BY n.created_at ASC",
+        );
+    let stmt = stmt.bind(&[monitor_id.into(), org_id.into()])?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListNotificationsForMonitorRow>()?;
+    Ok(rows)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct GetNotificationForMonitorRow {
+    pub id: Option<String>,
+    pub monitor_id: String,
+    pub kind: String,
+    pub target: String,
+    pub config_json: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+#[tracing::instrument(name = "d1c.get_notification_for_monitor", skip(d1))]
+pub async fn get_notification_for_monitor(
+    d1: &D1Database,
+    id: &str,
+    monitor_id: &str,
+    org_id: &str,
+) -> Result<Option<GetNotificationForMonitorRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT n.id, n.monitor_id, n.kind, n.target, n.config_json, n.created_at, n.updated_at FROM notifications AS n JOIN monitors AS m ON m.id = n.monitor_id WHERE n.id = ?1 AND n.monitor_id = ?2 AND m.org_id = ?3 LIMIT 1",
+        );
+    let stmt = stmt.bind(&[id.into(), monitor_id.into(), org_id.into()])?;
+    let result = stmt.first::<GetNotificationForMonitorRow>(None).await?;
+    Ok(result)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListNotificationsByKindRow {
+    pub id: Option<String>,
+    pub monitor_id: String,
+    pub kind: String,
+    pub target: String,
+    pub config_json: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+#[tracing::instrument(name = "d1c.list_notifications_by_kind", skip(d1))]
+pub async fn list_notifications_by_kind(
+    d1: &D1Database,
+    monitor_id: &str,
+    kind: &str,
+) -> Result<Vec<ListNotificationsByKindRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT id, monitor_id, kind, target, config_json, created_at, updated_at FROM notifications WHERE monitor_id = ?1 AND kind = ?2",
+        );
+    let stmt = stmt.bind(&[monitor_id.into(), kind.into()])?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListNotificationsByKindRow>()?;
+    Ok(rows)
+}
+#[tracing::instrument(name = "d1c.insert_notification", skip(d1))]
+pub async fn insert_notification(
+    d1: &D1Database,
+    id: &str,
+    monitor_id: &str,
+    kind: &str,
+    target: &str,
+    config_json: &str,
+    created_at: i64,
+    updated_at: i64,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "INSERT INTO notifications (id, monitor_id, kind, target, config_json, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                id.into(),
+                monitor_id.into(),
+                kind.into(),
+                target.into(),
+                config_json.into(),
+                (created_at as f64).into(),
+                (updated_at as f64).into(),
+            ],
+        )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.update_notification", skip(d1))]
+pub async fn update_notification(
+    d1: &D1Database,
+    target: &str,
+    config_json: &str,
+    updated_at: i64,
+    id: &str,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "UPDATE notifications SET target = ?1, config_json = ?2, updated_at = ?3 WHERE id = ?4",
+        );
+    let stmt = stmt
+        .bind(
+            &[target.into(), config_json.into(), (updated_at as f64).into(), id.into()],
+        )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.delete_notification", skip(d1))]
+pub async fn delete_notification(d1: &D1Database, id: &str) -> Result<()> {
+    let stmt = d1.prepare("DELETE FROM notifications WHERE id = ?1");
+    let stmt = stmt.bind(&[id.into()])?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.insert_notification_event", skip(d1))]
+pub async fn insert_notification_event(
+    d1: &D1Database,
+    id: &str,
+    delivery_id: &str,
+    notification_id: &str,
+    incident_id: Option<&str>,
+    event: &str,
+    attempt: i64,
+    status: &str,
+    http_status: Option<i64>,
+    latency_ms: i64,
+    error: Option<&str>,
+    next_retry_at: Option<i64>,
+    payload: &str,
+    created_at: i64,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "INSERT INTO notification_events (id, delivery_id, notification_id, incident_id, event, attempt, status, http_status, latency_ms, error, next_retry_at, payload, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                id.into(),
+                delivery_id.into(),
+                notification_id.into(),
+                match incident_id {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                event.into(),
+                (attempt as f64).into(),
+                status.into(),
+                match http_status {
+                    Some(value) => (value as f64).into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                (latency_ms as f64).into(),
+                match error {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                match next_retry_at {
+                    Some(value) => (value as f64).into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                payload.into(),
+                (created_at as f64).into(),
+            ],
+        )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListNotificationEventsForOrgRow {
+    pub id: Option<String>,
+    pub delivery_id: String,
+    pub notification_id: String,
+    pub incident_id: Option<String>,
+    pub event: String,
+    pub attempt: i64,
+    pub status: String,
+    pub http_status: Option<i64>,
+    pub latency_ms: Option<i64>,
+    pub error: Option<String>,
+    pub next_retry_at: Option<i64>,
+    pub created_at: i64,
+}
+#[tracing::instrument(name = "d1c.list_notification_events_for_org", skip(d1))]
+pub async fn list_notification_events_for_org(
+    d1: &D1Database,
+    notification_id: &str,
+    org_id: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<ListNotificationEventsForOrgRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT e.id, e.delivery_id, e.notification_id, e.incident_id, e.event, e.attempt, e.status, e.http_status, e.latency_ms, e.error, e.next_retry_at, e.created_at FROM notification_events AS e JOIN notifications AS n ON n.id = e.notification_id JOIN monitors AS m ON m.id = n.monitor_id WHERE e.notification_id = ?1 AND m.org_id = ?2 ORDER BY e.created_at DESC, e.attempt DESC LIMIT ?3 OFFSET ?4",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                notification_id.into(),
+                org_id.into(),
+                (limit as f64).into(),
+                (offset as f64).into(),
+            ],
+        )?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListNotificationEventsForOrgRow>()?;
+    Ok(rows)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct GetNotificationEventForOrgRow {
+    pub id: Option<String>,
+    pub delivery_id: String,
+    pub notification_id: String,
+    pub incident_id: Option<String>,
+    pub event: String,
+    pub attempt: i64,
+    pub status: String,
+    pub http_status: Option<i64>,
+    pub latency_ms: Option<i64>,
+    pub error: Option<String>,
+    pub next_retry_at: Option<i64>,
+    pub payload: String,
+    pub created_at: i64,
+    pub target: String,
+    pub config_json: Option<String>,
+}
+#[tracing::instrument(name = "d1c.get_notification_event_for_org", skip(d1))]
+pub async fn get_notification_event_for_org(
+    d1: &D1Database,
+    id: &str,
+    notification_id: &str,
+    org_id: &str,
+) -> Result<Option<GetNotificationEventForOrgRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT e.id, e.delivery_id, e.notification_id, e.incident_id, e.event, e.attempt, e.status, e.http_status, e.latency_ms, e.error, e.next_retry_at, e.payload, e.created_at, n.target, n.config_json FROM notification_events AS e JOIN notifications AS n ON n.id = e.notification_id JOIN monitors AS m ON m.id = n.monitor_id WHERE e.id = ?1 AND e.notification_id = ?2 AND m.org_id = ?3 LIMIT 1",
+        );
+    let stmt = stmt.bind(&[id.into(), notification_id.into(), org_id.into()])?;
+    let result = stmt.first::<GetNotificationEventForOrgRow>(None).await?;
+    Ok(result)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct GetLatestDeliveryAttemptRow {
+    pub attempt: i64,
+    pub status: String,
+    pub next_retry_at: Option<i64>,
+}
+#[tracing::instrument(name = "d1c.get_latest_delivery_attempt", skip(d1))]
+pub async fn get_latest_delivery_attempt(
+    d1: &D1Database,
+    delivery_id: &str,
+) -> Result<Option<GetLatestDeliveryAttemptRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT attempt, status, next_retry_at FROM notification_events WHERE delivery_id = ?1 ORDER BY attempt DESC LIMIT 1",
+        );
+    let stmt = stmt.bind(&[delivery_id.into()])?;
+    let result = stmt.first::<GetLatestDeliveryAttemptRow>(None).await?;
+    Ok(result)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct GetNotificationOwnerRow {
+    pub id: Option<String>,
+    pub monitor_id: String,
+}
+#[tracing::instrument(name = "d1c.get_notification_owner", skip(d1))]
+pub async fn get_notification_owner(
+    d1: &D1Database,
+    id: &str,
+    org_id: &str,
+) -> Result<Option<GetNotificationOwnerRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT n.id, n.monitor_id FROM notifications AS n JOIN monitors AS m ON m.id = n.monitor_id WHERE n.id = ?1 AND m.org_id = ?2 LIMIT 1",
+        );
+    let stmt = stmt.bind(&[id.into(), org_id.into()])?;
+    let result = stmt.first::<GetNotificationOwnerRow>(None).await?;
+    Ok(result)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListDueDeliveryRetriesRow {
+    pub id: Option<String>,
+    pub delivery_id: String,
+    pub notification_id: String,
+    pub incident_id: Option<String>,
+    pub event: String,
+    pub attempt: i64,
+    pub payload: String,
+    pub target: String,
+    pub config_json: Option<String>,
+}
+#[tracing::instrument(name = "d1c.list_due_delivery_retries", skip(d1))]
+pub async fn list_due_delivery_retries(
+    d1: &D1Database,
+    now: i64,
+    limit: i64,
+) -> Result<Vec<ListDueDeliveryRetriesRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT e.id, e.delivery_id, e.notification_id, e.incident_id, e.event, e.attempt, e.payload, n.target, n.config_json FROM notification_events AS e JOIN notifications AS n ON n.id = e.notification_id WHERE e.next_retry_at IS NOT NULL AND e.next_retry_at <= ?1 ORDER BY e.next_retry_at LIMIT ?2",
+        );
+    let stmt = stmt.bind(&[(now as f64).into(), (limit as f64).into()])?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListDueDeliveryRetriesRow>()?;
+    Ok(rows)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ClaimDeliveryRetryRow {
+    pub id: Option<String>,
+}
+#[tracing::instrument(name = "d1c.claim_delivery_retry", skip(d1))]
+pub async fn claim_delivery_retry(
+    d1: &D1Database,
+    id: &str,
+) -> Result<Option<ClaimDeliveryRetryRow>> {
+    let stmt = d1
+        .prepare(
+            "UPDATE notification_events SET next_retry_at = NULL WHERE id = ?1 AND next_retry_at IS NOT NULL RETURNING id",
+        );
+    let stmt = stmt.bind(&[id.into()])?;
+    let result = stmt.first::<ClaimDeliveryRetryRow>(None).await?;
+    Ok(result)
+}
@@ -108,3 +108,217 @@ pub async fn list_relays(d1: &D1Database) -> Result<Vec<ListRelaysRow>> {
     let rows = result.results::<ListRelaysRow>()?;
     Ok(rows)
 }
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct UpdateRelayRow {
+    pub id: Option<String>,
+    pub slug: String,
+    pub name: String,
+    pub location_hint: String,
+    pub jurisdiction: String,
+    pub durable_object_id: String,
+    pub enabled: i64,
+    pub last_bootstrapped_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+pub fn update_relay_stmt(
+    d1: &D1Database,
+    name: Option<&str>,
+    enabled: Option<i64>,
+    updated_at: i64,
+    id: &str,
+) -> Result<worker::D1PreparedStatement> {
+    let stmt = d1
+        .prepare(
+            "UPDATE relays SET name = COALESCE(?1, name), enabled = COALESCE(?2, enabled), updated_at = ?3 WHERE id = ?4 RETURNING *",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                match name {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                match enabled {
+                    Some(value) => (value as f64).into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                (updated_at as f64).into(),
+                id.into(),
+            ],
+        )?;
+    Ok(stmt)
+}
+#[tracing::instrument(name = "d1c.update_relay", skip(d1))]
+pub async fn update_relay(
+    d1: &D1Database,
+    name: Option<&str>,
+    enabled: Option<i64>,
+    updated_at: i64,
+    id: &str,
+) -> Result<Option<UpdateRelayRow>> {
+    let stmt = update_relay_stmt(d1, name, enabled, updated_at, id)?;
+    let result = stmt.first::<UpdateRelayRow>(None).await?;
+    Ok(result)
+}
+#[tracing::instrument(name = "d1c.record_relay_bootstrapped", skip(d1))]
+pub async fn record_relay_bootstrapped(
+    d1: &D1Database,
+    last_bootstrapped_at: i64,
+    updated_at: i64,
+    id: &str,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "UPDATE relays SET last_bootstrapped_at = ?1, last_error = NULL, updated_at = ?2 WHERE id = ?3",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                (last_bootstrapped_at as f64).into(),
+                (updated_at as f64).into(),
+                id.into(),
+            ],
+        )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.record_relay_health", skip(d1))]
+pub async fn record_relay_health(
+    d1: &D1Database,
+    last_error: Option<&str>,
+    updated_at: i64,
+    id: &str,
+) -> Result<()> {
+    let stmt = d1
+        .prepare("UPDATE relays SET last_error = ?1, updated_at = ?2 WHERE id = ?3");
+    let stmt = stmt
+        .bind(
+            &[
+                match last_error {
+                    Some(value) => value.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                (updated_at as f64).into(),
+                id.into(),
+            ],
+        )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.count_relay_monitors", skip(d1))]
+pub async fn count_relay_monitors(
+    d1: &D1Database,
+    relay_id: &str,
+) -> Result<Option<i64>> {
+    let stmt = d1
+        .prepare(
+            "SELECT COUNT(*) AS total FROM monitors WHERE relay_id = ?1 OR id IN (SELECT monitor_id FROM monitor_relays WHERE relay_id = ?1)",
+        );
+    let stmt = stmt.bind(&[relay_id.into()])?;
+    let result = stmt.first::<i64>(Some("total")).await?;
+    Ok(result)
+}
+#[tracing::instrument(name = "d1c.count_relay_open_incidents", skip(d1))]
+pub async fn count_relay_open_incidents(
+    d1: &D1Database,
+    relay_id: &str,
+) -> Result<Option<i64>> {
+    let stmt = d1
+        .prepare(
+            "SELECT COUNT(*) AS total FROM incidents WHERE status <> 'closed' AND monitor_id IN (SELECT monitor_id FROM monitor_dispatch_hot WHERE relay_id = ?1)",
+        );
+    let stmt = stmt.bind(&[relay_id.into()])?;
+    let result = stmt.first::<i64>(Some("total")).await?;
+    Ok(result)
+}
+pub fn reassign_monitor_relays_stmt(
+    d1: &D1Database,
+    to_relay_id: &str,
+    from_relay_id: &str,
+) -> Result<worker::D1PreparedStatement> {
+    let stmt = d1
+        .prepare(
+            "INSERT OR IGNORE INTO monitor_relays (monitor_id, relay_id, position) SELECT monitor_id, ?1, position FROM monitor_relays WHERE relay_id = ?2",
+        );
+    let stmt = stmt.bind(&[to_relay_id.into(), from_relay_id.into()])?;
+    Ok(stmt)
+}
+#[tracing::instrument(name = "d1c.reassign_monitor_relays", skip(d1))]
+pub async fn reassign_monitor_relays(
+    d1: &D1Database,
+    to_relay_id: &str,
+    from_relay_id: &str,
+) -> Result<()> {
+    let stmt = reassign_monitor_relays_stmt(d1, to_relay_id, from_relay_id)?;
+    stmt.run().await?;
+    Ok(())
+}
+pub fn reassign_primary_relay_stmt(
+    d1: &D1Database,
+    to_relay_id: &str,
+    updated_at: i64,
+    from_relay_id: &str,
+) -> Result<worker::D1PreparedStatement> {
+    let stmt = d1
+        .prepare(
+            "UPDATE monitors SET relay_id = ?1, updated_at = ?2 WHERE relay_id = ?3",
+        );
+    let stmt = stmt
+        .bind(&[to_relay_id.into(), (updated_at as f64).into(), from_relay_id.into()])?;
+    Ok(stmt)
+}
+#[tracing::instrument(name = "d1c.reassign_primary_relay", skip(d1))]
+pub async fn reassign_primary_relay(
+    d1: &D1Database,
+    to_relay_id: &str,
+    updated_at: i64,
+    from_relay_id: &str,
+) -> Result<()> {
+    let stmt = reassign_primary_relay_stmt(d1, to_relay_id, updated_at, from_relay_id)?;
+    stmt.run().await?;
+    Ok(())
+}
+pub fn delete_relay_assignments_stmt(
+    d1: &D1Database,
+    relay_id: &str,
+) -> Result<worker::D1PreparedStatement> {
+    let stmt = d1.prepare("DELETE FROM monitor_relays WHERE relay_id = ?1");
+    let stmt = stmt.bind(&[relay_id.into()])?;
+    Ok(stmt)
+}
+#[tracing::instrument(name = "d1c.delete_relay_assignments", skip(d1))]
+pub async fn delete_relay_assignments(d1: &D1Database, relay_id: &str) -> Result<()> {
+    let stmt = delete_relay_assignments_stmt(d1, relay_id)?;
+    stmt.run().await?;
+    Ok(())
+}
+pub fn delete_relay_hot_rows_stmt(
+    d1: &D1Database,
+    relay_id: &str,
+) -> Result<worker::D1PreparedStatement> {
+    let stmt = d1.prepare("DELETE FROM monitor_dispatch_hot WHERE relay_id = ?1");
+    let stmt = stmt.bind(&[relay_id.into()])?;
+    Ok(stmt)
+}
+#[tracing::instrument(name = "d1c.delete_relay_hot_rows", skip(d1))]
+pub async fn delete_relay_hot_rows(d1: &D1Database, relay_id: &str) -> Result<()> {
+    let stmt = delete_relay_hot_rows_stmt(d1, relay_id)?;
+    stmt.run().await?;
+    Ok(())
+}
+pub fn delete_relay_stmt(
+    d1: &D1Database,
+    id: &str,
+) -> Result<worker::D1PreparedStatement> {
+    let stmt = d1.prepare("DELETE FROM relays WHERE id = ?1");
+    let stmt = stmt.bind(&[id.into()])?;
+    Ok(stmt)
+}
+#[tracing::instrument(name = "d1c.delete_relay", skip(d1))]
+pub async fn delete_relay(d1: &D1Database, id: &str) -> Result<()> {
+    let stmt = delete_relay_stmt(d1, id)?;
+    stmt.run().await?;
+    Ok(())
+}
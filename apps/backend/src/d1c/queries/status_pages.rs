@@ -0,0 +1,187 @@
+use worker::D1Database;
+use worker::Result;
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct GetStatusPageBySlugRow {
+    pub id: Option<String>,
+    pub org_id: String,
+    pub slug: String,
+    pub title: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+#[tracing::instrument(name = "d1c.get_status_page_by_slug", skip(d1))]
+pub async fn get_status_page_by_slug(
+    d1: &D1Database,
+    slug: &str,
+) -> Result<Option<GetStatusPageBySlugRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT id, org_id, slug, title, created_at, updated_at FROM status_pages WHERE slug = ?1 LIMIT 1",
+        );
+    let stmt = stmt.bind(&[slug.into()])?;
+    let result = stmt.first::<GetStatusPageBySlugRow>(None).await?;
+    Ok(result)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct GetStatusPageForOrgRow {
+    pub id: Option<String>,
+    pub org_id: String,
+    pub slug: String,
+    pub title: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+#[tracing::instrument(name = "d1c.get_status_page_for_org", skip(d1))]
+pub async fn get_status_page_for_org(
+    d1: &D1Database,
+    id: &str,
+    org_id: &str,
+) -> Result<Option<GetStatusPageForOrgRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT id, org_id, slug, title, created_at, updated_at FROM status_pages WHERE id = ?1 AND org_id = ?2 LIMIT 1",
+        );
+    let stmt = stmt.bind(&[id.into(), org_id.into()])?;
+    let result = stmt.first::<GetStatusPageForOrgRow>(None).await?;
+    Ok(result)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListStatusPagesForOrgRow {
+    pub id: Option<String>,
+    pub org_id: String,
+    pub slug: String,
+    pub title: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+#[tracing::instrument(name = "d1c.list_status_pages_for_org", skip(d1))]
+pub async fn list_status_pages_for_org(
+    d1: &D1Database,
+    org_id: &str,
+) -> Result<Vec<ListStatusPagesForOrgRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT id, org_id, slug, title, created_at, updated_at FROM status_pages WHERE org_id = ?1 ORDER BY created_at ASC",
+        );
+    let stmt = stmt.bind(&[org_id.into()])?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListStatusPagesForOrgRow>()?;
+    Ok(rows)
+}
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ListStatusPageComponentsRow {
+    pub monitor_id: String,
+    pub display_name: String,
+    pub position: i64,
+    pub status: String,
+    pub last_checked_at: Option<i64>,
+}
+#[tracing::instrument(name = "d1c.list_status_page_components", skip(d1))]
+pub async fn list_status_page_components(
+    d1: &D1Database,
+    status_page_id: &str,
+) -> Result<Vec<ListStatusPageComponentsRow>> {
+    let stmt = d1
+        .prepare(
+            "SELECT spm.monitor_id, spm.display_name, spm.position, m.status, m.last_checked_at FROM status_page_monitors AS spm JOIN monitors AS m ON m.id = spm.monitor_id WHERE spm.status_page_id = ?1 ORDER BY spm.position ASC, spm.display_name ASC",
+        );
+    let stmt = stmt.bind(&[status_page_id.into()])?;
+    let result = stmt.all().await?;
+    let rows = result.results::<ListStatusPageComponentsRow>()?;
+    Ok(rows)
+}
+#[tracing::instrument(name = "d1c.insert_status_page", skip(d1))]
+pub async fn insert_status_page(
+    d1: &D1Database,
+    id: &str,
+    org_id: &str,
+    slug: &str,
+    title: &str,
+    created_at: i64,
+    updated_at: i64,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "INSERT INTO status_pages (id, org_id, slug, title, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                id.into(),
+                org_id.into(),
+                slug.into(),
+                title.into(),
+                (created_at as f64).into(),
+                (updated_at as f64).into(),
+            ],
+        )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.update_status_page", skip(d1))]
+pub async fn update_status_page(
+    d1: &D1Database,
+    slug: &str,
+    title: &str,
+    updated_at: i64,
+    id: &str,
+    org_id: &str,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "UPDATE status_pages SET slug = ?1, title = ?2, updated_at = ?3 WHERE id = ?4 AND org_id = ?5",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                slug.into(),
+                title.into(),
+                (updated_at as f64).into(),
+                id.into(),
+                org_id.into(),
+            ],
+        )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.delete_status_page", skip(d1))]
+pub async fn delete_status_page(d1: &D1Database, id: &str, org_id: &str) -> Result<()> {
+    let stmt = d1.prepare("DELETE FROM status_pages WHERE id = ?1 AND org_id = ?2");
+    let stmt = stmt.bind(&[id.into(), org_id.into()])?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.insert_status_page_monitor", skip(d1))]
+pub async fn insert_status_page_monitor(
+    d1: &D1Database,
+    status_page_id: &str,
+    monitor_id: &str,
+    display_name: &str,
+    position: i64,
+) -> Result<()> {
+    let stmt = d1
+        .prepare(
+            "INSERT INTO status_page_monitors (status_page_id, monitor_id, display_name, position) VALUES (?1, ?2, ?3, ?4)",
+        );
+    let stmt = stmt
+        .bind(
+            &[
+                status_page_id.into(),
+                monitor_id.into(),
+                display_name.into(),
+                (position as f64).into(),
+            ],
+        )?;
+    stmt.run().await?;
+    Ok(())
+}
+#[tracing::instrument(name = "d1c.clear_status_page_monitors", skip(d1))]
+pub async fn clear_status_page_monitors(
+    d1: &D1Database,
+    status_page_id: &str,
+) -> Result<()> {
+    let stmt = d1.prepare("DELETE FROM status_page_monitors WHERE status_page_id = ?1");
+    let stmt = stmt.bind(&[status_page_id.into()])?;
+    stmt.run().await?;
+    Ok(())
+}
@@ -1,25 +1,36 @@
 use worker::{D1Database, Result};
 
 use crate::d1c::queries::monitor_dispatches::{
-    finalize_dispatch as finalize_dispatch_query,
-    mark_dispatch_running as mark_dispatch_running_query, upsert_dispatch_pending,
+    count_down_verdicts, finalize_dispatch as finalize_dispatch_query,
+    mark_dispatch_failed as mark_dispatch_failed_query,
+    mark_dispatch_running as mark_dispatch_running_query,
+    mark_dispatch_timed_out as mark_dispatch_timed_out_query, record_dispatch_verdict,
+    upsert_dispatch_pending,
 };
+use crate::monitors::types::HeartbeatResult;
 
+/// Records a dispatch as sent at `now_ms`. `redispatches` counts how many times the reaper
+/// has re-sent this run; a fresh run starts at zero.
+#[allow(clippy::too_many_arguments)]
 pub async fn record_pending_dispatch(
     d1: &D1Database,
     monitor_id: &str,
+    hot_relay_id: &str,
     org_id: &str,
     dispatch_id: &str,
     scheduled_for_ts: i64,
+    redispatches: i64,
     now_ms: i64,
 ) -> Result<()> {
     upsert_dispatch_pending(
         d1,
         monitor_id,
+        hot_relay_id,
         dispatch_id,
         org_id,
         scheduled_for_ts,
         now_ms,
+        redispatches,
     )
     .await
 }
@@ -61,3 +72,78 @@ pub async fn finalize_dispatch(
     )
     .await
 }
+
+/// Moves a `pending`/`running` dispatch to `timed_out`. Returns `false` when the runner
+/// finished (or the ticker replaced the dispatch) first, so the caller must not reap it.
+pub async fn mark_dispatch_timed_out(
+    d1: &D1Database,
+    monitor_id: &str,
+    dispatch_id: &str,
+    completed_at_ts: i64,
+    error: &str,
+) -> Result<bool> {
+    Ok(mark_dispatch_timed_out_query(
+        d1,
+        completed_at_ts,
+        error,
+        completed_at_ts,
+        monitor_id,
+        dispatch_id,
+    )
+    .await?
+    .is_some())
+}
+
+/// Marks a dispatch the ticker could not hand off or reap as `failed`, unless the runner got
+/// it anyway and has already moved the row on.
+pub async fn mark_dispatch_failed(
+    d1: &D1Database,
+    monitor_id: &str,
+    dispatch_id: &str,
+    completed_at_ts: i64,
+    error: &str,
+) -> Result<()> {
+    mark_dispatch_failed_query(
+        d1,
+        completed_at_ts,
+        error,
+        completed_at_ts,
+        monitor_id,
+        dispatch_id,
+    )
+    .await
+}
+
+/// Stores a slot's result on its hot row, tagged with the run (`window_ts`) it belongs to.
+pub async fn record_verdict(
+    d1: &D1Database,
+    hot_relay_id: &str,
+    window_ts: i64,
+    heartbeat: &HeartbeatResult,
+) -> Result<()> {
+    record_dispatch_verdict(
+        d1,
+        heartbeat.status.to_string().as_str(),
+        window_ts,
+        &heartbeat.region,
+        &heartbeat.colo,
+        heartbeat.latency_ms,
+        heartbeat.error.as_deref(),
+        heartbeat.timestamp,
+        &heartbeat.monitor_id,
+        hot_relay_id,
+    )
+    .await
+}
+
+/// Slots that reported `Down` for the run scheduled at `window_ts`.
+pub async fn count_down_in_window(
+    d1: &D1Database,
+    monitor_id: &str,
+    window_ts: i64,
+) -> Result<u32> {
+    Ok(count_down_verdicts(d1, monitor_id, window_ts)
+        .await?
+        .unwrap_or(0)
+        .max(0) as u32)
+}
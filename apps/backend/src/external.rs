@@ -1 +1,2 @@
 mod durable_objects;
+pub mod queues;
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 use worker::*;
 
+use crate::analytics::backend::AnalyticsBackend;
+use crate::archive::service::archive_bucket;
+use crate::cloudflare::d1::get_d1;
+use crate::cloudflare::trace::resolve_colo;
+use crate::internal::dispatch::handle_dispatch;
+use crate::internal::types::{DispatchRequest, RunnerLocation};
 use crate::utils::date::now_ms;
 
 #[derive(Default, Serialize, Deserialize)]
@@ -12,6 +18,9 @@ struct RelayState {
     jurisdiction: Option<String>,
     bootstrapped_at: Option<i64>,
     updated_at: Option<i64>,
+    /// Colo the object was found running in, resolved on the first dispatch.
+    #[serde(default)]
+    colo: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -27,7 +36,7 @@ struct RelayBootstrapPayload {
 #[durable_object]
 pub struct Relay {
     state: State,
-    _env: Env,
+    env: Env,
 }
 
 impl Relay {
@@ -55,16 +64,64 @@ impl Relay {
         state.jurisdiction = Some(payload.jurisdiction);
         state.bootstrapped_at = Some(now);
         state.updated_at = Some(now);
+        state.colo = None;
 
         self.save_state(&state).await?;
 
         Response::ok("ok")
     }
+
+    /// Runs the check from this object's colo and records it exactly as the dispatch route
+    /// would, so heartbeats carry the Relay's location instead of the Ticker's.
+    #[tracing::instrument(
+        name = "external.durable_objects.relay.dispatch",
+        skip(self, payload),
+        fields(monitor_id = %payload.monitor_id, dispatch_id = %payload.dispatch_id)
+    )]
+    async fn dispatch(&self, payload: DispatchRequest) -> Result<Response> {
+        let location = self.location().await?;
+        let d1 = get_d1(&self.env)?;
+        let analytics = AnalyticsBackend::from_env(&self.env)?;
+        let evidence_bucket = archive_bucket(&self.env).ok();
+
+        match handle_dispatch(&d1, &analytics, evidence_bucket.as_ref(), payload, location).await {
+            Ok(()) => Response::empty().map(|response| response.with_status(202)),
+            Err(err) => {
+                let message: String = err.into();
+                console_error!("relay.dispatch: {message}");
+                Response::error(message, 500)
+            }
+        }
+    }
+
+    /// The region is the Relay's location hint; the colo is looked up once and cached.
+    async fn location(&self) -> Result<RunnerLocation> {
+        let mut state = self.load_state().await?;
+        let colo = match state.colo.clone() {
+            Some(colo) => colo,
+            None => {
+                let colo = resolve_colo().await.unwrap_or_else(|err| {
+                    console_warn!("relay.colo: {err:?}");
+                    "unknown".to_string()
+                });
+                if colo != "unknown" {
+                    state.colo = Some(colo.clone());
+                    self.save_state(&state).await?;
+                }
+                colo
+            }
+        };
+
+        Ok(RunnerLocation {
+            region: state.location_hint.unwrap_or("unknown".to_string()),
+            colo,
+        })
+    }
 }
 
 impl DurableObject for Relay {
     fn new(state: State, env: Env) -> Self {
-        Self { state, _env: env }
+        Self { state, env }
     }
 
     async fn fetch(&self, mut req: Request) -> Result<Response> {
@@ -73,10 +130,19 @@ impl DurableObject for Relay {
                 let payload: RelayBootstrapPayload = req.json().await?;
                 self.bootstrap(payload).await
             }
+            (Method::Post, "/internal/dispatch") => {
+                let payload: DispatchRequest = req.json().await?;
+                self.dispatch(payload).await
+            }
             (Method::Get, "/internal/status") => {
                 let state = self.load_state().await?;
                 Response::from_json(&state)
             }
+            // The relay row was deleted; nothing will address this object again.
+            (Method::Delete, "/internal/state") => {
+                self.state.storage().delete_all().await?;
+                Response::empty().map(|response| response.with_status(204))
+            }
             _ => Response::error("Not found", 404),
         }
     }
@@ -1,23 +1,33 @@
-use std::{str::FromStr, time::Duration};
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
 
 use cuid2::create_id;
-use futures::stream::{self, StreamExt, TryStreamExt};
+use futures::{
+    future,
+    stream::{self, StreamExt},
+};
 use js_sys::wasm_bindgen::JsValue;
 use serde::Deserialize;
 use serde_json::to_string;
 use worker::*;
 
 use crate::{
+    analytics::backend::AnalyticsBackend,
+    cloudflare::durable_objects::relay::get_relays_do,
     cloudflare::durable_objects::ticker_types::{
-        DispatchPayload, MonitorDispatchRow, TickerConfig, TickerError, TickerState,
+        DispatchPayload, DispatchTarget, MonitorDispatchRow, PlacementRelay, ReapPolicy,
+        TickerConfig, TickerError, TickerState,
     },
+    cloudflare::queues::{dispatch_mode, dispatch_queue, DispatchMode},
     d1c::queries::{
-        monitors::{list_due_monitors, update_monitor_next_run_at_stmt},
+        monitor_dispatches::{list_stale_dispatches, ListStaleDispatchesRow},
+        monitors::{
+            defer_monitor_next_run_at_stmt, list_due_monitors, update_monitor_next_run_at_stmt,
+        },
         organizations::get_org_sample_rate,
     },
-    dispatch_state::record_pending_dispatch,
-    internal::types::MonitorKind,
-    monitors::types::HttpMonitorConfig,
+    dispatch_state::{mark_dispatch_failed, mark_dispatch_timed_out, record_pending_dispatch},
+    internal::{dispatch::record_timed_out_dispatch, types::MonitorKind},
+    monitors::types::{HttpMethod, MonitorConfig, Placement},
     utils::date::now_ms,
 };
 
@@ -26,6 +36,20 @@ const DEFAULT_BATCH_SIZE: usize = 100;
 const MIN_REARM_DELAY_MS: u64 = 1_000;
 const MAX_BACKOFF_MS: u64 = 60_000;
 const MAX_CONCURRENT_DISPATCHES: usize = 20;
+const MIN_MONITOR_INTERVAL_MS: i64 = 1_000;
+const DISPATCH_SERVICE_BINDING: &str = "DISPATCH_SERVICE";
+const DISPATCH_PATH: &str = "/api/internal/dispatch/run";
+const REAP_POLICY_VAR: &str = "DISPATCH_REAP_POLICY";
+/// Slack past a monitor's own timeout before its dispatch counts as stuck; covers queue
+/// delivery, redirects and the round trip back to D1.
+const REAP_GRACE_MS: i64 = 60_000;
+/// Re-sends of one run the `redispatch` policy allows before it records the run as failed.
+const MAX_REDISPATCHES: i64 = 3;
+/// How long a monitor the ticker cannot parse waits before it is looked at again, so it does
+/// not take a batch slot on every tick.
+const UNSCHEDULABLE_RETRY_MS: i64 = 300_000;
+/// The runner's fallback when a monitor has no timeout configured.
+const DEFAULT_CHECK_TIMEOUT_MS: i64 = 30_000;
 
 #[durable_object]
 pub struct Ticker {
@@ -121,8 +145,19 @@ impl Ticker {
         self.dispatch_monitors(&config, claimed, sample_rate)
             .await?;
 
+        // A failed sweep must not hold up scheduling; the rows are picked up next tick.
+        let reaped = match self.reap_stuck_dispatches(&config, sample_rate).await {
+            Ok(reaped) => reaped,
+            Err(err) => {
+                console_error!("ticker.reaper: org_id={} {err:?}", config.org_id);
+                0
+            }
+        };
+
         state.last_tick_ts = now_ms();
         state.consecutive_errors = 0;
+        state.reaped_last_tick = reaped;
+        state.reaped_total = state.reaped_total.saturating_add(reaped as u64);
         self.save_state(&state).await?;
 
         if claimed_count >= config.batch_size {
@@ -158,16 +193,36 @@ impl Ticker {
         let mut claimed = Vec::with_capacity(rows.len());
         for row in rows {
             let monitor_id = row.id.clone().unwrap_or_default();
-            let kind = MonitorKind::from_str(&row.kind)
-                .map_err(|_| TickerError::unknown("ticker.claim.kind_parse", row.kind.clone()))?;
-
-            if kind != MonitorKind::Http {
-                return Err(TickerError::unsupported_monitor_kind(
-                    "ticker.claim.unsupported_kind",
-                    kind,
-                ));
-            }
-            let next_run_at = now + config.tick_interval_ms as i64;
+            let parsed = parse_monitor(&row.kind, &row.config_json).and_then(|monitor| {
+                parse_placement(&row.placement, row.relays_json.as_deref())
+                    .map(|placement| (monitor, placement))
+            });
+            // One unrunnable monitor must not hold up the rest of the org.
+            let ((kind, monitor_config), (placement, relays)) = match parsed {
+                Ok(parsed) => parsed,
+                Err(err) => {
+                    console_error!(
+                        "ticker.claim.skip: org_id={} monitor_id={monitor_id} {err:?}",
+                        config.org_id
+                    );
+                    statements.push(defer_monitor_next_run_at_stmt(
+                        &d1,
+                        now + UNSCHEDULABLE_RETRY_MS,
+                        now,
+                        &monitor_id,
+                        &config.org_id,
+                    )?);
+                    continue;
+                }
+            };
+
+            let scheduled_for_ts = row.next_run_at.unwrap_or(now);
+            let next_run_at = next_run_at(
+                &monitor_id,
+                scheduled_for_ts,
+                monitor_config.interval() * 1000,
+                now,
+            );
             let update_statement = update_monitor_next_run_at_stmt(
                 &d1,
                 next_run_at,
@@ -179,21 +234,16 @@ impl Ticker {
 
             statements.push(update_statement);
 
-            let config: HttpMonitorConfig =
-                serde_json::from_str(&row.config_json).map_err(|err| {
-                    TickerError::unknown("ticker.claim.config_parse", err.to_string())
-                })?;
-
-            let scheduled_for_ts = row.next_run_at.unwrap_or(now);
-
             claimed.push(MonitorDispatchRow {
                 id: monitor_id,
                 kind,
-                config,
+                config: monitor_config,
                 scheduled_for_ts,
                 status: row.status,
                 first_checked_at: row.first_checked_at,
                 last_failed_at: row.last_failed_at,
+                placement,
+                relays,
             });
         }
 
@@ -204,6 +254,181 @@ impl Ticker {
         Ok(claimed)
     }
 
+    /// Closes dispatches still `pending`/`running` past the monitor timeout (once per attempt)
+    /// plus `REAP_GRACE_MS`, e.g. because the runner crashed or the request was lost. Each is
+    /// marked `timed_out`, then handled per `DISPATCH_REAP_POLICY`. Returns how many were reaped.
+    #[tracing::instrument(
+        name = "external.durable_objects.ticker.reap_stuck_dispatches",
+        skip(self, config, sample_rate),
+        fields(org_id = %config.org_id)
+    )]
+    async fn reap_stuck_dispatches(
+        &self,
+        config: &TickerConfig,
+        sample_rate: f64,
+    ) -> std::result::Result<usize, TickerError> {
+        let d1 = self.env.d1("DB")?;
+        let now = now_ms();
+        let policy = self.reap_policy()?;
+
+        // No monitor can be stuck before its start plus the grace, so that bounds the scan;
+        // each row is then checked against its own timeout.
+        let rows = list_stale_dispatches(
+            &d1,
+            &config.org_id,
+            now - REAP_GRACE_MS,
+            config.batch_size as i64,
+        )
+        .await
+        .map_err(|err| TickerError::database("ticker.reap.list_stale", err))?;
+
+        // Each row stands alone: a bad config or a failed send is logged and the sweep goes on.
+        let mut reaped = 0;
+        for row in rows {
+            let monitor_id = row.monitor_id.clone();
+            match self
+                .reap_dispatch(&d1, config, policy, sample_rate, row, now)
+                .await
+            {
+                Ok(true) => reaped += 1,
+                Ok(false) => {}
+                Err(err) => console_error!(
+                    "ticker.reap.row: org_id={} monitor_id={monitor_id} {err:?}",
+                    config.org_id
+                ),
+            }
+        }
+
+        if reaped > 0 {
+            console_log!(
+                "ticker.reaper: org_id={} reaped={reaped} policy={policy}",
+                config.org_id
+            );
+        }
+        Ok(reaped)
+    }
+
+    /// Reaps one stale row. Returns `false` when it is still within its budget or the runner
+    /// finished first.
+    async fn reap_dispatch(
+        &self,
+        d1: &D1Database,
+        config: &TickerConfig,
+        policy: ReapPolicy,
+        sample_rate: f64,
+        row: ListStaleDispatchesRow,
+        now: i64,
+    ) -> std::result::Result<bool, TickerError> {
+        let parsed = parse_monitor(&row.kind, &row.config_json).and_then(|monitor| {
+            parse_placement(&row.placement, row.relays_json.as_deref())
+                .map(|placement| (monitor, placement))
+        });
+        let ((kind, monitor_config), (placement, relays)) = match parsed {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                // Nothing can run or record this dispatch, so close it instead of rescanning
+                // it on every alarm.
+                mark_dispatch_failed(
+                    d1,
+                    &row.monitor_id,
+                    &row.dispatch_id,
+                    now,
+                    &format!("Dispatch reaped with an unreadable monitor: {err:?}"),
+                )
+                .await
+                .map_err(|err| TickerError::database("ticker.reap.mark_failed", err))?;
+                return Err(err);
+            }
+        };
+
+        let timeout_ms = match monitor_config.timeout() {
+            timeout if timeout > 0 => timeout,
+            _ => DEFAULT_CHECK_TIMEOUT_MS,
+        };
+        // Every retry may run to the timeout too.
+        let retries = i64::from(monitor_config.retries());
+        let budget_ms = timeout_ms * (retries + 1) + monitor_config.retry_delay_ms() * retries;
+        let started_at = row.dispatched_at_ts.unwrap_or(row.scheduled_for_ts);
+        if started_at + budget_ms + REAP_GRACE_MS > now {
+            return Ok(false);
+        }
+
+        let error = format!(
+            "Dispatch timed out: still {} {}s after it started",
+            row.dispatch_status,
+            (now - started_at) / 1000
+        );
+        let claimed = mark_dispatch_timed_out(d1, &row.monitor_id, &row.dispatch_id, now, &error)
+            .await
+            .map_err(|err| TickerError::database("ticker.reap.mark", err))?;
+        if !claimed {
+            return Ok(false);
+        }
+
+        let monitor = MonitorDispatchRow {
+            id: row.monitor_id,
+            kind,
+            config: monitor_config,
+            scheduled_for_ts: row.scheduled_for_ts,
+            status: row.status,
+            first_checked_at: row.first_checked_at,
+            last_failed_at: row.last_failed_at,
+            placement,
+            relays,
+        };
+        // Rotation is keyed on the original slot, so this finds the relay that was sent
+        // the dispatch. A relay since removed or disabled falls back to the default path.
+        let target = dispatch_targets(&monitor)
+            .into_iter()
+            .find(|target| target.hot_relay_id == row.relay_id)
+            .unwrap_or(DispatchTarget {
+                relay: None,
+                hot_relay_id: row.relay_id,
+            });
+
+        // A target that never reports back is given up on after `MAX_REDISPATCHES` and
+        // recorded as failed like under `fail`.
+        if policy == ReapPolicy::Redispatch && row.redispatches < MAX_REDISPATCHES {
+            // Same run, same window: the retry's verdict must count toward the quorum of the
+            // slot it replaces. A failed send has already closed its hot row.
+            self.dispatch_to(config, &monitor, target, sample_rate, row.redispatches + 1)
+                .await?;
+            return Ok(true);
+        }
+
+        let analytics = AnalyticsBackend::from_env(&self.env)?;
+        let payload = dispatch_payload(
+            &row.dispatch_id,
+            &config.org_id,
+            &monitor,
+            &target,
+            sample_rate,
+        );
+        record_timed_out_dispatch(
+            d1,
+            &analytics,
+            &payload.into(),
+            started_at,
+            row.runner_colo.as_deref(),
+            &error,
+        )
+        .await
+        .map_err(|err| TickerError::unknown("ticker.reap.fail", err.into()))?;
+        Ok(true)
+    }
+
+    fn reap_policy(&self) -> std::result::Result<ReapPolicy, TickerError> {
+        match self.env.var(REAP_POLICY_VAR) {
+            Ok(value) => ReapPolicy::from_str(value.to_string().trim()).map_err(|_| {
+                TickerError::unknown(
+                    "ticker.reap.policy",
+                    format!("unknown {REAP_POLICY_VAR} value {value}; expected fail or redispatch"),
+                )
+            }),
+            Err(_) => Ok(ReapPolicy::Fail),
+        }
+    }
+
     #[tracing::instrument(
         name = "external.durable_objects.ticker.dispatch_monitors",
         skip(self, config, monitors, sample_rate),
@@ -220,13 +445,34 @@ impl Ticker {
         }
 
         let concurrency = config.batch_size.max(1).min(MAX_CONCURRENT_DISPATCHES);
-
-        stream::iter(monitors.into_iter())
-            .map(|monitor| self.dispatch_monitor(config, monitor, sample_rate))
+        let total = monitors.len();
+
+        // One monitor's failure must not cancel the rest of the claimed batch; each failed
+        // dispatch has already closed its own hot row (see `dispatch_to`).
+        let mut failures = stream::iter(monitors.into_iter())
+            .map(|monitor| async move {
+                let monitor_id = monitor.id.clone();
+                self.dispatch_monitor(config, monitor, sample_rate)
+                    .await
+                    .map_err(|err| (monitor_id, err))
+            })
             .buffer_unordered(concurrency)
-            .try_collect::<Vec<_>>()
-            .await
-            .map(|_| ())
+            .filter_map(|result| async move { result.err() })
+            .collect::<Vec<_>>()
+            .await;
+
+        for (monitor_id, err) in &failures {
+            console_error!(
+                "ticker.dispatch.monitor: org_id={} monitor_id={monitor_id} {err:?}",
+                config.org_id
+            );
+        }
+
+        // Only a batch where nothing went out counts as a failed tick.
+        match failures.pop() {
+            Some((_, err)) if failures.len() + 1 == total => Err(err),
+            _ => Ok(()),
+        }
     }
 
     #[tracing::instrument(
@@ -239,33 +485,81 @@ impl Ticker {
         config: &TickerConfig,
         monitor: MonitorDispatchRow,
         sample_rate: f64,
+    ) -> std::result::Result<(), TickerError> {
+        future::join_all(
+            dispatch_targets(&monitor)
+                .into_iter()
+                .map(|target| self.dispatch_to(config, &monitor, target, sample_rate, 0)),
+        )
+        .await
+        .into_iter()
+        .collect()
+    }
+
+    /// One run of the monitor on one target, under its own dispatch id.
+    #[tracing::instrument(
+        name = "external.durable_objects.ticker.dispatch_to",
+        skip(self, config, monitor, target, sample_rate),
+        fields(org_id = %config.org_id, monitor_id = %monitor.id, hot_relay_id = %target.hot_relay_id)
+    )]
+    async fn dispatch_to(
+        &self,
+        config: &TickerConfig,
+        monitor: &MonitorDispatchRow,
+        target: DispatchTarget,
+        sample_rate: f64,
+        redispatches: i64,
     ) -> std::result::Result<(), TickerError> {
         let dispatch_id = create_id().to_string();
-        self.record_pending_dispatch(&config.org_id, &monitor, &dispatch_id)
+        self.record_pending_dispatch(&config.org_id, monitor, &target, &dispatch_id, redispatches)
             .await?;
-        self.send_dispatch_request(&dispatch_id, &config.org_id, &monitor, sample_rate)
+        let sent = self
+            .send_dispatch_request(&dispatch_id, &config.org_id, monitor, &target, sample_rate)
+            .await;
+
+        // Nothing should run this dispatch, so close the hot row now rather than leave it
+        // `pending` for the reaper.
+        if let Err(err) = &sent {
+            let d1 = self.env.d1("DB")?;
+            if let Err(mark_err) = mark_dispatch_failed(
+                &d1,
+                &monitor.id,
+                &dispatch_id,
+                now_ms(),
+                &format!("Dispatch could not be sent: {err:?}"),
+            )
             .await
+            {
+                console_error!("ticker.dispatch.unsent: {mark_err:?}");
+            }
+        }
+
+        sent
     }
 
     #[tracing::instrument(
         name = "external.durable_objects.ticker.record_pending_dispatch",
-        skip(self, org_id, monitor, dispatch_id),
-        fields(org_id = %org_id, monitor_id = %monitor.id, dispatch_id = %dispatch_id)
+        skip(self, org_id, monitor, target, dispatch_id),
+        fields(org_id = %org_id, monitor_id = %monitor.id, dispatch_id = %dispatch_id, redispatches = %redispatches)
     )]
     async fn record_pending_dispatch(
         &self,
         org_id: &str,
         monitor: &MonitorDispatchRow,
+        target: &DispatchTarget,
         dispatch_id: &str,
+        redispatches: i64,
     ) -> std::result::Result<(), TickerError> {
         let d1 = self.env.d1("DB")?;
         let now = now_ms();
         record_pending_dispatch(
             &d1,
             &monitor.id,
+            &target.hot_relay_id,
             org_id,
             dispatch_id,
             monitor.scheduled_for_ts,
+            redispatches,
             now,
         )
         .await
@@ -274,7 +568,7 @@ impl Ticker {
 
     #[tracing::instrument(
         name = "external.durable_objects.ticker.send_dispatch_request",
-        skip(self, dispatch_id, org_id, monitor, sample_rate),
+        skip(self, dispatch_id, org_id, monitor, target, sample_rate),
         fields(dispatch_id = %dispatch_id, org_id = %org_id, monitor_id = %monitor.id, sample_rate = %sample_rate)
     )]
     async fn send_dispatch_request(
@@ -282,35 +576,31 @@ impl Ticker {
         dispatch_id: &str,
         org_id: &str,
         monitor: &MonitorDispatchRow,
+        target: &DispatchTarget,
         sample_rate: f64,
     ) -> std::result::Result<(), TickerError> {
-        let dispatch_url = self
-            .env
-            .var("DISPATCH_URL")
-            .map_err(|_| TickerError::missing_var("ticker.dispatch.url", "DISPATCH_URL"))?
-            .to_string();
+        let payload = dispatch_payload(dispatch_id, org_id, monitor, target, sample_rate);
+
+        if let Some(relay) = &target.relay {
+            return self.send_to_relay(&relay.object_id, &payload).await;
+        }
+
+        let mode = dispatch_mode(&self.env)
+            .map_err(|err| TickerError::unknown("ticker.dispatch.mode", format!("{err:?}")))?;
+        if mode == DispatchMode::Queue {
+            return dispatch_queue(&self.env)
+                .map_err(|err| TickerError::request("ticker.dispatch.queue", err))?
+                .send(payload)
+                .await
+                .map_err(|err| TickerError::request("ticker.dispatch.enqueue", err));
+        }
+
         let token = self
             .env
             .var("DISPATCH_TOKEN")
             .map_err(|_| TickerError::missing_var("ticker.dispatch.token", "DISPATCH_TOKEN"))?
             .to_string();
 
-        let payload = DispatchPayload {
-            dispatch_id: dispatch_id.to_string(),
-            monitor_id: monitor.id.clone(),
-            org_id: org_id.to_string(),
-            monitor_url: monitor.config.url.clone(),
-            kind: monitor.kind.clone(),
-            scheduled_for_ts: monitor.scheduled_for_ts,
-            timeout_ms: monitor.config.timeout,
-            follow_redirects: monitor.config.follow_redirects,
-            verify_tls: monitor.config.verify_tls,
-            status: monitor.status.clone(),
-            first_checked_at: monitor.first_checked_at,
-            last_failed_at: monitor.last_failed_at,
-            sample_rate,
-        };
-
         let body = to_string(&payload).map_err(|err| {
             TickerError::request(
                 "ticker.dispatch.serialize",
@@ -318,8 +608,6 @@ impl Ticker {
             )
         })?;
 
-        let url = format!("{}/api/internal/dispatch/run", dispatch_url);
-
         let headers = Headers::new();
         headers
             .set("Content-Type", "application/json")
@@ -333,16 +621,87 @@ impl Ticker {
         init.with_body(Some(JsValue::from_str(&body)));
         init.with_headers(headers);
 
-        let req = Request::new_with_init(&url, &init)?;
-        let response = Fetch::Request(req)
-            .send()
-            .await
-            .map_err(|err| TickerError::request("ticker.dispatch.fetch", err))?;
+        // Prefer the self service binding: the request never leaves Cloudflare, so production
+        // can refuse the route to public callers. `DISPATCH_URL` stays as the fallback.
+        let status = match self.env.service(DISPATCH_SERVICE_BINDING) {
+            Ok(service) => {
+                let req = Request::new_with_init(
+                    &format!("https://{DISPATCH_SERVICE_BINDING}{DISPATCH_PATH}"),
+                    &init,
+                )?;
+                service
+                    .fetch_request(req)
+                    .await
+                    .map_err(|err| TickerError::request("ticker.dispatch.service", err))?
+                    .status()
+                    .as_u16()
+            }
+            Err(_) => {
+                let dispatch_url = self
+                    .env
+                    .var("DISPATCH_URL")
+                    .map_err(|_| TickerError::missing_var("ticker.dispatch.url", "DISPATCH_URL"))?
+                    .to_string();
+                let req = Request::new_with_init(&format!("{dispatch_url}{DISPATCH_PATH}"), &init)?;
+                Fetch::Request(req)
+                    .send()
+                    .await
+                    .map_err(|err| TickerError::request("ticker.dispatch.fetch", err))?
+                    .status_code()
+            }
+        };
 
-        if response.status_code() >= 400 {
+        if status >= 400 {
             return Err(TickerError::response_status(
                 "ticker.dispatch.response",
-                response.status_code(),
+                status,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Hands the check to the monitor's Relay, which runs it from its pinned colo.
+    #[tracing::instrument(
+        name = "external.durable_objects.ticker.send_to_relay",
+        skip(self, payload),
+        fields(relay_object_id = %object_id, dispatch_id = %payload.dispatch_id)
+    )]
+    async fn send_to_relay(
+        &self,
+        object_id: &str,
+        payload: &DispatchPayload,
+    ) -> std::result::Result<(), TickerError> {
+        let namespace = get_relays_do(&self.env)
+            .map_err(|err| TickerError::request("ticker.relay.namespace", err))?;
+        let stub = namespace
+            .id_from_string(object_id)
+            .and_then(|id| id.get_stub())
+            .map_err(|err| TickerError::request("ticker.relay.stub", err))?;
+
+        let body = to_string(payload).map_err(|err| {
+            TickerError::request("ticker.relay.serialize", worker::Error::SerdeJsonError(err))
+        })?;
+        let headers = Headers::new();
+        headers
+            .set("Content-Type", "application/json")
+            .map_err(|err| TickerError::request("ticker.relay.headers", err))?;
+
+        let mut init = RequestInit::new();
+        init.with_method(Method::Post);
+        init.with_body(Some(JsValue::from_str(&body)));
+        init.with_headers(headers);
+        let req = Request::new_with_init("https://relay/internal/dispatch", &init)?;
+
+        let status = stub
+            .fetch_with_request(req)
+            .await
+            .map_err(|err| TickerError::request("ticker.relay.fetch", err))?
+            .status_code();
+        if status >= 400 {
+            return Err(TickerError::response_status(
+                "ticker.relay.response",
+                status,
             ));
         }
 
@@ -420,3 +779,165 @@ impl DurableObject for Ticker {
         Response::ok("ok")
     }
 }
+
+fn parse_monitor(
+    kind: &str,
+    config_json: &str,
+) -> std::result::Result<(MonitorKind, MonitorConfig), TickerError> {
+    let kind = MonitorKind::from_str(kind)
+        .map_err(|_| TickerError::unknown("ticker.claim.kind_parse", kind.to_string()))?;
+
+    if !matches!(kind, MonitorKind::Http | MonitorKind::Tcp) {
+        return Err(TickerError::unsupported_monitor_kind(
+            "ticker.claim.unsupported_kind",
+            kind,
+        ));
+    }
+    let config = MonitorConfig::from_json(&kind, config_json)
+        .map_err(|err| TickerError::unknown("ticker.claim.config_parse", String::from(err)))?;
+    Ok((kind, config))
+}
+
+fn parse_placement(
+    placement: &str,
+    relays_json: Option<&str>,
+) -> std::result::Result<(Placement, Vec<PlacementRelay>), TickerError> {
+    let placement = Placement::from_str(placement)
+        .map_err(|_| TickerError::unknown("ticker.claim.placement_parse", placement.to_string()))?;
+    let mut relays: Vec<PlacementRelay> = match relays_json {
+        Some(json) => serde_json::from_str(json)
+            .map_err(|err| TickerError::unknown("ticker.claim.relays_parse", err.to_string()))?,
+        None => Vec::new(),
+    };
+    relays.sort_by_key(|relay| relay.position);
+    Ok((placement, relays))
+}
+
+/// Where this run of the monitor goes. Without an enabled relay there is a single target on
+/// the default dispatch path.
+fn dispatch_targets(monitor: &MonitorDispatchRow) -> Vec<DispatchTarget> {
+    let single = |relay: Option<&PlacementRelay>| {
+        vec![DispatchTarget {
+            relay: relay.cloned(),
+            hot_relay_id: String::new(),
+        }]
+    };
+
+    match monitor.placement {
+        _ if monitor.relays.is_empty() => single(None),
+        Placement::Single => single(monitor.relays.first()),
+        Placement::RoundRobin => {
+            // Slots sit one interval apart, so the slot index advances by one per run.
+            let interval_ms = (monitor.config.interval() * 1000).max(MIN_MONITOR_INTERVAL_MS);
+            let slot = monitor.scheduled_for_ts.div_euclid(interval_ms);
+            let index = slot.rem_euclid(monitor.relays.len() as i64) as usize;
+            single(monitor.relays.get(index))
+        }
+        Placement::All => monitor
+            .relays
+            .iter()
+            .map(|relay| DispatchTarget {
+                relay: Some(relay.clone()),
+                hot_relay_id: relay.relay_id.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// Regions that must fail the same run before the monitor is down. Only `all` placement runs
+/// several regions at once; the quorum is capped at the enabled relay count so a monitor can
+/// still go down after a relay is disabled.
+fn down_quorum(monitor: &MonitorDispatchRow) -> u32 {
+    match monitor.placement {
+        Placement::All => monitor
+            .config
+            .down_quorum()
+            .unwrap_or(1)
+            .min(monitor.relays.len() as u32)
+            .max(1),
+        Placement::Single | Placement::RoundRobin => 1,
+    }
+}
+
+fn dispatch_payload(
+    dispatch_id: &str,
+    org_id: &str,
+    monitor: &MonitorDispatchRow,
+    target: &DispatchTarget,
+    sample_rate: f64,
+) -> DispatchPayload {
+    let mut payload = DispatchPayload {
+        dispatch_id: dispatch_id.to_string(),
+        monitor_id: monitor.id.clone(),
+        org_id: org_id.to_string(),
+        monitor_url: monitor.config.target(),
+        kind: monitor.kind.clone(),
+        scheduled_for_ts: monitor.scheduled_for_ts,
+        timeout_ms: monitor.config.timeout(),
+        follow_redirects: false,
+        verify_tls: true,
+        method: HttpMethod::default(),
+        headers: BTreeMap::new(),
+        body: None,
+        assertions: Vec::new(),
+        degraded_after_ms: None,
+        down_after_ms: None,
+        tcp: None,
+        failure_threshold: monitor.config.failure_threshold(),
+        recovery_threshold: monitor.config.recovery_threshold(),
+        status: monitor.status.clone(),
+        first_checked_at: monitor.first_checked_at,
+        last_failed_at: monitor.last_failed_at,
+        sample_rate,
+        relay_id: target.relay.as_ref().map(|relay| relay.relay_id.clone()),
+        hot_relay_id: target.hot_relay_id.clone(),
+        down_quorum: down_quorum(monitor),
+        retries: monitor.config.retries(),
+        retry_delay_ms: monitor.config.retry_delay_ms(),
+    };
+    match &monitor.config {
+        MonitorConfig::Http(http) => {
+            payload.follow_redirects = http.follow_redirects;
+            payload.verify_tls = http.verify_tls;
+            payload.method = http.method;
+            payload.headers = http.headers.clone();
+            payload.body = http.body.clone();
+            payload.assertions = http.assertions.clone();
+            payload.degraded_after_ms = http.degraded_after_ms;
+            payload.down_after_ms = http.down_after_ms;
+        }
+        MonitorConfig::Tcp(tcp) => {
+            payload.tcp = Some(tcp.clone());
+        }
+    }
+    payload
+}
+
+/// Picks the first slot on the monitor's own grid after `scheduled_for_ts`.
+///
+/// The grid is `phase + k * interval`, where `phase` is derived from the monitor id, so
+/// monitors created together spread across the interval instead of firing in the same alarm.
+/// Anchoring to the scheduled slot (not `now`) keeps runs from drifting by the alarm latency;
+/// slots missed entirely (e.g. after an outage) are skipped rather than replayed.
+fn next_run_at(monitor_id: &str, scheduled_for_ts: i64, interval_ms: i64, now: i64) -> i64 {
+    let interval_ms = interval_ms.max(MIN_MONITOR_INTERVAL_MS);
+    let phase = (schedule_hash(monitor_id) % interval_ms as u64) as i64;
+    let next_slot_after = |ts: i64| match (phase - ts).rem_euclid(interval_ms) {
+        0 => ts + interval_ms,
+        offset => ts + offset,
+    };
+
+    let next = next_slot_after(scheduled_for_ts);
+    if next > now {
+        next
+    } else {
+        next_slot_after(now)
+    }
+}
+
+/// FNV-1a; stable across deploys, unlike `DefaultHasher`.
+fn schedule_hash(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
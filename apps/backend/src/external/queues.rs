@@ -1 +1 @@
-pub mod heartbeat_results;
+pub mod dispatch;
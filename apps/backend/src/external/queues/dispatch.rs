@@ -0,0 +1,107 @@
+use futures::{stream, StreamExt};
+use worker::{
+    console_error, console_warn, D1Database, Env, Message, MessageBatch, MessageExt,
+    QueueRetryOptionsBuilder, Result,
+};
+
+use crate::analytics::backend::AnalyticsBackend;
+use crate::archive::service::archive_bucket;
+use crate::cloudflare::d1::get_d1;
+use crate::cloudflare::queues::DISPATCH_DLQ_VAR;
+use crate::dispatch_state::finalize_dispatch;
+use crate::internal::dispatch::handle_dispatch;
+use crate::internal::types::{DispatchRequest, RunnerLocation};
+use crate::utils::date::now_ms;
+
+/// Checks run concurrently within one batch; each message is acked or retried on its own.
+const MAX_CONCURRENT_MESSAGES: usize = 10;
+/// Back-off before a failed message is redelivered. After `max_retries` the queue moves it to
+/// the dead-letter queue.
+const RETRY_DELAY_SECONDS: u32 = 30;
+
+/// Entry point for both the dispatch queue and its dead-letter queue, told apart by name.
+#[tracing::instrument(
+    name = "external.queues.dispatch.consume",
+    skip(batch, env),
+    fields(queue = %batch.queue())
+)]
+pub async fn consume_dispatch_batch(batch: MessageBatch<DispatchRequest>, env: &Env) -> Result<()> {
+    let messages = batch
+        .iter()
+        .filter_map(|message| match message {
+            Ok(message) => Some(message),
+            Err(err) => {
+                console_error!("queue.dispatch.decode: {err:?}");
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    let d1 = get_d1(env)?;
+
+    let dead_letter_queue = env.var(DISPATCH_DLQ_VAR).map(|value| value.to_string());
+    if dead_letter_queue.is_ok_and(|name| name == batch.queue()) {
+        for message in &messages {
+            close_dead_lettered(&d1, message).await;
+        }
+        return Ok(());
+    }
+
+    let analytics = AnalyticsBackend::from_env(env)?;
+    let evidence_bucket = archive_bucket(env).ok();
+    let location = RunnerLocation::resolve(None).await;
+    stream::iter(messages)
+        .for_each_concurrent(MAX_CONCURRENT_MESSAGES, |message| {
+            let d1 = &d1;
+            let analytics = &analytics;
+            let evidence_bucket = evidence_bucket.as_ref();
+            let location = location.clone();
+            async move {
+                let payload = message.body().clone();
+                match handle_dispatch(d1, analytics, evidence_bucket, payload, location).await {
+                    Ok(()) => message.ack(),
+                    Err(err) => {
+                        let reason: String = err.into();
+                        console_error!(
+                            "queue.dispatch.retry: dispatch_id={} {reason}",
+                            message.body().dispatch_id
+                        );
+                        message.retry_with_options(
+                            &QueueRetryOptionsBuilder::new()
+                                .with_delay_seconds(RETRY_DELAY_SECONDS)
+                                .build(),
+                        );
+                    }
+                }
+            }
+        })
+        .await;
+
+    Ok(())
+}
+
+/// The check never completed, so there is no heartbeat to record; mark the hot row failed so
+/// it does not sit in `running` forever.
+async fn close_dead_lettered(d1: &D1Database, message: &Message<DispatchRequest>) {
+    let payload = message.body();
+    console_warn!(
+        "queue.dispatch.dead_lettered: monitor_id={} dispatch_id={}",
+        payload.monitor_id,
+        payload.dispatch_id
+    );
+    match finalize_dispatch(
+        d1,
+        &payload.monitor_id,
+        &payload.dispatch_id,
+        "failed",
+        now_ms(),
+        Some("Dispatch dead-lettered after exhausting queue retries"),
+    )
+    .await
+    {
+        Ok(()) => message.ack(),
+        Err(err) => {
+            console_error!("queue.dispatch.dead_letter.finalize: {err:?}");
+            message.retry();
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::router::AppState;
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+pub mod engine;
+pub mod handlers;
+pub mod service;
+pub mod types;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(handlers::list_incidents_handler))
+        .route("/{id}", get(handlers::get_incident_handler))
+        .route(
+            "/{id}/acknowledge",
+            post(handlers::acknowledge_incident_handler),
+        )
+        .route("/{id}/resolve", post(handlers::resolve_incident_handler))
+        .route("/{id}/notes", post(handlers::add_incident_note_handler))
+}
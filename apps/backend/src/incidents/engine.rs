@@ -0,0 +1,130 @@
+use cuid2::create_id;
+use worker::{console_warn, D1Database, Result};
+
+use crate::d1c::queries::incidents::{
+    close_incident, count_other_failing_relays, get_active_incident_for_monitor, open_incident,
+    record_monitor_failure, record_monitor_success,
+};
+use crate::incidents::service::record_incident_event;
+use crate::incidents::types::{IncidentEventKind, IncidentTransition};
+use crate::monitors::types::{HeartbeatResult, MonitorStatus};
+use crate::utils::date::now_ms;
+
+/// Advances the monitor's consecutive failure/success counters and opens or closes its
+/// incident once the configured threshold is crossed.
+///
+/// Only `Down` counts as a failure; `Degraded` responses still answered, so they count
+/// towards recovery.
+///
+/// Counters are kept per hot row: `relay_id` is the fan-out slot (see
+/// `DispatchRequest::hot_relay_id`). The incident opens once `quorum` slots are past the
+/// failure threshold, and closes once fewer than `quorum` other slots still are.
+#[tracing::instrument(
+    name = "incidents.engine.evaluate",
+    skip(d1, heartbeat),
+    fields(monitor_id = %heartbeat.monitor_id, relay_id = %relay_id, status = %heartbeat.status)
+)]
+pub async fn evaluate_incident(
+    d1: &D1Database,
+    heartbeat: &HeartbeatResult,
+    relay_id: &str,
+    failure_threshold: u32,
+    recovery_threshold: u32,
+    quorum: u32,
+) -> Result<IncidentTransition> {
+    let now = now_ms();
+    let failed = heartbeat.status == MonitorStatus::Down;
+
+    let counters = if failed {
+        record_monitor_failure(d1, now, &heartbeat.monitor_id, relay_id)
+            .await?
+            .map(|row| (row.consecutive_failures, row.consecutive_successes))
+    } else {
+        record_monitor_success(d1, now, &heartbeat.monitor_id, relay_id)
+            .await?
+            .map(|row| (row.consecutive_failures, row.consecutive_successes))
+    };
+
+    // Counters live on the hot dispatch row the ticker writes before dispatching.
+    let Some((failures, successes)) = counters else {
+        console_warn!(
+            "incidents.engine.missing_hot_state: monitor_id={} relay_id={relay_id}",
+            heartbeat.monitor_id
+        );
+        return Ok(IncidentTransition::Unchanged);
+    };
+
+    let active = get_active_incident_for_monitor(d1, &heartbeat.monitor_id).await?;
+    let other_failing = || async {
+        count_other_failing_relays(
+            d1,
+            &heartbeat.monitor_id,
+            relay_id,
+            failure_threshold as i64,
+        )
+        .await
+        .map(|total| total.unwrap_or(0))
+    };
+
+    match active {
+        None if failed && failures >= failure_threshold as i64 => {
+            if quorum > 1 && 1 + other_failing().await? < quorum as i64 {
+                return Ok(IncidentTransition::Unchanged);
+            }
+
+            let incident_id = create_id().to_string();
+            let reason = heartbeat
+                .error
+                .clone()
+                .unwrap_or_else(|| "Health check failed".to_string());
+            open_incident(
+                d1,
+                &incident_id,
+                &heartbeat.monitor_id,
+                heartbeat.timestamp,
+                Some(reason.as_str()),
+                heartbeat
+                    .evidence_key
+                    .as_ref()
+                    .map(|_| heartbeat.dispatch_id.as_str()),
+                now,
+                now,
+            )
+            .await?;
+
+            // A concurrent dispatch may have won the insert; report whichever incident is live.
+            match get_active_incident_for_monitor(d1, &heartbeat.monitor_id).await? {
+                Some(row) if row.id.as_deref() == Some(incident_id.as_str()) => {
+                    record_incident_event(
+                        d1,
+                        &incident_id,
+                        IncidentEventKind::Opened,
+                        None,
+                        Some(reason.as_str()),
+                    )
+                    .await?;
+                    Ok(IncidentTransition::Opened { incident_id })
+                }
+                _ => Ok(IncidentTransition::Unchanged),
+            }
+        }
+        Some(row) if !failed && successes >= recovery_threshold as i64 => {
+            if other_failing().await? >= quorum as i64 {
+                return Ok(IncidentTransition::Unchanged);
+            }
+
+            let incident_id = row.id.unwrap_or_default();
+            close_incident(d1, heartbeat.timestamp, now, &incident_id).await?;
+            record_incident_event(
+                d1,
+                &incident_id,
+                IncidentEventKind::Closed,
+                None,
+                Some(format!("Recovered after {successes} consecutive passing checks").as_str()),
+            )
+            .await?;
+            Ok(IncidentTransition::Closed { incident_id })
+        }
+        _ => Ok(IncidentTransition::Unchanged),
+    }
+}
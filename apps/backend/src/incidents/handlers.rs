@@ -0,0 +1,114 @@
+use crate::auth::membership::load_membership;
+use crate::cloudflare::d1::AppDb;
+use crate::incidents::service::{
+    acknowledge_incident_for_org, add_incident_note_for_org, get_incident_detail_for_org,
+    list_incidents_for_org_page, resolve_incident_for_org,
+};
+use crate::incidents::types::{
+    CreateIncidentNote, IncidentActor, IncidentDetail, IncidentPage, ListIncidentsQuery,
+};
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::Result,
+    Json,
+};
+use hb_auth::User;
+
+fn actor_from(auth: &User) -> IncidentActor {
+    IncidentActor {
+        id: auth.sub().to_string(),
+        email: auth.email().to_string(),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "incidents.http.list",
+    skip(d1, query),
+    fields(subject = %auth.sub())
+)]
+pub async fn list_incidents_handler(
+    AppDb(d1): AppDb,
+    auth: User,
+    Query(query): Query<ListIncidentsQuery>,
+) -> Result<Json<IncidentPage>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match list_incidents_for_org_page(&d1, &org_id, query).await {
+        Ok(page) => Ok(Json(page)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "incidents.http.get_by_id",
+    skip(d1),
+    fields(incident_id = %id)
+)]
+pub async fn get_incident_handler(
+    Path(id): Path<String>,
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<Json<IncidentDetail>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match get_incident_detail_for_org(&d1, &org_id, &id).await {
+        Ok(detail) => Ok(Json(detail)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "incidents.http.acknowledge",
+    skip(d1),
+    fields(incident_id = %id, identity_id = %auth.sub())
+)]
+pub async fn acknowledge_incident_handler(
+    Path(id): Path<String>,
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<Json<IncidentDetail>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match acknowledge_incident_for_org(&d1, &org_id, &id, &actor_from(&auth)).await {
+        Ok(detail) => Ok(Json(detail)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "incidents.http.resolve",
+    skip(d1),
+    fields(incident_id = %id, identity_id = %auth.sub())
+)]
+pub async fn resolve_incident_handler(
+    Path(id): Path<String>,
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<Json<IncidentDetail>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match resolve_incident_for_org(&d1, &org_id, &id, &actor_from(&auth)).await {
+        Ok(detail) => Ok(Json(detail)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "incidents.http.add_note",
+    skip(d1, note),
+    fields(incident_id = %id, identity_id = %auth.sub())
+)]
+pub async fn add_incident_note_handler(
+    Path(id): Path<String>,
+    AppDb(d1): AppDb,
+    auth: User,
+    Json(note): Json<CreateIncidentNote>,
+) -> Result<Json<IncidentDetail>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match add_incident_note_for_org(&d1, &org_id, &id, &actor_from(&auth), &note.message).await {
+        Ok(detail) => Ok(Json(detail)),
+        Err(err) => Err(err.into()),
+    }
+}
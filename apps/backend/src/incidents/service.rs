@@ -0,0 +1,251 @@
+use cuid2::create_id;
+use worker::D1Database;
+
+use crate::d1c::queries::incidents::{
+    acknowledge_incident, count_incidents_for_org, get_incident_for_org, insert_incident_event,
+    list_incident_events, list_incidents_for_org, reset_monitor_counters, resolve_incident,
+};
+use crate::incidents::types::{
+    Incident, IncidentActor, IncidentDetail, IncidentError, IncidentEvent, IncidentEventKind,
+    IncidentPage, ListIncidentsQuery,
+};
+use crate::notifications::service::notify_incident_event;
+use crate::notifications::types::NotificationEvent;
+use crate::utils::date::now_ms;
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+const MAX_NOTE_LENGTH: usize = 4000;
+
+/// Appends a timeline entry. `actor` is `None` for transitions made by the incident engine.
+pub async fn record_incident_event(
+    d1: &D1Database,
+    incident_id: &str,
+    kind: IncidentEventKind,
+    actor: Option<&IncidentActor>,
+    message: Option<&str>,
+) -> worker::Result<()> {
+    insert_incident_event(
+        d1,
+        &create_id().to_string(),
+        incident_id,
+        kind.to_string().as_str(),
+        actor.map(|actor| actor.id.as_str()),
+        actor.map(|actor| actor.email.as_str()),
+        message,
+        now_ms(),
+    )
+    .await
+}
+
+#[tracing::instrument(
+    name = "incidents.list_for_org",
+    skip(d1, query),
+    fields(org_id = %org_id)
+)]
+pub async fn list_incidents_for_org_page(
+    d1: &D1Database,
+    org_id: &str,
+    query: ListIncidentsQuery,
+) -> Result<IncidentPage, IncidentError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0).max(0);
+    if let (Some(since), Some(until)) = (query.since, query.until) {
+        if since >= until {
+            return Err(IncidentError::InvalidInput(
+                "since must be earlier than until".to_string(),
+            ));
+        }
+    }
+
+    let status = query.status.map(|status| status.to_string());
+    let rows = list_incidents_for_org(
+        d1,
+        org_id,
+        status.as_deref(),
+        query.monitor_id.as_deref(),
+        query.since,
+        query.until,
+        limit,
+        offset,
+    )
+    .await?;
+    let total = count_incidents_for_org(
+        d1,
+        org_id,
+        status.as_deref(),
+        query.monitor_id.as_deref(),
+        query.since,
+        query.until,
+    )
+    .await?
+    .unwrap_or(0);
+
+    let items = rows
+        .into_iter()
+        .map(Incident::try_from)
+        .collect::<Result<Vec<_>, IncidentError>>()?;
+
+    Ok(IncidentPage {
+        items,
+        total,
+        limit,
+        offset,
+    })
+}
+
+#[tracing::instrument(
+    name = "incidents.get_for_org",
+    skip(d1),
+    fields(org_id = %org_id, incident_id = %incident_id)
+)]
+pub async fn get_incident_detail_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    incident_id: &str,
+) -> Result<IncidentDetail, IncidentError> {
+    let incident = load_incident(d1, org_id, incident_id).await?;
+    let events = list_incident_events(d1, incident_id)
+        .await?
+        .into_iter()
+        .map(IncidentEvent::try_from)
+        .collect::<Result<Vec<_>, IncidentError>>()?;
+
+    Ok(IncidentDetail { incident, events })
+}
+
+#[tracing::instrument(
+    name = "incidents.acknowledge_for_org",
+    skip(d1, actor),
+    fields(org_id = %org_id, incident_id = %incident_id, actor_id = %actor.id)
+)]
+pub async fn acknowledge_incident_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    incident_id: &str,
+    actor: &IncidentActor,
+) -> Result<IncidentDetail, IncidentError> {
+    let incident = load_incident(d1, org_id, incident_id).await?;
+    if acknowledge_incident(d1, now_ms(), &incident.id)
+        .await?
+        .is_none()
+    {
+        return Err(IncidentError::Conflict(format!(
+            "incident {} is {}, only open incidents can be acknowledged",
+            incident.id, incident.status
+        )));
+    }
+
+    record_incident_event(
+        d1,
+        &incident.id,
+        IncidentEventKind::Acknowledged,
+        Some(actor),
+        None,
+    )
+    .await?;
+    notify_incident_event(
+        d1,
+        org_id,
+        &incident.id,
+        NotificationEvent::IncidentAcknowledged,
+        Some(actor),
+    )
+    .await;
+    get_incident_detail_for_org(d1, org_id, incident_id).await
+}
+
+#[tracing::instrument(
+    name = "incidents.resolve_for_org",
+    skip(d1, actor),
+    fields(org_id = %org_id, incident_id = %incident_id, actor_id = %actor.id)
+)]
+pub async fn resolve_incident_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    incident_id: &str,
+    actor: &IncidentActor,
+) -> Result<IncidentDetail, IncidentError> {
+    let incident = load_incident(d1, org_id, incident_id).await?;
+    let now = now_ms();
+    if resolve_incident(d1, now, now, &incident.id)
+        .await?
+        .is_none()
+    {
+        return Err(IncidentError::Conflict(format!(
+            "incident {} is already closed",
+            incident.id
+        )));
+    }
+    // Restart the count, so a monitor that is still failing opens a new incident only after
+    // another full run of failures rather than on its next check.
+    reset_monitor_counters(d1, now, &incident.monitor_id).await?;
+
+    record_incident_event(
+        d1,
+        &incident.id,
+        IncidentEventKind::Resolved,
+        Some(actor),
+        None,
+    )
+    .await?;
+    notify_incident_event(
+        d1,
+        org_id,
+        &incident.id,
+        NotificationEvent::IncidentResolved,
+        Some(actor),
+    )
+    .await;
+    get_incident_detail_for_org(d1, org_id, incident_id).await
+}
+
+#[tracing::instrument(
+    name = "incidents.add_note_for_org",
+    skip(d1, actor, message),
+    fields(org_id = %org_id, incident_id = %incident_id, actor_id = %actor.id)
+)]
+pub async fn add_incident_note_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    incident_id: &str,
+    actor: &IncidentActor,
+    message: &str,
+) -> Result<IncidentDetail, IncidentError> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err(IncidentError::InvalidInput(
+            "Note must not be empty".to_string(),
+        ));
+    }
+    if message.chars().count() > MAX_NOTE_LENGTH {
+        return Err(IncidentError::InvalidInput(format!(
+            "Note must be at most {MAX_NOTE_LENGTH} characters"
+        )));
+    }
+
+    let incident = load_incident(d1, org_id, incident_id).await?;
+    record_incident_event(
+        d1,
+        &incident.id,
+        IncidentEventKind::Note,
+        Some(actor),
+        Some(message),
+    )
+    .await?;
+    get_incident_detail_for_org(d1, org_id, incident_id).await
+}
+
+async fn load_incident(
+    d1: &D1Database,
+    org_id: &str,
+    incident_id: &str,
+) -> Result<Incident, IncidentError> {
+    match get_incident_for_org(d1, incident_id, org_id).await? {
+        Some(row) => Incident::try_from(row),
+        None => Err(IncidentError::NotFound),
+    }
+}
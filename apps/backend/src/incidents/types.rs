@@ -0,0 +1,218 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use worker::console_error;
+
+use crate::archive::types::evidence_path;
+use crate::d1c::queries::incidents::{
+    GetIncidentForOrgRow, ListIncidentEventsRow, ListIncidentsForOrgRow,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Display, PartialEq, Eq, EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentStatus {
+    Open,
+    Acknowledged,
+    Closed,
+}
+
+/// Outcome of feeding one heartbeat through the incident engine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IncidentTransition {
+    Opened { incident_id: String },
+    Closed { incident_id: String },
+    Unchanged,
+}
+
+/// Timeline entry kinds. `Closed` is raised by the engine on recovery, `Resolved` by a person.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Display, PartialEq, Eq, EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentEventKind {
+    Opened,
+    Acknowledged,
+    Resolved,
+    Closed,
+    Note,
+}
+
+/// The member acting on an incident, taken from the Access identity.
+#[derive(Debug, Clone)]
+pub struct IncidentActor {
+    pub id: String,
+    pub email: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct Incident {
+    pub id: String,
+    pub monitor_id: String,
+    pub monitor_name: String,
+    pub status: IncidentStatus,
+    pub reason: Option<String>,
+    /// Failure evidence from the check that opened the incident, when it was captured.
+    pub evidence_url: Option<String>,
+    pub opened_ts: i64,
+    pub closed_ts: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl TryFrom<ListIncidentsForOrgRow> for Incident {
+    type Error = IncidentError;
+
+    fn try_from(row: ListIncidentsForOrgRow) -> Result<Self, Self::Error> {
+        let status = IncidentStatus::from_str(&row.status)
+            .map_err(|_| IncidentError::InvalidStatus(row.status.clone()))?;
+
+        Ok(Incident {
+            id: row.id.unwrap_or_default(),
+            monitor_id: row.monitor_id,
+            monitor_name: row.monitor_name,
+            status,
+            reason: row.reason,
+            evidence_url: row.evidence_dispatch_id.as_deref().map(evidence_path),
+            opened_ts: row.opened_ts,
+            closed_ts: row.closed_ts,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+impl TryFrom<GetIncidentForOrgRow> for Incident {
+    type Error = IncidentError;
+
+    fn try_from(row: GetIncidentForOrgRow) -> Result<Self, Self::Error> {
+        let status = IncidentStatus::from_str(&row.status)
+            .map_err(|_| IncidentError::InvalidStatus(row.status.clone()))?;
+
+        Ok(Incident {
+            id: row.id.unwrap_or_default(),
+            monitor_id: row.monitor_id,
+            monitor_name: row.monitor_name,
+            status,
+            reason: row.reason,
+            evidence_url: row.evidence_dispatch_id.as_deref().map(evidence_path),
+            opened_ts: row.opened_ts,
+            closed_ts: row.closed_ts,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct IncidentEvent {
+    pub id: String,
+    pub kind: IncidentEventKind,
+    pub actor_id: Option<String>,
+    pub actor_email: Option<String>,
+    pub message: Option<String>,
+    pub created_at: i64,
+}
+
+impl TryFrom<ListIncidentEventsRow> for IncidentEvent {
+    type Error = IncidentError;
+
+    fn try_from(row: ListIncidentEventsRow) -> Result<Self, Self::Error> {
+        let kind = IncidentEventKind::from_str(&row.kind)
+            .map_err(|_| IncidentError::InvalidEventKind(row.kind.clone()))?;
+
+        Ok(IncidentEvent {
+            id: row.id.unwrap_or_default(),
+            kind,
+            actor_id: row.actor_id,
+            actor_email: row.actor_email,
+            message: row.message,
+            created_at: row.created_at,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct IncidentDetail {
+    #[serde(flatten)]
+    pub incident: Incident,
+    pub events: Vec<IncidentEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListIncidentsQuery {
+    pub status: Option<IncidentStatus>,
+    pub monitor_id: Option<String>,
+    /// Inclusive lower bound on `opened_ts` (ms).
+    pub since: Option<i64>,
+    /// Exclusive upper bound on `opened_ts` (ms).
+    pub until: Option<i64>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct IncidentPage {
+    pub items: Vec<Incident>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct CreateIncidentNote {
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum IncidentError {
+    DbRun(worker::Error),
+    NotFound,
+    InvalidStatus(String),
+    InvalidEventKind(String),
+    InvalidInput(String),
+    Conflict(String),
+}
+
+impl From<worker::Error> for IncidentError {
+    fn from(err: worker::Error) -> Self {
+        IncidentError::DbRun(err)
+    }
+}
+
+impl From<IncidentError> for axum::http::StatusCode {
+    fn from(err: IncidentError) -> axum::http::StatusCode {
+        match err {
+            IncidentError::DbRun(err) => {
+                console_error!("incidents.db.run: {err:?}");
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+            IncidentError::NotFound => {
+                console_error!("incidents.not.found");
+                axum::http::StatusCode::NOT_FOUND
+            }
+            IncidentError::InvalidStatus(status) => {
+                console_error!("incidents.invalid.status: {status}");
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+            IncidentError::InvalidEventKind(kind) => {
+                console_error!("incidents.invalid.event_kind: {kind}");
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+            IncidentError::InvalidInput(reason) => {
+                console_error!("incidents.invalid.input: {reason}");
+                axum::http::StatusCode::BAD_REQUEST
+            }
+            IncidentError::Conflict(reason) => {
+                console_error!("incidents.conflict: {reason}");
+                axum::http::StatusCode::CONFLICT
+            }
+        }
+    }
+}
@@ -1,16 +1,20 @@
 use axum::{
-    routing::{get, post},
+    routing::{get, patch, post},
     Router,
 };
 
-mod dispatch;
+mod assertions;
+pub mod dispatch;
+mod evidence;
 mod handlers;
+mod tcp;
 pub mod types;
 
 use crate::{
     internal::handlers::{
-        dispatch_handler, list_relays_handler, reconcile_tickers_handler, register_relay_handler,
-        seed_monitors_handler,
+        delete_relay_handler, dispatch_handler, list_relays_handler, rebootstrap_relay_handler,
+        reconcile_tickers_handler, register_relay_handler, relay_health_handler,
+        seed_monitors_handler, update_relay_handler,
     },
     router::AppState,
 };
@@ -23,5 +27,11 @@ pub fn router() -> Router<AppState> {
             "/relays",
             get(list_relays_handler).post(register_relay_handler),
         )
+        .route(
+            "/relays/{id}",
+            patch(update_relay_handler).delete(delete_relay_handler),
+        )
+        .route("/relays/{id}/rebootstrap", post(rebootstrap_relay_handler))
+        .route("/relays/{id}/health", post(relay_health_handler))
         .route("/seed", post(seed_monitors_handler))
 }
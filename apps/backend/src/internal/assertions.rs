@@ -0,0 +1,270 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use futures::StreamExt;
+use regex::Regex;
+use serde_json::Value;
+use worker::Response;
+
+use crate::monitors::types::{HttpAssertion, StatusRange};
+
+/// Body assertions never buffer more than this, whatever `max_response_size` allows.
+const MAX_ASSERTION_BODY_BYTES: u64 = 4 * 1024 * 1024;
+/// Compiled `body_matches` patterns kept per isolate; cleared wholesale once full.
+const MAX_CACHED_PATTERNS: usize = 256;
+
+thread_local! {
+    static PATTERNS: RefCell<HashMap<String, Regex>> = RefCell::new(HashMap::new());
+}
+
+#[derive(Debug)]
+pub struct AssertionFailure {
+    pub name: &'static str,
+    pub detail: String,
+}
+
+impl AssertionFailure {
+    fn new(assertion: &HttpAssertion, detail: impl Into<String>) -> Self {
+        Self {
+            name: assertion.name(),
+            detail: detail.into(),
+        }
+    }
+}
+
+pub fn has_status_assertion(assertions: &[HttpAssertion]) -> bool {
+    assertions
+        .iter()
+        .any(|assertion| matches!(assertion, HttpAssertion::Status { .. }))
+}
+
+/// Without an explicit status assertion only 2xx responses are accepted.
+pub fn accepts_status(assertions: &[HttpAssertion], code: u16) -> bool {
+    if !has_status_assertion(assertions) {
+        return (200..=299).contains(&code);
+    }
+
+    status_failures(assertions, code).is_empty()
+}
+
+pub fn status_failures(assertions: &[HttpAssertion], code: u16) -> Vec<AssertionFailure> {
+    assertions
+        .iter()
+        .filter_map(|assertion| match assertion {
+            HttpAssertion::Status { codes, ranges }
+                if !(codes.contains(&code) || ranges.iter().any(|range| range.contains(code))) =>
+            {
+                Some(AssertionFailure::new(
+                    assertion,
+                    format!("got {code}, expected {}", describe_status(codes, ranges)),
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[tracing::instrument(
+    name = "internal.assertions.evaluate_body",
+    skip(assertions, response),
+    fields(assertions = %assertions.len())
+)]
+pub async fn evaluate_body(
+    assertions: &[HttpAssertion],
+    response: &mut Response,
+) -> Vec<AssertionFailure> {
+    let body_assertions: Vec<&HttpAssertion> = assertions
+        .iter()
+        .filter(|assertion| assertion.needs_body())
+        .collect();
+
+    if body_assertions.is_empty() {
+        return Vec::new();
+    }
+
+    // Bail out before buffering when the server already told us the body is too large.
+    let content_length = response
+        .headers()
+        .get("Content-Length")
+        .ok()
+        .flatten()
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    if let Some(length) = content_length {
+        let oversized: Vec<AssertionFailure> = body_assertions
+            .iter()
+            .filter_map(|assertion| match assertion {
+                HttpAssertion::MaxResponseSize { bytes } if length > *bytes => Some(
+                    AssertionFailure::new(assertion, format!("{length} bytes exceeds {bytes}")),
+                ),
+                _ => None,
+            })
+            .collect();
+        if !oversized.is_empty() {
+            return oversized;
+        }
+    }
+
+    // Stream the body against the tightest size limit instead of buffering it first, so a
+    // huge or endless response fails as soon as it crosses the limit.
+    let size_limit = body_assertions
+        .iter()
+        .filter_map(|assertion| match assertion {
+            HttpAssertion::MaxResponseSize { bytes } => Some(*bytes),
+            _ => None,
+        })
+        .min();
+    let limit = size_limit.map_or(MAX_ASSERTION_BODY_BYTES, |bytes| {
+        bytes.min(MAX_ASSERTION_BODY_BYTES)
+    });
+    let body = match read_body(response, limit).await {
+        Ok(Some(body)) => body,
+        Ok(None) if size_limit == Some(limit) => {
+            return body_assertions
+                .into_iter()
+                .filter(|assertion| {
+                    matches!(assertion, HttpAssertion::MaxResponseSize { bytes } if *bytes == limit)
+                })
+                .map(|assertion| {
+                    AssertionFailure::new(assertion, format!("more than {limit} bytes"))
+                })
+                .collect();
+        }
+        Ok(None) => {
+            return body_assertions
+                .into_iter()
+                .map(|assertion| {
+                    AssertionFailure::new(
+                        assertion,
+                        format!("body exceeds {limit} bytes, too large to check"),
+                    )
+                })
+                .collect();
+        }
+        Err(err) => {
+            return body_assertions
+                .into_iter()
+                .map(|assertion| {
+                    AssertionFailure::new(assertion, format!("unable to read body: {err}"))
+                })
+                .collect();
+        }
+    };
+    let text = String::from_utf8_lossy(&body);
+    let mut json: Option<Result<Value, String>> = None;
+
+    let mut failures = Vec::new();
+    for assertion in body_assertions {
+        let failure = match assertion {
+            HttpAssertion::Status { .. } => None,
+            HttpAssertion::BodyContains { value } => (!text.contains(value.as_str())).then(|| {
+                AssertionFailure::new(assertion, format!("body does not contain {value:?}"))
+            }),
+            HttpAssertion::BodyNotContains { value } => text
+                .contains(value.as_str())
+                .then(|| AssertionFailure::new(assertion, format!("body contains {value:?}"))),
+            HttpAssertion::BodyMatches { pattern } => match compiled_pattern(pattern) {
+                Ok(regex) if regex.is_match(&text) => None,
+                Ok(_) => Some(AssertionFailure::new(
+                    assertion,
+                    format!("body does not match /{pattern}/"),
+                )),
+                Err(err) => Some(AssertionFailure::new(
+                    assertion,
+                    format!("invalid pattern: {err}"),
+                )),
+            },
+            HttpAssertion::JsonPointerEquals { pointer, value } => {
+                match json.get_or_insert_with(|| parse_json(&body)) {
+                    Ok(document) => match document.pointer(pointer) {
+                        Some(actual) if actual == value => None,
+                        Some(actual) => Some(AssertionFailure::new(
+                            assertion,
+                            format!("{pointer}: expected {value}, got {actual}"),
+                        )),
+                        None => Some(AssertionFailure::new(
+                            assertion,
+                            format!("{pointer}: not found"),
+                        )),
+                    },
+                    Err(err) => Some(AssertionFailure::new(assertion, err.clone())),
+                }
+            }
+            HttpAssertion::JsonPointerExists { pointer } => {
+                match json.get_or_insert_with(|| parse_json(&body)) {
+                    Ok(document) => document
+                        .pointer(pointer)
+                        .is_none()
+                        .then(|| AssertionFailure::new(assertion, format!("{pointer}: not found"))),
+                    Err(err) => Some(AssertionFailure::new(assertion, err.clone())),
+                }
+            }
+            HttpAssertion::MaxResponseSize { bytes } => (body.len() as u64 > *bytes).then(|| {
+                AssertionFailure::new(assertion, format!("{} bytes exceeds {bytes}", body.len()))
+            }),
+        };
+
+        if let Some(failure) = failure {
+            failures.push(failure);
+        }
+    }
+
+    failures
+}
+
+/// Reads at most `limit` bytes of the body; `Ok(None)` means it is longer than that.
+async fn read_body(response: &mut Response, limit: u64) -> Result<Option<Vec<u8>>, String> {
+    let mut stream = response.stream().map_err(|err| err.to_string())?;
+    let mut body = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|err| err.to_string())?;
+        if (body.len() + chunk.len()) as u64 > limit {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(Some(body))
+}
+
+/// Patterns are validated when the monitor is saved; this only saves recompiling them on
+/// every check.
+fn compiled_pattern(pattern: &str) -> Result<Regex, regex::Error> {
+    PATTERNS.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(regex) = cache.get(pattern) {
+            return Ok(regex.clone());
+        }
+        let regex = Regex::new(pattern)?;
+        if cache.len() >= MAX_CACHED_PATTERNS {
+            cache.clear();
+        }
+        cache.insert(pattern.to_string(), regex.clone());
+        Ok(regex)
+    })
+}
+
+/// Renders failures into the single-line form stored in `HeartbeatResult.error`.
+pub fn describe_failures(failures: &[AssertionFailure]) -> String {
+    let details = failures
+        .iter()
+        .map(|failure| format!("{} ({})", failure.name, failure.detail))
+        .collect::<Vec<_>>()
+        .join("; ");
+    format!("Assertion failed: {details}")
+}
+
+fn parse_json(body: &[u8]) -> Result<Value, String> {
+    serde_json::from_slice(body).map_err(|err| format!("response is not valid JSON: {err}"))
+}
+
+fn describe_status(codes: &[u16], ranges: &[StatusRange]) -> String {
+    codes
+        .iter()
+        .map(|code| code.to_string())
+        .chain(
+            ranges
+                .iter()
+                .map(|range| format!("{}-{}", range.low, range.high)),
+        )
+        .collect::<Vec<_>>()
+        .join(", ")
+}
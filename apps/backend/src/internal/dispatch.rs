@@ -1,39 +1,54 @@
+use std::collections::BTreeMap;
 use std::result::Result;
 use std::str::FromStr;
 use std::time::Duration;
 
 use futures::{future::select, future::Either, pin_mut};
 use js_sys::Math;
+use worker::wasm_bindgen::JsValue;
 use worker::D1Database;
 use worker::{
-    console_error, console_log, AbortController, AnalyticsEngineDataPointBuilder,
-    AnalyticsEngineDataset, Cf, Delay, Fetch, Method, Request, RequestInit, Response,
+    console_error, console_log, AbortController, Bucket, Delay, Fetch, Request, RequestInit,
+    Response,
 };
 
-use crate::dispatch_state::{finalize_dispatch, mark_dispatch_running};
-use crate::internal::types::{DispatchError, DispatchRequest, MonitorKind};
+use crate::analytics::backend::HeartbeatStore;
+use crate::archive::service::put_failure_evidence;
+use crate::dispatch_state::{
+    count_down_in_window, finalize_dispatch, mark_dispatch_running, record_verdict,
+};
+use crate::incidents::engine::evaluate_incident;
+use crate::incidents::types::IncidentTransition;
+use crate::internal::evidence::HttpTrail;
+use crate::internal::types::{DispatchError, DispatchRequest, MonitorKind, RunnerLocation};
+use crate::internal::{assertions, tcp};
 use crate::monitors::service::update_monitor_status_for_org;
-use crate::monitors::types::{HeartbeatResult, MonitorStatus, MonitorStatusSnapshot};
+use crate::monitors::types::{
+    HeartbeatResult, HttpAssertion, HttpMethod, HttpRequestBody, MonitorStatus,
+    MonitorStatusSnapshot,
+};
+use crate::notifications::service::notify_incident_event;
+use crate::notifications::types::NotificationEvent;
 use crate::utils::date::now_ms;
 
 #[tracing::instrument(
     name = "internal.dispatch.handle_dispatch",
-    skip(d1, analytics, payload, cf),
+    skip(d1, analytics, evidence_bucket, payload, location),
     fields(monitor_id = %payload.monitor_id, org_id = %payload.org_id, dispatch_id = %payload.dispatch_id)
 )]
 pub async fn handle_dispatch(
-    d1: D1Database,
-    analytics: &AnalyticsEngineDataset,
+    d1: &D1Database,
+    analytics: &impl HeartbeatStore,
+    evidence_bucket: Option<&Bucket>,
     payload: DispatchRequest,
-    cf: Cf,
+    location: RunnerLocation,
 ) -> Result<(), DispatchError> {
     let start = now_ms();
     let snapshot = monitor_snapshot_from_payload(&payload);
-    let region: String = cf.region().unwrap_or("unknown".to_string());
-    let colo: String = cf.colo();
+    let RunnerLocation { region, colo } = location;
 
     mark_dispatch_running(
-        &d1,
+        d1,
         &payload.monitor_id,
         &payload.dispatch_id,
         Some(&colo),
@@ -42,18 +57,31 @@ pub async fn handle_dispatch(
     .await
     .map_err(|err| DispatchError::database("dispatch.hot.running", err))?;
 
-    let check = check_monitor(&payload, start, &region, &colo).await;
+    let Attempts {
+        check,
+        trail,
+        started_at,
+        attempts,
+        errors: attempt_errors,
+    } = check_with_retries(&payload, &region, &colo).await;
     let fallback_end = now_ms();
     let mut dispatch_status = "completed";
     let mut dispatch_error: Option<String> = None;
 
     let completion_ts = match check {
-        Ok(result) => {
+        Ok(mut result) => {
+            result.attempts = attempts;
+            result.attempt_errors = attempt_errors;
             let ts = result.timestamp;
-            persist_heartbeat_result(&d1, analytics, &snapshot, result).await?;
+            persist_heartbeat_result(d1, analytics, &payload, &snapshot, result).await?;
             ts
         }
-        Err(DispatchError::CheckFailed(result)) => {
+        Err(DispatchError::CheckFailed(mut result)) => {
+            result.attempts = attempts;
+            result.attempt_errors = attempt_errors;
+            if matches!(payload.kind, MonitorKind::Http) {
+                attach_failure_evidence(evidence_bucket, trail, &mut result).await;
+            }
             let ts = result.timestamp;
             let error_text = result
                 .error
@@ -61,18 +89,21 @@ pub async fn handle_dispatch(
                 .unwrap_or_else(|| "check failed".to_string());
             dispatch_status = "failed";
             dispatch_error = Some(error_text);
-            persist_heartbeat_result(&d1, analytics, &snapshot, result).await?;
+            persist_heartbeat_result(d1, analytics, &payload, &snapshot, result).await?;
             ts
         }
         Err(err) => {
             let failure_ts = fallback_end;
-            let latency_ms = failure_ts - start;
+            let latency_ms = failure_ts - started_at;
             let error_message: String = err.into();
+            let mut attempt_errors = attempt_errors;
+            attempt_errors.push(attempt_error(&error_message));
             dispatch_status = "failed";
             dispatch_error = Some(error_message.clone());
             persist_heartbeat_result(
-                &d1,
+                d1,
                 analytics,
+                &payload,
                 &snapshot,
                 HeartbeatResult {
                     monitor_id: payload.monitor_id.clone(),
@@ -86,6 +117,9 @@ pub async fn handle_dispatch(
                     sample_rate: payload.sample_rate,
                     error: Some(error_message),
                     code: None,
+                    evidence_key: None,
+                    attempts,
+                    attempt_errors,
                 },
             )
             .await?;
@@ -94,7 +128,7 @@ pub async fn handle_dispatch(
     };
 
     finalize_dispatch(
-        &d1,
+        d1,
         &payload.monitor_id,
         &payload.dispatch_id,
         dispatch_status,
@@ -107,26 +141,214 @@ pub async fn handle_dispatch(
     Ok(())
 }
 
+/// Longest error kept per attempt; a heartbeat's blobs share one small size budget.
+const MAX_ATTEMPT_ERROR_CHARS: usize = 200;
+
+/// The last attempt of a check, with what came before it.
+struct Attempts {
+    check: Result<HeartbeatResult, DispatchError>,
+    /// What the last attempt saw, for failure evidence.
+    trail: HttpTrail,
+    started_at: i64,
+    attempts: u32,
+    errors: Vec<String>,
+}
+
+/// Runs the check, retrying a failed attempt up to `payload.retries` times so one flaky
+/// handshake does not count as `Down`. Only the last attempt's result is kept; the earlier
+/// failures survive as their error messages.
+async fn check_with_retries(payload: &DispatchRequest, region: &String, colo: &String) -> Attempts {
+    let mut errors = Vec::new();
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let started_at = now_ms();
+        let mut trail = HttpTrail::new(&payload.monitor_url);
+        let check = check_monitor(payload, started_at, region, colo, &mut trail).await;
+        if let Err(DispatchError::CheckFailed(result)) = &check {
+            errors.push(attempt_error(
+                result.error.as_deref().unwrap_or("check failed"),
+            ));
+            if attempts <= payload.retries {
+                console_log!(
+                    "Check attempt {attempts} failed for monitor {}, retrying",
+                    payload.monitor_id
+                );
+                Delay::from(Duration::from_millis(payload.retry_delay_ms.max(0) as u64)).await;
+                continue;
+            }
+        }
+
+        return Attempts {
+            check,
+            trail,
+            started_at,
+            attempts,
+            errors,
+        };
+    }
+}
+
+fn attempt_error(error: &str) -> String {
+    error.chars().take(MAX_ATTEMPT_ERROR_CHARS).collect()
+}
+
+/// Records a dispatch the runner never finished as a failed check, so it feeds monitor status
+/// and incidents exactly like a check that came back down.
+#[tracing::instrument(
+    name = "internal.dispatch.record_timed_out_dispatch",
+    skip(d1, analytics, payload, runner_colo),
+    fields(monitor_id = %payload.monitor_id, org_id = %payload.org_id, dispatch_id = %payload.dispatch_id)
+)]
+pub async fn record_timed_out_dispatch(
+    d1: &D1Database,
+    analytics: &impl HeartbeatStore,
+    payload: &DispatchRequest,
+    started_at: i64,
+    runner_colo: Option<&str>,
+    error: &str,
+) -> Result<(), DispatchError> {
+    let now = now_ms();
+    let snapshot = monitor_snapshot_from_payload(payload);
+    persist_heartbeat_result(
+        d1,
+        analytics,
+        payload,
+        &snapshot,
+        HeartbeatResult {
+            monitor_id: payload.monitor_id.clone(),
+            org_id: payload.org_id.clone(),
+            dispatch_id: payload.dispatch_id.clone(),
+            timestamp: now,
+            status: MonitorStatus::Down,
+            latency_ms: now - started_at,
+            region: "unknown".to_string(),
+            colo: runner_colo.unwrap_or("unknown").to_string(),
+            sample_rate: payload.sample_rate,
+            error: Some(error.to_string()),
+            code: None,
+            evidence_key: None,
+            attempts: 1,
+            attempt_errors: Vec::new(),
+        },
+    )
+    .await
+}
+
 #[tracing::instrument(
     name = "internal.dispatch.persist_heartbeat_result",
-    skip(d1, analytics, snapshot, result),
+    skip(d1, analytics, payload, snapshot, result),
     fields(monitor_id = %result.monitor_id, org_id = %result.org_id, dispatch_id = %result.dispatch_id)
 )]
 async fn persist_heartbeat_result(
     d1: &D1Database,
-    analytics: &AnalyticsEngineDataset,
+    analytics: &impl HeartbeatStore,
+    payload: &DispatchRequest,
     snapshot: &MonitorStatusSnapshot,
     result: HeartbeatResult,
 ) -> Result<(), DispatchError> {
-    update_monitor_status_for_org(d1, &result, snapshot)
+    record_verdict(d1, &payload.hot_relay_id, payload.scheduled_for_ts, &result)
+        .await
+        .map_err(|err| DispatchError::database("dispatch.hot.verdict", err))?;
+    let aggregate = quorum_aggregate(d1, payload, &result).await?;
+    update_monitor_status_for_org(d1, aggregate.as_ref().unwrap_or(&result), snapshot)
         .await
         .map_err(DispatchError::Monitor)?;
+    let transition = evaluate_incident(
+        d1,
+        &result,
+        &payload.hot_relay_id,
+        payload.failure_threshold,
+        payload.recovery_threshold,
+        payload.down_quorum,
+    )
+    .await
+    .map_err(|err| DispatchError::database("dispatch.incident.evaluate", err))?;
+    match transition {
+        IncidentTransition::Opened { incident_id } => {
+            notify_incident_event(
+                d1,
+                &result.org_id,
+                &incident_id,
+                NotificationEvent::IncidentOpened,
+                None,
+            )
+            .await;
+        }
+        IncidentTransition::Closed { incident_id } => {
+            notify_incident_event(
+                d1,
+                &result.org_id,
+                &incident_id,
+                NotificationEvent::IncidentResolved,
+                None,
+            )
+            .await;
+        }
+        IncidentTransition::Unchanged => {}
+    }
     if should_record(result.sample_rate) {
-        write_heartbeat_to_analytics(analytics, &result).map_err(DispatchError::Heartbeat)?;
+        analytics
+            .write_heartbeat(&result, payload.relay_id.as_deref())
+            .await
+            .map_err(DispatchError::Heartbeat)?;
     }
     Ok(())
 }
 
+/// The monitor-level view of a regional result under a down quorum: `Down` once enough regions
+/// failed this run, `Degraded` while only some have. `None` means the result stands as is.
+/// A below-quorum `Degraded` does not count as down (see `MonitorStatusSnapshot`), so it
+/// leaves `last_failed_at` alone.
+async fn quorum_aggregate(
+    d1: &D1Database,
+    payload: &DispatchRequest,
+    result: &HeartbeatResult,
+) -> Result<Option<HeartbeatResult>, DispatchError> {
+    if payload.down_quorum <= 1 {
+        return Ok(None);
+    }
+
+    let down = count_down_in_window(d1, &payload.monitor_id, payload.scheduled_for_ts)
+        .await
+        .map_err(|err| DispatchError::database("dispatch.hot.quorum", err))?;
+    let quorum = payload.down_quorum;
+    let (status, error) = match down {
+        0 => return Ok(None),
+        _ if down >= quorum && result.status == MonitorStatus::Down => return Ok(None),
+        _ if down >= quorum => (
+            MonitorStatus::Down,
+            format!("Down in {down} regions, meeting the quorum of {quorum}"),
+        ),
+        _ => (
+            MonitorStatus::Degraded,
+            format!("Down in {down} of the {quorum} regions needed to mark the monitor down"),
+        ),
+    };
+
+    Ok(Some(HeartbeatResult {
+        status,
+        error: Some(error),
+        ..result.clone()
+    }))
+}
+
+/// Stores what the failed HTTP check saw and links it from the heartbeat. Best effort: without
+/// a bucket, or if the write fails, the heartbeat simply carries no evidence.
+async fn attach_failure_evidence(
+    bucket: Option<&Bucket>,
+    trail: HttpTrail,
+    result: &mut HeartbeatResult,
+) {
+    let Some(bucket) = bucket else {
+        return;
+    };
+    match put_failure_evidence(bucket, &trail.into_evidence(result)).await {
+        Ok(key) => result.evidence_key = Some(key),
+        Err(err) => console_error!("dispatch.evidence.put: {err:?}"),
+    }
+}
+
 fn should_record(sample_rate: f64) -> bool {
     if sample_rate >= 1.0 {
         return true;
@@ -150,12 +372,13 @@ fn monitor_snapshot_from_payload(payload: &DispatchRequest) -> MonitorStatusSnap
         status,
         first_checked_at: payload.first_checked_at,
         last_failed_at: payload.last_failed_at,
+        down_quorum: payload.down_quorum,
     }
 }
 
 #[tracing::instrument(
     name = "internal.dispatch.check_monitor",
-    skip(payload, start, region, colo),
+    skip(payload, start, region, colo, trail),
     fields(monitor_id = %payload.monitor_id, org_id = %payload.org_id, dispatch_id = %payload.dispatch_id)
 )]
 async fn check_monitor(
@@ -163,9 +386,12 @@ async fn check_monitor(
     start: i64,
     region: &String,
     colo: &String,
+    trail: &mut HttpTrail,
 ) -> Result<HeartbeatResult, DispatchError> {
     let check = match &payload.kind {
-        MonitorKind::Http => check_http_monitor(payload, start, region.clone(), colo.clone()).await,
+        MonitorKind::Http => {
+            check_http_monitor(payload, start, region.clone(), colo.clone(), trail).await
+        }
         MonitorKind::Tcp => check_tcp_monitor(payload, start, region.clone(), colo.clone()).await,
         MonitorKind::Udp => todo!("This will be handled by the container protocol adapter"),
     };
@@ -187,6 +413,9 @@ async fn check_monitor(
                 sample_rate: payload.sample_rate,
                 error: Some(format!("{err:?}")),
                 code: None,
+                evidence_key: None,
+                attempts: 1,
+                attempt_errors: Vec::new(),
             };
 
             return Err(DispatchError::CheckFailed(failure));
@@ -196,36 +425,11 @@ async fn check_monitor(
     Ok(result)
 }
 
-#[tracing::instrument(
-    name = "internal.dispatch.write_heartbeat_to_analytics",
-    skip(dataset, event),
-    fields(monitor_id = %event.monitor_id, org_id = %event.org_id, dispatch_id = %event.dispatch_id)
-)]
-fn write_heartbeat_to_analytics(
-    dataset: &AnalyticsEngineDataset,
-    event: &HeartbeatResult,
-) -> worker::Result<()> {
-    let builder = AnalyticsEngineDataPointBuilder::new()
-        .indexes(vec![event.monitor_id.as_str()])
-        .add_blob(event.org_id.as_str())
-        .add_blob(event.dispatch_id.as_str())
-        .add_double(event.timestamp as f64)
-        .add_blob(event.status)
-        .add_double(event.latency_ms as f64)
-        .add_blob(event.region.as_str())
-        .add_blob(event.colo.as_str())
-        .add_blob(event.error.as_ref().unwrap_or(&String::new()).as_str())
-        .add_double(event.code.unwrap_or(0) as f64)
-        .add_double(event.sample_rate);
-
-    dataset.write_data_point(&builder.build())
-}
-
 const MAX_REDIRECT_DEPTH: u8 = 10;
 
 #[tracing::instrument(
     name = "internal.dispatch.check_http_monitor",
-    skip(payload, start, region, colo),
+    skip(payload, start, region, colo, trail),
     fields(monitor_id = %payload.monitor_id, org_id = %payload.org_id, dispatch_id = %payload.dispatch_id)
 )]
 async fn check_http_monitor(
@@ -233,12 +437,23 @@ async fn check_http_monitor(
     start: i64,
     region: String,
     colo: String,
+    trail: &mut HttpTrail,
 ) -> Result<HeartbeatResult, DispatchError> {
     let mut next_url = payload.monitor_url.clone();
+    let mut method = payload.method;
+    let mut body = payload.body.as_ref();
     let follow_redirects = payload.follow_redirects;
 
     for depth in 0..MAX_REDIRECT_DEPTH {
-        let response = match perform_fetch(&next_url, payload.timeout_ms, payload.verify_tls).await
+        let mut response = match perform_fetch(
+            &next_url,
+            method,
+            &payload.headers,
+            body,
+            payload.timeout_ms,
+            payload.verify_tls,
+        )
+        .await
         {
             Ok(resp) => resp,
             Err(DispatchError::Heartbeat(err)) => {
@@ -255,6 +470,9 @@ async fn check_http_monitor(
                     sample_rate: payload.sample_rate,
                     error: Some(format!("HTTP fetch error: {err:?}")),
                     code: None,
+                    evidence_key: None,
+                    attempts: 1,
+                    attempt_errors: Vec::new(),
                 }));
             }
             Err(other) => return Err(other),
@@ -262,38 +480,11 @@ async fn check_http_monitor(
         let end = now_ms();
 
         match response.status_code() {
-            200..=299 => {
-                if depth > 0 {
-                    console_log!(
-                        "HTTP check passed after {} redirects for monitor {}",
-                        depth,
-                        payload.monitor_id
-                    );
-                }
-                console_log!(
-                    "HTTP check passed for monitor {} {} at {}",
-                    payload.monitor_id,
-                    payload.monitor_url,
-                    end
-                );
-                return Ok(HeartbeatResult {
-                    monitor_id: payload.monitor_id.clone(),
-                    org_id: payload.org_id.clone(),
-                    dispatch_id: payload.dispatch_id.clone(),
-                    timestamp: end,
-                    status: MonitorStatus::Up,
-                    latency_ms: end - start,
-                    region,
-                    colo,
-                    sample_rate: payload.sample_rate,
-                    error: None,
-                    code: None,
-                });
-            }
             300..=399 if follow_redirects => {
                 let location = match response.headers().get("Location") {
                     Ok(Some(loc)) => loc,
                     Ok(None) => {
+                        trail.capture(&next_url, &mut response).await;
                         return Err(DispatchError::CheckFailed(HeartbeatResult {
                             monitor_id: payload.monitor_id.clone(),
                             org_id: payload.org_id.clone(),
@@ -306,9 +497,13 @@ async fn check_http_monitor(
                             sample_rate: payload.sample_rate,
                             error: Some("Redirect location not found".to_string()),
                             code: Some(response.status_code() as u16),
+                            evidence_key: None,
+                            attempts: 1,
+                            attempt_errors: Vec::new(),
                         }));
                     }
                     Err(err) => {
+                        trail.capture(&next_url, &mut response).await;
                         return Err(DispatchError::CheckFailed(HeartbeatResult {
                             monitor_id: payload.monitor_id.clone(),
                             org_id: payload.org_id.clone(),
@@ -321,14 +516,162 @@ async fn check_http_monitor(
                             sample_rate: payload.sample_rate,
                             error: Some(format!("Redirect location not found {err:?}")),
                             code: Some(response.status_code() as u16),
+                            evidence_key: None,
+                            attempts: 1,
+                            attempt_errors: Vec::new(),
                         }));
                     }
                 };
 
+                // 303 See Other always switches the follow-up request to a bodyless GET.
+                if response.status_code() == 303 {
+                    method = HttpMethod::Get;
+                    body = None;
+                }
+                trail.redirect(&next_url, response.status_code(), &location);
                 next_url = location;
                 continue;
             }
+            code if assertions::accepts_status(&payload.assertions, code) => {
+                // Body assertions consume the response; keep an unread copy for evidence.
+                let mut unread = if payload.assertions.iter().any(HttpAssertion::needs_body) {
+                    response.cloned().ok()
+                } else {
+                    None
+                };
+                let failures = assertions::evaluate_body(&payload.assertions, &mut response).await;
+                if !failures.is_empty() {
+                    trail
+                        .capture(&next_url, unread.as_mut().unwrap_or(&mut response))
+                        .await;
+                    let error = assertions::describe_failures(&failures);
+                    console_error!(
+                        "HTTP check failed {} {} {}",
+                        payload.monitor_id,
+                        payload.monitor_url,
+                        error
+                    );
+                    return Err(DispatchError::CheckFailed(HeartbeatResult {
+                        monitor_id: payload.monitor_id.clone(),
+                        org_id: payload.org_id.clone(),
+                        dispatch_id: payload.dispatch_id.clone(),
+                        timestamp: end,
+                        status: MonitorStatus::Down,
+                        latency_ms: end - start,
+                        region,
+                        colo,
+                        sample_rate: payload.sample_rate,
+                        error: Some(error),
+                        code: Some(code),
+                        evidence_key: None,
+                        attempts: 1,
+                        attempt_errors: Vec::new(),
+                    }));
+                }
+
+                let latency_ms = end - start;
+                if let Some(threshold) = payload.down_after_ms.filter(|t| latency_ms > *t) {
+                    trail
+                        .capture(&next_url, unread.as_mut().unwrap_or(&mut response))
+                        .await;
+                    console_error!(
+                        "HTTP check too slow {} {} {}ms",
+                        payload.monitor_id,
+                        payload.monitor_url,
+                        latency_ms
+                    );
+                    return Err(DispatchError::CheckFailed(HeartbeatResult {
+                        monitor_id: payload.monitor_id.clone(),
+                        org_id: payload.org_id.clone(),
+                        dispatch_id: payload.dispatch_id.clone(),
+                        timestamp: end,
+                        status: MonitorStatus::Down,
+                        latency_ms,
+                        region,
+                        colo,
+                        sample_rate: payload.sample_rate,
+                        error: Some(format!(
+                            "Response took {latency_ms} ms, exceeding the down threshold of {threshold} ms"
+                        )),
+                        code: Some(code),
+                        evidence_key: None,
+                        attempts: 1,
+                        attempt_errors: Vec::new(),
+                    }));
+                }
+
+                let (status, error) = match payload.degraded_after_ms {
+                    Some(threshold) if latency_ms > threshold => (
+                        MonitorStatus::Degraded,
+                        Some(format!(
+                            "Response took {latency_ms} ms, exceeding the degraded threshold of {threshold} ms"
+                        )),
+                    ),
+                    _ => (MonitorStatus::Up, None),
+                };
+
+                if depth > 0 {
+                    console_log!(
+                        "HTTP check passed after {} redirects for monitor {}",
+                        depth,
+                        payload.monitor_id
+                    );
+                }
+                console_log!(
+                    "HTTP check passed for monitor {} {} at {} ({})",
+                    payload.monitor_id,
+                    payload.monitor_url,
+                    end,
+                    status
+                );
+                return Ok(HeartbeatResult {
+                    monitor_id: payload.monitor_id.clone(),
+                    org_id: payload.org_id.clone(),
+                    dispatch_id: payload.dispatch_id.clone(),
+                    timestamp: end,
+                    status,
+                    latency_ms,
+                    region,
+                    colo,
+                    sample_rate: payload.sample_rate,
+                    error,
+                    code: None,
+                    evidence_key: None,
+                    attempts: 1,
+                    attempt_errors: Vec::new(),
+                });
+            }
+            code if assertions::has_status_assertion(&payload.assertions) => {
+                let error = assertions::describe_failures(&assertions::status_failures(
+                    &payload.assertions,
+                    code,
+                ));
+                trail.capture(&next_url, &mut response).await;
+                console_error!(
+                    "HTTP check failed {} {} {}",
+                    payload.monitor_id,
+                    payload.monitor_url,
+                    error
+                );
+                return Err(DispatchError::CheckFailed(HeartbeatResult {
+                    monitor_id: payload.monitor_id.clone(),
+                    org_id: payload.org_id.clone(),
+                    dispatch_id: payload.dispatch_id.clone(),
+                    timestamp: end,
+                    status: MonitorStatus::Down,
+                    latency_ms: end - start,
+                    region,
+                    colo,
+                    sample_rate: payload.sample_rate,
+                    error: Some(error),
+                    code: Some(code),
+                    evidence_key: None,
+                    attempts: 1,
+                    attempt_errors: Vec::new(),
+                }));
+            }
             300..=399 => {
+                trail.capture(&next_url, &mut response).await;
                 return Err(DispatchError::CheckFailed(HeartbeatResult {
                     monitor_id: payload.monitor_id.clone(),
                     org_id: payload.org_id.clone(),
@@ -341,9 +684,13 @@ async fn check_http_monitor(
                     sample_rate: payload.sample_rate,
                     error: Some("Redirection not enabled".to_string()),
                     code: None,
+                    evidence_key: None,
+                    attempts: 1,
+                    attempt_errors: Vec::new(),
                 }));
             }
             400..=499 => {
+                trail.capture(&next_url, &mut response).await;
                 console_error!(
                     "HTTP check failed {} {} {}",
                     payload.monitor_id,
@@ -362,9 +709,13 @@ async fn check_http_monitor(
                     sample_rate: payload.sample_rate,
                     error: Some("Client error".to_string()),
                     code: Some(response.status_code() as u16),
+                    evidence_key: None,
+                    attempts: 1,
+                    attempt_errors: Vec::new(),
                 }));
             }
             _ => {
+                trail.capture(&next_url, &mut response).await;
                 console_error!(
                     "HTTP check failed {} {} {}",
                     payload.monitor_id,
@@ -383,6 +734,9 @@ async fn check_http_monitor(
                     sample_rate: payload.sample_rate,
                     error: Some("Server error".to_string()),
                     code: Some(response.status_code() as u16),
+                    evidence_key: None,
+                    attempts: 1,
+                    attempt_errors: Vec::new(),
                 }));
             }
         }
@@ -406,23 +760,33 @@ async fn check_http_monitor(
         sample_rate: payload.sample_rate,
         error: Some("Too many redirects".to_string()),
         code: None,
+        evidence_key: None,
+        attempts: 1,
+        attempt_errors: Vec::new(),
     }))
 }
 
 const DEFAULT_HTTP_TIMEOUT_MS: i64 = 30_000;
+const DEFAULT_TCP_TIMEOUT_MS: i64 = 10_000;
 
 #[tracing::instrument(
     name = "internal.dispatch.perform_fetch",
-    skip(url, timeout_ms, _verify_tls),
-    fields(url = %url, timeout_ms = %timeout_ms)
+    skip(url, method, request_headers, body, timeout_ms, _verify_tls),
+    fields(url = %url, method = %method, timeout_ms = %timeout_ms)
 )]
 async fn perform_fetch(
     url: &str,
+    method: HttpMethod,
+    request_headers: &BTreeMap<String, String>,
+    body: Option<&HttpRequestBody>,
     timeout_ms: i64,
     _verify_tls: bool,
 ) -> Result<Response, DispatchError> {
     let mut init = RequestInit::new();
-    init.with_method(Method::Get);
+    init.with_method(method.into());
+    if let Some(body) = body {
+        init.with_body(Some(JsValue::from_str(&body.to_payload())));
+    }
 
     // NOTE: verify_tls is accepted but not enforced - Workers fetch() doesn't support
     // disabling TLS verification. The option exists for future protocol adapter container
@@ -430,9 +794,19 @@ async fn perform_fetch(
 
     let mut req = Request::new_with_init(url, &init).map_err(DispatchError::Heartbeat)?;
     let headers = req.headers_mut().map_err(DispatchError::Heartbeat)?;
-    headers
-        .set("Content-Type", "application/json")
-        .map_err(DispatchError::Heartbeat)?;
+    for (name, value) in request_headers {
+        headers.set(name, value).map_err(DispatchError::Heartbeat)?;
+    }
+    if let Some(body) = body {
+        if !headers
+            .has("Content-Type")
+            .map_err(DispatchError::Heartbeat)?
+        {
+            headers
+                .set("Content-Type", body.content_type())
+                .map_err(DispatchError::Heartbeat)?;
+        }
+    }
 
     let controller = AbortController::default();
     let signal = controller.signal();
@@ -459,24 +833,63 @@ async fn perform_fetch(
     }
 }
 
+#[tracing::instrument(
+    name = "internal.dispatch.check_tcp_monitor",
+    skip(payload, start, region, colo),
+    fields(monitor_id = %payload.monitor_id, org_id = %payload.org_id, dispatch_id = %payload.dispatch_id)
+)]
 async fn check_tcp_monitor(
     payload: &DispatchRequest,
     start: i64,
     region: String,
     colo: String,
 ) -> Result<HeartbeatResult, DispatchError> {
+    let outcome = match payload.tcp.as_ref() {
+        Some(config) => {
+            let effective_timeout_ms = if payload.timeout_ms <= 0 {
+                DEFAULT_TCP_TIMEOUT_MS
+            } else {
+                payload.timeout_ms
+            } as u64;
+            let probe_future = tcp::probe(config);
+            let timeout_future = Delay::from(Duration::from_millis(effective_timeout_ms));
+
+            pin_mut!(probe_future, timeout_future);
+
+            match select(probe_future, timeout_future).await {
+                Either::Left((result, _)) => result,
+                Either::Right((_unit, _)) => Err(format!(
+                    "TCP check timed out after {effective_timeout_ms} ms"
+                )),
+            }
+        }
+        None => Err("TCP config missing from dispatch payload".to_string()),
+    };
+
     let end = now_ms();
-    Err(DispatchError::CheckFailed(HeartbeatResult {
+    let mut result = HeartbeatResult {
         monitor_id: payload.monitor_id.clone(),
         org_id: payload.org_id.clone(),
         dispatch_id: payload.dispatch_id.clone(),
         timestamp: end,
-        status: MonitorStatus::Down,
+        status: MonitorStatus::Up,
         latency_ms: end - start,
         region,
         colo,
         sample_rate: payload.sample_rate,
-        error: Some("TCP check not implemented".to_string()),
+        error: None,
         code: None,
-    }))
+        evidence_key: None,
+        attempts: 1,
+        attempt_errors: Vec::new(),
+    };
+
+    match outcome {
+        Ok(()) => Ok(result),
+        Err(error) => {
+            result.status = MonitorStatus::Down;
+            result.error = Some(error);
+            Err(DispatchError::CheckFailed(result))
+        }
+    }
 }
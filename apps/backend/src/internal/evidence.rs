@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use futures::StreamExt;
+use worker::Response;
+
+use crate::archive::types::{FailureEvidence, RedirectHop};
+use crate::monitors::types::HeartbeatResult;
+
+/// Response body kept per failure; enough for an error page or JSON error without storing
+/// whole downloads.
+pub const EVIDENCE_BODY_BYTES: usize = 16 * 1024;
+
+/// Headers whose values are credentials rather than diagnostics.
+const REDACTED_HEADERS: [&str; 2] = ["set-cookie", "www-authenticate"];
+
+/// What an HTTP check saw on its way to a verdict: the redirects it followed and, once it
+/// fails, the last response's headers and the start of its body.
+#[derive(Debug, Default)]
+pub struct HttpTrail {
+    requested_url: String,
+    final_url: String,
+    redirects: Vec<RedirectHop>,
+    status_code: Option<u16>,
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+    body_truncated: bool,
+}
+
+impl HttpTrail {
+    pub fn new(url: &str) -> Self {
+        HttpTrail {
+            requested_url: url.to_string(),
+            final_url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn redirect(&mut self, url: &str, status: u16, location: &str) {
+        self.redirects.push(RedirectHop {
+            url: url.to_string(),
+            status,
+            location: location.to_string(),
+        });
+        self.final_url = location.to_string();
+    }
+
+    /// Records the failing response. Reads at most `EVIDENCE_BODY_BYTES` of the body, so the
+    /// response must not have been consumed yet.
+    pub async fn capture(&mut self, url: &str, response: &mut Response) {
+        self.final_url = url.to_string();
+        self.status_code = Some(response.status_code());
+        self.headers = response
+            .headers()
+            .entries()
+            .map(|(name, value)| {
+                if REDACTED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                    (name, "[redacted]".to_string())
+                } else {
+                    (name, value)
+                }
+            })
+            .collect();
+
+        let Ok(mut stream) = response.stream() else {
+            return;
+        };
+        let mut body = Vec::new();
+        while let Some(Ok(chunk)) = stream.next().await {
+            body.extend_from_slice(&chunk);
+            if body.len() > EVIDENCE_BODY_BYTES {
+                body.truncate(EVIDENCE_BODY_BYTES);
+                self.body_truncated = true;
+                break;
+            }
+        }
+        self.body = Some(String::from_utf8_lossy(&body).into_owned());
+    }
+
+    pub fn into_evidence(self, result: &HeartbeatResult) -> FailureEvidence {
+        FailureEvidence {
+            dispatch_id: result.dispatch_id.clone(),
+            monitor_id: result.monitor_id.clone(),
+            org_id: result.org_id.clone(),
+            captured_at: result.timestamp,
+            region: result.region.clone(),
+            colo: result.colo.clone(),
+            requested_url: self.requested_url,
+            final_url: self.final_url,
+            redirects: self.redirects,
+            status_code: self.status_code.or(result.code),
+            error: result.error.clone(),
+            headers: self.headers,
+            body: self.body,
+            body_truncated: self.body_truncated,
+        }
+    }
+}
@@ -1,19 +1,25 @@
+use crate::analytics::extractor::AppAnalytics;
+use crate::archive::service::archive_bucket;
 use crate::auth::membership::load_membership;
+use crate::auth::Role;
 use crate::bootstrap::ticker_bootstrap::ensure_all_tickers;
-use crate::cloudflare::analytics::AppAnalytics;
 use crate::cloudflare::d1::AppDb;
 use crate::cloudflare::durable_objects::relay::AppRelays;
 use crate::cloudflare::durable_objects::ticker::AppTicker;
 use crate::cloudflare::request::RequestCf;
 use crate::internal::dispatch::handle_dispatch;
-use crate::internal::types::{DispatchRequest, MonitorKind, ReconcileResponse};
+use crate::internal::types::{DispatchRequest, MonitorKind, ReconcileResponse, RunnerLocation};
 use crate::monitors::service::create_monitor_for_org;
-use crate::monitors::types::{CreateMonitor, HttpMonitorConfig};
-use crate::relays::service::{list_relays, register_relay};
-use crate::relays::types::{RegisterRelayPayload, RelayRecord};
+use crate::monitors::types::{CreateMonitor, HttpMonitorConfig, MonitorConfig, Placement};
+use crate::relays::service::{
+    delete_relay, list_relays, probe_relay_health, rebootstrap_relay, register_relay, update_relay,
+};
+use crate::relays::types::{
+    DeleteRelayQuery, RegisterRelayPayload, RelayHealth, RelayRecord, UpdateRelayPayload,
+};
 use crate::router::AppState;
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
     response::Result,
     Json,
@@ -74,6 +80,88 @@ pub async fn list_relays_handler(
     list_relays(&d1).await.map(Json).map_err(StatusCode::from)
 }
 
+#[worker::send]
+#[tracing::instrument(
+    name = "internal.handlers.update_relay_handler",
+    skip(d1, auth, payload),
+    fields(relay_id = %relay_id, user = %auth.sub())
+)]
+pub async fn update_relay_handler(
+    Path(relay_id): Path<String>,
+    AppDb(d1): AppDb,
+    auth: User<Role>,
+    Json(payload): Json<UpdateRelayPayload>,
+) -> Result<Json<RelayRecord>, StatusCode> {
+    if !auth.has_role(Role::Admin) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    update_relay(&d1, &relay_id, payload)
+        .await
+        .map(Json)
+        .map_err(StatusCode::from)
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "internal.handlers.delete_relay_handler",
+    skip(relays, d1, auth, query),
+    fields(relay_id = %relay_id, user = %auth.sub())
+)]
+pub async fn delete_relay_handler(
+    Path(relay_id): Path<String>,
+    Query(query): Query<DeleteRelayQuery>,
+    relays: AppRelays,
+    AppDb(d1): AppDb,
+    auth: User<Role>,
+) -> Result<StatusCode, StatusCode> {
+    if !auth.has_role(Role::Admin) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    match delete_relay(&relays, &d1, &relay_id, query.reassign_to.as_deref()).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "internal.handlers.rebootstrap_relay_handler",
+    skip(relays, d1, auth),
+    fields(relay_id = %relay_id, user = %auth.sub())
+)]
+pub async fn rebootstrap_relay_handler(
+    Path(relay_id): Path<String>,
+    relays: AppRelays,
+    AppDb(d1): AppDb,
+    auth: User<Role>,
+) -> Result<Json<RelayRecord>, StatusCode> {
+    if !auth.has_role(Role::Admin) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    rebootstrap_relay(&relays, &d1, &relay_id)
+        .await
+        .map(Json)
+        .map_err(StatusCode::from)
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "internal.handlers.relay_health_handler",
+    skip(relays, d1, _user),
+    fields(relay_id = %relay_id, user = %_user.sub())
+)]
+pub async fn relay_health_handler(
+    Path(relay_id): Path<String>,
+    relays: AppRelays,
+    AppDb(d1): AppDb,
+    _user: User,
+) -> Result<Json<RelayHealth>, StatusCode> {
+    probe_relay_health(&relays, &d1, &relay_id)
+        .await
+        .map(Json)
+        .map_err(StatusCode::from)
+}
+
 #[worker::send]
 #[axum::debug_handler]
 #[tracing::instrument(
@@ -89,12 +177,38 @@ pub async fn dispatch_handler(
     headers: HeaderMap,
     Json(payload): Json<DispatchRequest>,
 ) -> Result<StatusCode, StatusCode> {
+    validate_dispatch_caller(&state, &headers)?;
     validate_dispatch_token(&state, &headers)?;
-    handle_dispatch(d1, &analytics, payload, cf).await?;
+    let evidence_bucket = archive_bucket(&state.env()).ok();
+    handle_dispatch(
+        &d1,
+        &analytics,
+        evidence_bucket.as_ref(),
+        payload,
+        RunnerLocation::resolve(cf).await,
+    )
+    .await?;
 
     Ok(StatusCode::ACCEPTED)
 }
 
+/// With `DISPATCH_BINDING_ONLY=true`, only the `DISPATCH_SERVICE` binding may dispatch.
+/// Requests from the internet always carry `CF-Connecting-IP` (the edge overwrites any
+/// client-supplied value); requests over a service binding never do.
+fn validate_dispatch_caller(state: &AppState, headers: &HeaderMap) -> Result<(), StatusCode> {
+    let binding_only = state
+        .env()
+        .var("DISPATCH_BINDING_ONLY")
+        .map(|value| value.to_string().trim().eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+
+    if binding_only && headers.contains_key("cf-connecting-ip") {
+        console_error!("internal.dispatch.public_caller_rejected");
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
+
 fn validate_dispatch_token(state: &AppState, headers: &HeaderMap) -> Result<(), StatusCode> {
     let expected = state
         .env()
@@ -171,12 +285,20 @@ fn seed_definitions(quantity: usize, relay_id: &str) -> Vec<CreateMonitor> {
         relay_id: &str,
     ) {
         for (idx, url) in urls.iter().cycle().take(count).enumerate() {
-            let config = HttpMonitorConfig::new(url, 60, 7000, true, follow_redirects);
+            let config = MonitorConfig::Http(HttpMonitorConfig::new(
+                url,
+                60,
+                7000,
+                true,
+                follow_redirects,
+            ));
             let monitor = CreateMonitor {
                 name: format!("{prefix} #{:03}", idx + 1),
                 kind: MonitorKind::Http,
                 config,
                 relay_id: relay_id.to_string(),
+                relay_ids: Vec::new(),
+                placement: Placement::Single,
             };
 
             list.push(monitor);
@@ -0,0 +1,95 @@
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use worker::{console_warn, Socket};
+
+use crate::monitors::types::{TcpMonitorConfig, TcpTlsMode};
+
+/// Upper bound on how much we buffer while waiting for `expect`.
+const MAX_EXPECT_READ_BYTES: usize = 16 * 1024;
+
+/// Opens a connection to the configured target and runs the optional send/expect exchange.
+///
+/// With STARTTLS the exchange happens in plaintext and the socket is upgraded once `expect`
+/// has been seen. The caller is responsible for enforcing the overall timeout.
+#[tracing::instrument(
+    name = "internal.tcp.probe",
+    skip(config),
+    fields(host = %config.host, port = %config.port, tls = %config.tls)
+)]
+pub async fn probe(config: &TcpMonitorConfig) -> Result<(), String> {
+    let mut socket = Socket::builder()
+        .secure_transport(config.tls.into())
+        .connect(config.host.trim(), config.port)
+        .map_err(|err| format!("TCP connect error: {err}"))?;
+    socket
+        .opened()
+        .await
+        .map_err(|err| format!("TCP connect error: {err}"))?;
+
+    let exchanged = exchange(&mut socket, config).await;
+    let result = match (config.tls, exchanged) {
+        (TcpTlsMode::StartTls, Ok(())) => {
+            socket = socket.start_tls();
+            socket
+                .opened()
+                .await
+                .map(|_| ())
+                .map_err(|err| format!("STARTTLS upgrade failed: {err}"))
+        }
+        (_, exchanged) => exchanged,
+    };
+
+    if let Err(err) = socket.close().await {
+        console_warn!("internal.tcp.close: {err:?}");
+    }
+
+    result
+}
+
+async fn exchange(socket: &mut Socket, config: &TcpMonitorConfig) -> Result<(), String> {
+    if let Some(send) = &config.send {
+        socket
+            .write_all(send.as_bytes())
+            .await
+            .map_err(|err| format!("TCP write error: {err}"))?;
+        socket
+            .flush()
+            .await
+            .map_err(|err| format!("TCP write error: {err}"))?;
+    }
+
+    if let Some(expect) = &config.expect {
+        read_until(socket, expect).await?;
+    }
+
+    Ok(())
+}
+
+async fn read_until(socket: &mut Socket, expect: &str) -> Result<(), String> {
+    let needle = expect.as_bytes();
+    let mut received = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    loop {
+        let read = socket
+            .read(&mut chunk)
+            .await
+            .map_err(|err| format!("TCP read error: {err}"))?;
+        if read == 0 {
+            return Err(format!("Connection closed before {expect:?} was received"));
+        }
+
+        received.extend_from_slice(&chunk[..read]);
+        if received
+            .windows(needle.len())
+            .any(|window| window == needle)
+        {
+            return Ok(());
+        }
+
+        if received.len() >= MAX_EXPECT_READ_BYTES {
+            return Err(format!(
+                "{expect:?} not found in the first {MAX_EXPECT_READ_BYTES} bytes"
+            ));
+        }
+    }
+}
@@ -1,9 +1,13 @@
-use std::{fmt::Display, str::FromStr};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
 use serde::{Deserialize, Serialize};
-use worker::console_error;
+use worker::{console_error, Cf};
 
-use crate::monitors::types::{HeartbeatResult, MonitorError};
+use crate::cloudflare::trace::runner_colo;
+use crate::monitors::types::{
+    default_failure_threshold, default_recovery_threshold, HeartbeatResult, HttpAssertion,
+    HttpMethod, HttpRequestBody, MonitorError, TcpMonitorConfig,
+};
 
 #[derive(Serialize)]
 pub struct ReconcileResponse {
@@ -12,6 +16,30 @@ pub struct ReconcileResponse {
     pub failed: usize,
 }
 
+/// Where a check ran, recorded with its heartbeat.
+#[derive(Debug, Clone)]
+pub struct RunnerLocation {
+    pub region: String,
+    pub colo: String,
+}
+
+impl RunnerLocation {
+    /// Uses the inbound request's `cf` when there is one. Dispatches over a service binding
+    /// or from the queue consumer have none, so the colo comes from a cached trace lookup.
+    pub async fn resolve(cf: Option<Cf>) -> Self {
+        match cf {
+            Some(cf) => RunnerLocation {
+                region: cf.region().unwrap_or("unknown".to_string()),
+                colo: cf.colo(),
+            },
+            None => RunnerLocation {
+                region: "unknown".to_string(),
+                colo: runner_colo().await,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum MonitorKind {
@@ -43,7 +71,7 @@ impl FromStr for MonitorKind {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DispatchRequest {
     pub dispatch_id: String,
@@ -55,17 +83,55 @@ pub struct DispatchRequest {
     pub timeout_ms: i64,
     pub follow_redirects: bool,
     pub verify_tls: bool,
+    #[serde(default)]
+    pub method: HttpMethod,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: Option<HttpRequestBody>,
+    #[serde(default)]
+    pub assertions: Vec<HttpAssertion>,
+    #[serde(default)]
+    pub degraded_after_ms: Option<i64>,
+    #[serde(default)]
+    pub down_after_ms: Option<i64>,
+    #[serde(default)]
+    pub tcp: Option<TcpMonitorConfig>,
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    #[serde(default = "default_recovery_threshold")]
+    pub recovery_threshold: u32,
     #[serde(default = "default_sample_rate")]
     pub sample_rate: f64,
     pub status: Option<String>,
     pub first_checked_at: Option<i64>,
     pub last_failed_at: Option<i64>,
+    /// Relay the check was sent to; recorded with the heartbeat for the location map.
+    #[serde(default)]
+    pub relay_id: Option<String>,
+    /// Key of the `monitor_dispatch_hot` row this dispatch updates: the relay under `all`
+    /// placement, where runs overlap, and empty otherwise.
+    #[serde(default)]
+    pub hot_relay_id: String,
+    /// Slots that must report `Down` for this run before the monitor is marked down; 1 unless
+    /// the monitor fans out to every relay with a `down_quorum`.
+    #[serde(default = "default_down_quorum")]
+    pub down_quorum: u32,
+    /// Extra attempts after a failed check, `retry_delay_ms` apart.
+    #[serde(default)]
+    pub retries: u32,
+    #[serde(default)]
+    pub retry_delay_ms: i64,
 }
 
 const fn default_sample_rate() -> f64 {
     1.0
 }
 
+const fn default_down_quorum() -> u32 {
+    1
+}
+
 #[derive(Debug)]
 pub enum DispatchError {
     Database {
@@ -3,21 +3,29 @@
 use axum::{body::Body as AxumBody, response::Response as AxumResponse};
 use console_error_panic_hook::set_once as set_panic_hook;
 use tower_service::Service;
-use worker::{Context, Env, HttpRequest, Result};
+use worker::{
+    console_error, console_log, Context, Env, HttpRequest, MessageBatch, Result, ScheduleContext,
+    ScheduledEvent,
+};
 use worker_macros::event;
 
 pub mod analytics;
+pub mod archive;
 pub mod auth;
 pub mod bootstrap;
 pub mod cloudflare;
 pub mod d1c;
 pub mod dispatch_state;
 pub mod external;
+pub mod incidents;
 pub mod internal;
 pub mod monitors;
+pub mod notifications;
 pub mod organizations;
 pub mod relays;
 pub mod router;
+pub mod stats;
+pub mod status_pages;
 pub mod utils;
 
 #[allow(clippy::disallowed_methods)]
@@ -37,3 +45,38 @@ pub async fn main(req: HttpRequest, env: Env, _ctx: Context) -> Result<AxumRespo
 
     Ok(response)
 }
+
+/// Cron triggers (see `[triggers]` in `wrangler.toml`): the per-minute schedule sends due
+/// webhook retries, the daily one exports yesterday's heartbeats to R2.
+#[allow(clippy::disallowed_methods)]
+#[event(scheduled)]
+pub async fn scheduled(event: ScheduledEvent, env: Env, _ctx: ScheduleContext) {
+    set_panic_hook();
+
+    if event.cron() == notifications::webhook::DELIVERY_RETRY_CRON {
+        match notifications::service::run_due_retries(&env).await {
+            Ok(sent) => console_log!("notifications.retries: sent={sent}"),
+            Err(err) => console_error!("notifications.retries: {err:?}"),
+        }
+        return;
+    }
+
+    match archive::service::run_scheduled_archive(&env).await {
+        Ok(run) => console_log!("archive.run: {run:?}"),
+        Err(err) => console_error!("archive.run: {err:?}"),
+    }
+}
+
+/// Queue consumer for `DISPATCH_MODE=queue`: runs dispatch messages and closes out the ones
+/// that land on the dead-letter queue (see `[[queues.consumers]]` in `wrangler.toml`).
+#[allow(clippy::disallowed_methods)]
+#[event(queue)]
+pub async fn queue(
+    batch: MessageBatch<internal::types::DispatchRequest>,
+    env: Env,
+    _ctx: Context,
+) -> Result<()> {
+    set_panic_hook();
+
+    external::queues::dispatch::consume_dispatch_batch(batch, &env).await
+}
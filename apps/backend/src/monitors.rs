@@ -1,4 +1,4 @@
-use crate::router::AppState;
+use crate::{notifications, router::AppState};
 use axum::{
     routing::{delete, get, patch, post},
     Router,
@@ -14,9 +14,27 @@ pub fn router() -> Router<AppState> {
             "/{id}/heartbeats",
             get(handlers::get_monitor_heartbeats_handler),
         )
+        .route("/{id}/series", get(handlers::get_monitor_series_handler))
+        .route("/{id}/stats", get(handlers::get_monitor_stats_handler))
         .route("/{id}", get(handlers::get_monitor_by_id_handler))
         .route("/", get(handlers::get_monitors_handler))
         .route("/", post(handlers::create_monitor_handler))
         .route("/{id}", patch(handlers::update_monitor_handler))
         .route("/{id}", delete(handlers::delete_monitor_handler))
+        .route(
+            "/{id}/notifications",
+            get(notifications::handlers::list_notifications_handler),
+        )
+        .route(
+            "/{id}/notifications",
+            post(notifications::handlers::create_notification_handler),
+        )
+        .route(
+            "/{id}/notifications/{notification_id}",
+            patch(notifications::handlers::update_notification_handler),
+        )
+        .route(
+            "/{id}/notifications/{notification_id}",
+            delete(notifications::handlers::delete_notification_handler),
+        )
 }
@@ -1,11 +1,16 @@
-use crate::analytics::extractor::AppAeClient;
-use crate::analytics::monitor_health::{recent_heartbeats, HeartbeatSample, TimeWindow};
+use crate::analytics::extractor::AppAnalytics;
+use crate::analytics::monitor_health::{
+    heartbeat_series, recent_heartbeats, BucketWidth, HeartbeatBucket, HeartbeatSample, TimeWindow,
+};
+use crate::analytics::stats::{monitor_stats, MonitorStats};
 use crate::auth::membership::load_membership;
 use crate::auth::Role;
 use crate::cloudflare::d1::AppDb;
 use crate::cloudflare::durable_objects::ticker::AppTicker;
 use crate::d1c::queries::monitors::{delete_monitor, get_monitor_by_id, get_monitors_by_org_id};
-use crate::monitors::service::{create_monitor_for_org, update_monitor_for_org};
+use crate::monitors::service::{
+    create_monitor_for_org, load_monitor_relay_state, load_relay_state, update_monitor_for_org,
+};
 use crate::monitors::types::{CreateMonitor, Monitor, MonitorError, UpdateMonitor};
 use crate::utils::date::now_ms;
 use axum::{
@@ -31,11 +36,13 @@ pub async fn get_monitor_by_id_handler(
 ) -> Result<Json<Monitor>, StatusCode> {
     let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
 
-    match get_monitor_by_id(&d1, &id, &org_id).await {
-        Ok(Some(row)) => Monitor::try_from(row).map(Json).map_err(StatusCode::from),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+    let mut monitor = match get_monitor_by_id(&d1, &id, &org_id).await {
+        Ok(Some(row)) => Monitor::try_from(row)?,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    load_monitor_relay_state(&d1, &mut monitor).await?;
+    Ok(Json(monitor))
 }
 
 #[worker::send]
@@ -49,15 +56,15 @@ pub async fn get_monitors_handler(
     auth: User,
 ) -> Result<Json<Vec<Monitor>>, StatusCode> {
     let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
-    match get_monitors_by_org_id(&d1, &org_id).await {
+    let mut monitors = match get_monitors_by_org_id(&d1, &org_id).await {
         Ok(rows) => rows
             .into_iter()
             .map(Monitor::try_from)
-            .collect::<Result<Vec<_>, MonitorError>>()
-            .map(Json)
-            .map_err(StatusCode::from),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+            .collect::<Result<Vec<_>, MonitorError>>()?,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    load_relay_state(&d1, &org_id, &mut monitors).await?;
+    Ok(Json(monitors))
 }
 
 #[worker::send]
@@ -148,13 +155,13 @@ pub struct WindowSummary {
 #[worker::send]
 #[tracing::instrument(
     name = "monitors.http.heartbeats",
-    skip(d1, ae_client),
+    skip(d1, analytics),
     fields(monitor_id = %id)
 )]
 pub async fn get_monitor_heartbeats_handler(
     Path(id): Path<String>,
     AppDb(d1): AppDb,
-    AppAeClient(ae_client): AppAeClient,
+    AppAnalytics(analytics): AppAnalytics,
     Query(params): Query<MonitorHeartbeatsQuery>,
     auth: User,
 ) -> Result<Json<MonitorHeartbeatsResponse>, StatusCode> {
@@ -171,7 +178,7 @@ pub async fn get_monitor_heartbeats_handler(
     let hours = params.window_hours.unwrap_or(24).clamp(1, 720);
     let window = TimeWindow::last_hours(hours, now_ms());
 
-    let items = recent_heartbeats(&ae_client, &id, &org_id, &window, limit)
+    let items = recent_heartbeats(&analytics, &id, &org_id, &window, limit)
         .await
         .map_err(|err| {
             console_error!("monitors.heartbeats.ae: {err:?}");
@@ -190,3 +197,102 @@ pub async fn get_monitor_heartbeats_handler(
 
     Ok(Json(response))
 }
+
+#[worker::send]
+#[tracing::instrument(
+    name = "monitors.http.stats",
+    skip(d1, analytics),
+    fields(monitor_id = %id)
+)]
+pub async fn get_monitor_stats_handler(
+    Path(id): Path<String>,
+    AppDb(d1): AppDb,
+    AppAnalytics(analytics): AppAnalytics,
+    auth: User,
+) -> Result<Json<MonitorStats>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+
+    match get_monitor_by_id(&d1, &id, &org_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+
+    let stats = monitor_stats(&analytics, &org_id, &id, now_ms())
+        .await
+        .map_err(|err| {
+            console_error!("monitors.stats.ae: {err:?}");
+            StatusCode::BAD_GATEWAY
+        })?;
+
+    Ok(Json(stats))
+}
+
+/// Keeps a 1m series over 30 days from turning into one giant response.
+const MAX_SERIES_BUCKETS: i64 = 1_500;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorSeriesQuery {
+    pub bucket: Option<BucketWidth>,
+    pub window_hours: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorSeriesResponse {
+    pub monitor_id: String,
+    pub bucket: BucketWidth,
+    pub bucket_ms: i64,
+    pub window: WindowSummary,
+    pub items: Vec<HeartbeatBucket>,
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "monitors.http.series",
+    skip(d1, analytics),
+    fields(monitor_id = %id)
+)]
+pub async fn get_monitor_series_handler(
+    Path(id): Path<String>,
+    AppDb(d1): AppDb,
+    AppAnalytics(analytics): AppAnalytics,
+    Query(params): Query<MonitorSeriesQuery>,
+    auth: User,
+) -> Result<Json<MonitorSeriesResponse>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+
+    match get_monitor_by_id(&d1, &id, &org_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+
+    let bucket = params.bucket.unwrap_or(BucketWidth::FiveMinutes);
+    let hours = params.window_hours.unwrap_or(24).clamp(1, 720);
+    if hours * 3_600_000 / bucket.ms() > MAX_SERIES_BUCKETS {
+        console_error!("monitors.series.too_many_buckets: hours={hours} bucket={bucket:?}");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let window = TimeWindow::last_hours(hours, now_ms());
+
+    let items = heartbeat_series(&analytics, &id, &org_id, &window, bucket)
+        .await
+        .map_err(|err| {
+            console_error!("monitors.series.ae: {err:?}");
+            StatusCode::BAD_GATEWAY
+        })?;
+
+    Ok(Json(MonitorSeriesResponse {
+        monitor_id: id,
+        bucket,
+        bucket_ms: bucket.ms(),
+        window: WindowSummary {
+            since_ms: window.since_ms,
+            until_ms: window.until_ms,
+            hours,
+        },
+        items,
+    }))
+}
@@ -1,16 +1,22 @@
 use cuid2::create_id;
-use std::{convert::TryFrom, result::Result};
+use std::{convert::TryFrom, result::Result, str::FromStr};
 use worker::wasm_bindgen::JsValue;
-use worker::{console_log, D1Database};
+use worker::{console_log, D1Database, D1PreparedStatement};
 
 use crate::bootstrap::ticker_bootstrap::ensure_ticker_bootstrapped;
 use crate::cloudflare::durable_objects::ticker::AppTicker;
+use crate::d1c::queries::incidents::get_active_incident_for_monitor;
+use crate::d1c::queries::monitor_dispatches::{
+    list_region_verdicts_for_monitor, list_region_verdicts_for_org,
+};
 use crate::d1c::queries::monitors::{
-    create_monitor, get_monitor_by_id, set_monitor_relay, update_monitor_status,
+    create_monitor, delete_monitor_hot_rows_stmt, delete_monitor_relays_stmt, get_monitor_by_id,
+    insert_monitor_relay_stmt, list_monitor_relays, list_monitor_relays_for_org,
+    set_monitor_placement_stmt, update_monitor_status,
 };
 use crate::monitors::types::{
     CreateMonitor, HeartbeatResult, Monitor, MonitorError, MonitorStatus, MonitorStatusSnapshot,
-    UpdateMonitor,
+    Placement, RegionVerdict, UpdateMonitor,
 };
 use crate::relays::errors::RelayError;
 use crate::relays::service::get_relay_by_id;
@@ -29,17 +35,15 @@ pub async fn create_monitor_for_org(
     monitor: CreateMonitor,
 ) -> Result<Monitor, MonitorError> {
     let id = create_id().to_string();
-    let relay_id = monitor.relay_id.trim().to_string();
-    if relay_id.is_empty() {
-        return Err(MonitorError::InvalidConfig(
-            "Relay selection is required".to_string(),
-        ));
-    }
-
-    get_relay_by_id(d1, &relay_id)
-        .await
-        .map_err(relay_error_to_monitor_error)?;
+    let relay_ids = requested_relay_ids(Some(&monitor.relay_id), Some(monitor.relay_ids));
+    validate_placement(d1, monitor.placement, &relay_ids).await?;
 
+    if monitor.config.kind() != monitor.kind {
+        return Err(MonitorError::InvalidConfig(format!(
+            "Config does not match monitor kind {}",
+            monitor.kind
+        )));
+    }
     monitor.config.validate()?;
     let config_json = monitor.config.to_json()?;
     let kind = monitor.kind.to_string();
@@ -63,14 +67,12 @@ pub async fn create_monitor_for_org(
         .await
         .map_err(MonitorError::Bootstrap)?;
 
-    set_monitor_relay(d1, &relay_id, now_ms(), &id, &org_id)
-        .await
-        .map_err(MonitorError::DbRun)?;
+    replace_monitor_relays(d1, org_id, &id, monitor.placement, &relay_ids).await?;
 
     match get_monitor_by_id(d1, &id, &org_id).await {
         Ok(Some(row)) => {
             let mut monitor = Monitor::try_from(row)?;
-            monitor.relay_id = Some(relay_id);
+            monitor.relay_ids = relay_ids;
             Ok(monitor)
         }
         Ok(None) => Err(MonitorError::NotFound),
@@ -80,6 +82,134 @@ pub async fn create_monitor_for_org(
     }
 }
 
+/// `relay_ids` wins over the single `relay_id` shorthand; blanks and repeats are dropped.
+fn requested_relay_ids(relay_id: Option<&str>, relay_ids: Option<Vec<String>>) -> Vec<String> {
+    let requested = match relay_ids {
+        Some(ids) if !ids.is_empty() => ids,
+        _ => relay_id.map(|id| vec![id.to_string()]).unwrap_or_default(),
+    };
+
+    let mut unique: Vec<String> = Vec::with_capacity(requested.len());
+    for id in requested {
+        let id = id.trim();
+        if !id.is_empty() && !unique.iter().any(|seen| seen == id) {
+            unique.push(id.to_string());
+        }
+    }
+    unique
+}
+
+async fn validate_placement(
+    d1: &D1Database,
+    placement: Placement,
+    relay_ids: &[String],
+) -> Result<(), MonitorError> {
+    if relay_ids.is_empty() {
+        return Err(MonitorError::InvalidConfig(
+            "Relay selection is required".to_string(),
+        ));
+    }
+    if placement == Placement::Single && relay_ids.len() > 1 {
+        return Err(MonitorError::InvalidConfig(
+            "Single placement takes exactly one relay".to_string(),
+        ));
+    }
+    for relay_id in relay_ids {
+        get_relay_by_id(d1, relay_id)
+            .await
+            .map_err(relay_error_to_monitor_error)?;
+    }
+    Ok(())
+}
+
+/// Rewrites the monitor's relay list and placement in one batch. The hot rows go too: their
+/// incident counters belong to the old fan-out slots.
+async fn replace_monitor_relays(
+    d1: &D1Database,
+    org_id: &str,
+    monitor_id: &str,
+    placement: Placement,
+    relay_ids: &[String],
+) -> Result<(), MonitorError> {
+    let statements =
+        replace_monitor_relays_statements(d1, org_id, monitor_id, placement, relay_ids)?;
+    d1.batch(statements).await.map_err(MonitorError::DbRun)?;
+    Ok(())
+}
+
+fn replace_monitor_relays_statements(
+    d1: &D1Database,
+    org_id: &str,
+    monitor_id: &str,
+    placement: Placement,
+    relay_ids: &[String],
+) -> Result<Vec<D1PreparedStatement>, MonitorError> {
+    let primary = relay_ids.first().map(String::as_str).unwrap_or_default();
+    let mut statements = vec![
+        set_monitor_placement_stmt(
+            d1,
+            &placement.to_string(),
+            primary,
+            now_ms(),
+            monitor_id,
+            org_id,
+        )?,
+        delete_monitor_relays_stmt(d1, monitor_id)?,
+    ];
+    for (position, relay_id) in relay_ids.iter().enumerate() {
+        statements.push(insert_monitor_relay_stmt(
+            d1,
+            monitor_id,
+            relay_id,
+            position as i64,
+        )?);
+    }
+    statements.push(delete_monitor_hot_rows_stmt(d1, monitor_id)?);
+    Ok(statements)
+}
+
+/// Fills `relay_ids` from `monitor_relays` and `regions` from the hot rows; the row conversion
+/// only knows the primary relay. Loads the whole org in two queries, for list views.
+pub async fn load_relay_state(
+    d1: &D1Database,
+    org_id: &str,
+    monitors: &mut [Monitor],
+) -> Result<(), MonitorError> {
+    let mut assignments = list_monitor_relays_for_org(d1, org_id).await?;
+    let mut verdicts = list_region_verdicts_for_org(d1, org_id).await?;
+    for monitor in monitors.iter_mut() {
+        monitor.relay_ids = assignments
+            .iter_mut()
+            .filter(|row| row.monitor_id == monitor.id)
+            .map(|row| std::mem::take(&mut row.relay_id))
+            .collect();
+        let (own, rest) = std::mem::take(&mut verdicts)
+            .into_iter()
+            .partition(|row| row.monitor_id == monitor.id);
+        verdicts = rest;
+        monitor.regions = own.into_iter().map(RegionVerdict::from).collect();
+    }
+    Ok(())
+}
+
+/// [`load_relay_state`] for a single monitor, reading only its own rows.
+pub async fn load_monitor_relay_state(
+    d1: &D1Database,
+    monitor: &mut Monitor,
+) -> Result<(), MonitorError> {
+    monitor.relay_ids = list_monitor_relays(d1, &monitor.id)
+        .await?
+        .into_iter()
+        .map(|row| row.relay_id)
+        .collect();
+    monitor.regions = list_region_verdicts_for_monitor(d1, &monitor.id)
+        .await?
+        .into_iter()
+        .map(RegionVerdict::from)
+        .collect();
+    Ok(())
+}
+
 fn relay_error_to_monitor_error(err: RelayError) -> MonitorError {
     match err {
         RelayError::Validation { field: _, message } => MonitorError::InvalidConfig(message),
@@ -91,9 +221,61 @@ fn relay_error_to_monitor_error(err: RelayError) -> MonitorError {
         RelayError::Conflict(_) => {
             MonitorError::InvalidConfig("Relay configuration conflict".to_string())
         }
+        RelayError::NotFound => MonitorError::InvalidConfig("relay not found".to_string()),
     }
 }
 
+/// Checks `relayId`/`relayIds`/`placement` from an update and returns the statements that
+/// apply them; omitted parts keep their current value. Empty when nothing changes.
+async fn placement_statements(
+    d1: &D1Database,
+    org_id: &str,
+    monitor_id: &str,
+    relay_id: Option<String>,
+    relay_ids: Option<Vec<String>>,
+    placement: Option<Placement>,
+) -> Result<Vec<D1PreparedStatement>, MonitorError> {
+    let blank_relay_id = relay_id.as_deref().is_none_or(|id| id.trim().is_empty());
+    if blank_relay_id && relay_ids.is_none() && placement.is_none() {
+        return Ok(Vec::new());
+    }
+
+    let Some(current) = get_monitor_by_id(d1, monitor_id, org_id).await? else {
+        return Err(MonitorError::NotFound);
+    };
+    let placement = match placement {
+        Some(placement) => placement,
+        None => Placement::from_str(&current.placement).unwrap_or_default(),
+    };
+    let current_relay_ids: Vec<String> = list_monitor_relays(d1, monitor_id)
+        .await?
+        .into_iter()
+        .map(|row| row.relay_id)
+        .collect();
+    let mut relay_ids = requested_relay_ids(relay_id.as_deref(), relay_ids);
+    if relay_ids.is_empty() {
+        relay_ids = current_relay_ids.clone();
+    }
+    // Edit forms resend the relay on every save; only a real change should reset hot state.
+    if placement.to_string() == current.placement && relay_ids == current_relay_ids {
+        return Ok(Vec::new());
+    }
+
+    // Replacing the relays drops the hot rows, and with them the counters an open incident
+    // needs to recover.
+    if get_active_incident_for_monitor(d1, monitor_id)
+        .await?
+        .is_some()
+    {
+        return Err(MonitorError::Conflict(
+            "Resolve the open incident before changing the monitor's relays".to_string(),
+        ));
+    }
+
+    validate_placement(d1, placement, &relay_ids).await?;
+    replace_monitor_relays_statements(d1, org_id, monitor_id, placement, &relay_ids)
+}
+
 #[tracing::instrument(
     name = "monitors.update_for_org",
     skip(d1),
@@ -115,7 +297,22 @@ pub async fn update_monitor_for_org(
         values.push(JsValue::from_str(&name));
     }
 
-    if let Some(kind) = monitor.kind {
+    // The kind and config_json columns must always agree, so a kind change needs a new config.
+    let kind = match (monitor.kind, monitor.config.as_ref()) {
+        (Some(kind), Some(config)) if config.kind() != kind => {
+            return Err(MonitorError::InvalidConfig(format!(
+                "Config does not match monitor kind {kind}"
+            )));
+        }
+        (Some(_), None) => {
+            return Err(MonitorError::InvalidConfig(
+                "Changing the monitor kind requires a config".to_string(),
+            ));
+        }
+        (kind, config) => kind.or_else(|| config.map(|config| config.kind())),
+    };
+
+    if let Some(kind) = kind {
         fields.push("kind = ?".to_string());
         let kind_str = kind.to_string();
         values.push(JsValue::from_str(&kind_str));
@@ -133,19 +330,31 @@ pub async fn update_monitor_for_org(
         values.push(js_number(enabled as i64));
     }
 
-    if let Some(ref relay_id) = monitor.relay_id {
-        let relay_id = relay_id.trim();
-        if !relay_id.is_empty() {
-            get_relay_by_id(d1, relay_id)
-                .await
-                .map_err(relay_error_to_monitor_error)?;
-            fields.push("relay_id = ?".to_string());
-            values.push(JsValue::from_str(relay_id));
-        }
-    }
+    // Checked before anything is written, then applied in the same batch as the field update
+    // so a failed update cannot leave the relays changed.
+    let mut statements = placement_statements(
+        d1,
+        org_id,
+        monitor_id,
+        monitor.relay_id,
+        monitor.relay_ids,
+        monitor.placement,
+    )
+    .await?;
 
     if fields.is_empty() {
-        return Err(MonitorError::NoFieldsToUpdate);
+        if statements.is_empty() {
+            return Err(MonitorError::NoFieldsToUpdate);
+        }
+        d1.batch(statements).await.map_err(MonitorError::DbRun)?;
+        return match get_monitor_by_id(d1, monitor_id, org_id).await? {
+            Some(row) => {
+                let mut monitor = Monitor::try_from(row)?;
+                load_monitor_relay_state(d1, &mut monitor).await?;
+                Ok(monitor)
+            }
+            None => Err(MonitorError::NotFound),
+        };
     }
 
     fields.push("updated_at = ?".to_string());
@@ -155,7 +364,7 @@ pub async fn update_monitor_for_org(
     values.push(JsValue::from_str(org_id));
 
     let sql = format!(
-        "UPDATE monitors SET {} WHERE id = ? AND org_id = ? RETURNING id, org_id, name, kind, enabled, config_json, status, last_checked_at, last_failed_at, first_checked_at, rt_ms, region, relay_id, last_error, next_run_at, created_at, updated_at",
+        "UPDATE monitors SET {} WHERE id = ? AND org_id = ? RETURNING id, org_id, name, kind, enabled, config_json, status, last_checked_at, last_failed_at, first_checked_at, rt_ms, region, relay_id, placement, last_error, next_run_at, created_at, updated_at",
         fields.join(", ")
     );
 
@@ -163,14 +372,23 @@ pub async fn update_monitor_for_org(
         .prepare(&sql)
         .bind(&values)
         .map_err(MonitorError::DbBind)?;
+    statements.push(query);
 
-    let result = query.first::<crate::d1c::queries::monitors::GetMonitorByIdRow>(None)
+    // The field update runs last, so its RETURNING row is the monitor after every change.
+    let result = d1
+        .batch(statements)
         .await
-        .map_err(MonitorError::DbRun)?;
+        .map_err(MonitorError::DbRun)?
+        .pop()
+        .map(|result| result.results::<crate::d1c::queries::monitors::GetMonitorByIdRow>())
+        .transpose()
+        .map_err(MonitorError::DbRun)?
+        .and_then(|rows| rows.into_iter().next());
 
     match result {
         Some(row) => {
-            let monitor = Monitor::try_from(row)?;
+            let mut monitor = Monitor::try_from(row)?;
+            load_monitor_relay_state(d1, &mut monitor).await?;
             console_log!("updated monitor: {monitor_id}");
             Ok(monitor)
         }
@@ -192,14 +410,16 @@ pub async fn update_monitor_status_for_org(
     let first_checked_at = snapshot.first_checked_at.unwrap_or(now);
 
     // Determine last_failed_at: only update when transitioning to a down state
-    let last_failed_at = if heartbeat.status.is_down() && !snapshot.status.is_down() {
+    let last_failed_at = if snapshot.counts_as_down(&heartbeat.status)
+        && !snapshot.counts_as_down(&snapshot.status)
+    {
         now
     } else {
         snapshot.last_failed_at.unwrap_or(0)
     };
 
     let last_error = heartbeat.error.clone().unwrap_or_else(|| {
-        if heartbeat.status.is_down() {
+        if snapshot.counts_as_down(&heartbeat.status) {
             "Health check failed".to_string()
         } else {
             String::new()
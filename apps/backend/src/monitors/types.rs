@@ -1,8 +1,11 @@
-use std::str::FromStr;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
 
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
-use worker::{console_error, BlobType};
+use worker::{console_error, BlobType, Method, SecureTransport};
 
 use crate::{
     auth::membership::MembershipError, bootstrap::types::BootstrapError,
@@ -13,6 +16,255 @@ fn default_monitor_kind() -> MonitorKind {
     MonitorKind::Http
 }
 
+pub(crate) fn default_failure_threshold() -> u32 {
+    DEFAULT_FAILURE_THRESHOLD
+}
+
+pub(crate) fn default_recovery_threshold() -> u32 {
+    DEFAULT_RECOVERY_THRESHOLD
+}
+
+pub(crate) fn default_retry_delay_ms() -> i64 {
+    DEFAULT_RETRY_DELAY_MS
+}
+
+fn default_attempts() -> u32 {
+    1
+}
+
+const MAX_HTTP_ASSERTIONS: usize = 20;
+const MAX_HTTP_HEADERS: usize = 32;
+const MAX_HTTP_BODY_BYTES: usize = 64 * 1024;
+const MAX_TCP_PAYLOAD_BYTES: usize = 4 * 1024;
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+const DEFAULT_RECOVERY_THRESHOLD: u32 = 2;
+const MAX_INCIDENT_THRESHOLD: u32 = 100;
+const MAX_DOWN_QUORUM: u32 = 32;
+const DEFAULT_RETRY_DELAY_MS: i64 = 1_000;
+const MAX_CHECK_RETRIES: u32 = 3;
+const MAX_RETRY_DELAY_MS: i64 = 10_000;
+
+fn validate_incident_thresholds(failure: u32, recovery: u32) -> Result<(), MonitorError> {
+    for (label, threshold) in [
+        ("Failure threshold", failure),
+        ("Recovery threshold", recovery),
+    ] {
+        if !(1..=MAX_INCIDENT_THRESHOLD).contains(&threshold) {
+            return Err(MonitorError::InvalidConfig(format!(
+                "{label} must be between 1 and {MAX_INCIDENT_THRESHOLD}"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_down_quorum(quorum: Option<u32>) -> Result<(), MonitorError> {
+    match quorum {
+        Some(quorum) if !(1..=MAX_DOWN_QUORUM).contains(&quorum) => {
+            Err(MonitorError::InvalidConfig(format!(
+                "Down quorum must be between 1 and {MAX_DOWN_QUORUM}"
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_retries(retries: u32, retry_delay_ms: i64) -> Result<(), MonitorError> {
+    if retries > MAX_CHECK_RETRIES {
+        return Err(MonitorError::InvalidConfig(format!(
+            "Retries must be at most {MAX_CHECK_RETRIES}"
+        )));
+    }
+
+    if !(0..=MAX_RETRY_DELAY_MS).contains(&retry_delay_ms) {
+        return Err(MonitorError::InvalidConfig(format!(
+            "Retry delay must be between 0 and {MAX_RETRY_DELAY_MS} ms"
+        )));
+    }
+
+    Ok(())
+}
+
+/// RFC 7230 `token` characters.
+fn is_valid_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct StatusRange {
+    pub low: u16,
+    pub high: u16,
+}
+
+impl StatusRange {
+    pub fn contains(&self, code: u16) -> bool {
+        (self.low..=self.high).contains(&code)
+    }
+}
+
+/// A single check applied to the final (post-redirect) HTTP response.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HttpAssertion {
+    Status {
+        #[serde(default)]
+        codes: Vec<u16>,
+        #[serde(default)]
+        ranges: Vec<StatusRange>,
+    },
+    BodyContains {
+        value: String,
+    },
+    BodyNotContains {
+        value: String,
+    },
+    BodyMatches {
+        pattern: String,
+    },
+    JsonPointerEquals {
+        pointer: String,
+        value: serde_json::Value,
+    },
+    JsonPointerExists {
+        pointer: String,
+    },
+    MaxResponseSize {
+        bytes: u64,
+    },
+}
+
+impl HttpAssertion {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HttpAssertion::Status { .. } => "status",
+            HttpAssertion::BodyContains { .. } => "body_contains",
+            HttpAssertion::BodyNotContains { .. } => "body_not_contains",
+            HttpAssertion::BodyMatches { .. } => "body_matches",
+            HttpAssertion::JsonPointerEquals { .. } => "json_pointer_equals",
+            HttpAssertion::JsonPointerExists { .. } => "json_pointer_exists",
+            HttpAssertion::MaxResponseSize { .. } => "max_response_size",
+        }
+    }
+
+    /// Whether evaluating this assertion requires reading the response body.
+    pub fn needs_body(&self) -> bool {
+        !matches!(self, HttpAssertion::Status { .. })
+    }
+
+    pub fn validate(&self) -> Result<(), MonitorError> {
+        let invalid = |reason: &str| {
+            Err(MonitorError::InvalidConfig(format!(
+                "Assertion {}: {reason}",
+                self.name()
+            )))
+        };
+
+        match self {
+            HttpAssertion::Status { codes, ranges } => {
+                if codes.is_empty() && ranges.is_empty() {
+                    return invalid("at least one status code or range is required");
+                }
+                if codes.iter().any(|code| !(100..=599).contains(code)) {
+                    return invalid("status codes must be between 100 and 599");
+                }
+                if ranges
+                    .iter()
+                    .any(|range| range.low > range.high || range.low < 100 || range.high > 599)
+                {
+                    return invalid("status ranges must be ordered and between 100 and 599");
+                }
+            }
+            HttpAssertion::BodyContains { value } | HttpAssertion::BodyNotContains { value } => {
+                if value.is_empty() {
+                    return invalid("value must not be empty");
+                }
+            }
+            HttpAssertion::BodyMatches { pattern } => {
+                if let Err(err) = regex::Regex::new(pattern) {
+                    return invalid(&format!("invalid pattern: {err}"));
+                }
+            }
+            HttpAssertion::JsonPointerEquals { pointer, .. }
+            | HttpAssertion::JsonPointerExists { pointer } => {
+                if !pointer.is_empty() && !pointer.starts_with('/') {
+                    return invalid("JSON pointer must be empty or start with '/'");
+                }
+            }
+            HttpAssertion::MaxResponseSize { bytes } => {
+                if *bytes == 0 {
+                    return invalid("bytes must be greater than 0");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, Default, Display, PartialEq, Eq, EnumString,
+)]
+#[strum(serialize_all = "UPPERCASE", ascii_case_insensitive)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    #[default]
+    Get,
+    Head,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Options,
+}
+
+impl HttpMethod {
+    pub fn allows_body(&self) -> bool {
+        !matches!(self, HttpMethod::Get | HttpMethod::Head)
+    }
+}
+
+impl From<HttpMethod> for Method {
+    fn from(method: HttpMethod) -> Self {
+        match method {
+            HttpMethod::Get => Method::Get,
+            HttpMethod::Head => Method::Head,
+            HttpMethod::Post => Method::Post,
+            HttpMethod::Put => Method::Put,
+            HttpMethod::Patch => Method::Patch,
+            HttpMethod::Delete => Method::Delete,
+            HttpMethod::Options => Method::Options,
+        }
+    }
+}
+
+/// Request body sent with the probe; `json` bodies default to `application/json`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum HttpRequestBody {
+    Text(String),
+    Json(serde_json::Value),
+}
+
+impl HttpRequestBody {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            HttpRequestBody::Text(_) => "text/plain; charset=utf-8",
+            HttpRequestBody::Json(_) => "application/json",
+        }
+    }
+
+    pub fn to_payload(&self) -> String {
+        match self {
+            HttpRequestBody::Text(text) => text.clone(),
+            HttpRequestBody::Json(value) => value.to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HttpMonitorConfig {
     pub url: String,
@@ -20,6 +272,36 @@ pub struct HttpMonitorConfig {
     pub timeout: i64,
     pub verify_tls: bool,
     pub follow_redirects: bool,
+    #[serde(default)]
+    pub method: HttpMethod,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: Option<HttpRequestBody>,
+    #[serde(default)]
+    pub assertions: Vec<HttpAssertion>,
+    /// Successful responses slower than this are recorded as `Degraded`.
+    #[serde(default)]
+    pub degraded_after_ms: Option<i64>,
+    /// Successful responses slower than this are recorded as `Down`.
+    #[serde(default)]
+    pub down_after_ms: Option<i64>,
+    /// Consecutive `Down` checks before an incident is opened.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Consecutive passing checks before the open incident is closed.
+    #[serde(default = "default_recovery_threshold")]
+    pub recovery_threshold: u32,
+    /// With `all` placement, regions that must report `Down` for the same run before the
+    /// monitor is marked down. Unset means any one region.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub down_quorum: Option<u32>,
+    /// Extra attempts after a failed check before it is recorded as `Down`.
+    #[serde(default)]
+    pub retries: u32,
+    /// Pause between attempts.
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: i64,
 }
 
 impl HttpMonitorConfig {
@@ -36,6 +318,17 @@ impl HttpMonitorConfig {
             timeout,
             verify_tls,
             follow_redirects,
+            method: HttpMethod::default(),
+            headers: BTreeMap::new(),
+            body: None,
+            assertions: Vec::new(),
+            degraded_after_ms: None,
+            down_after_ms: None,
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            recovery_threshold: DEFAULT_RECOVERY_THRESHOLD,
+            down_quorum: None,
+            retries: 0,
+            retry_delay_ms: DEFAULT_RETRY_DELAY_MS,
         }
     }
 
@@ -59,6 +352,96 @@ impl HttpMonitorConfig {
             ));
         }
 
+        self.validate_request()?;
+        self.validate_latency_thresholds()?;
+        validate_incident_thresholds(self.failure_threshold, self.recovery_threshold)?;
+        validate_down_quorum(self.down_quorum)?;
+        validate_retries(self.retries, self.retry_delay_ms)?;
+
+        if self.assertions.len() > MAX_HTTP_ASSERTIONS {
+            return Err(MonitorError::InvalidConfig(format!(
+                "At most {MAX_HTTP_ASSERTIONS} assertions are allowed"
+            )));
+        }
+
+        for assertion in &self.assertions {
+            assertion.validate()?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_request(&self) -> Result<(), MonitorError> {
+        if self.headers.len() > MAX_HTTP_HEADERS {
+            return Err(MonitorError::InvalidConfig(format!(
+                "At most {MAX_HTTP_HEADERS} headers are allowed"
+            )));
+        }
+
+        let mut seen = BTreeSet::new();
+        for (name, value) in &self.headers {
+            if !is_valid_header_name(name) {
+                return Err(MonitorError::InvalidConfig(format!(
+                    "Invalid header name: {name:?}"
+                )));
+            }
+            if !seen.insert(name.to_ascii_lowercase()) {
+                return Err(MonitorError::InvalidConfig(format!(
+                    "Duplicate header: {name}"
+                )));
+            }
+            if value.chars().any(|c| c == '\r' || c == '\n' || c == '\0') {
+                return Err(MonitorError::InvalidConfig(format!(
+                    "Header {name} contains invalid characters"
+                )));
+            }
+        }
+
+        if let Some(body) = &self.body {
+            if !self.method.allows_body() {
+                return Err(MonitorError::InvalidConfig(format!(
+                    "{} requests cannot include a body",
+                    self.method
+                )));
+            }
+            if body.to_payload().len() > MAX_HTTP_BODY_BYTES {
+                return Err(MonitorError::InvalidConfig(format!(
+                    "Request body must be at most {MAX_HTTP_BODY_BYTES} bytes"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_latency_thresholds(&self) -> Result<(), MonitorError> {
+        for (label, threshold) in [
+            ("Degraded threshold", self.degraded_after_ms),
+            ("Down threshold", self.down_after_ms),
+        ] {
+            let Some(threshold) = threshold else {
+                continue;
+            };
+            if threshold < 1 {
+                return Err(MonitorError::InvalidConfig(format!(
+                    "{label} must be at least 1 ms"
+                )));
+            }
+            if threshold >= self.timeout {
+                return Err(MonitorError::InvalidConfig(format!(
+                    "{label} must be lower than the timeout"
+                )));
+            }
+        }
+
+        if let (Some(degraded), Some(down)) = (self.degraded_after_ms, self.down_after_ms) {
+            if degraded >= down {
+                return Err(MonitorError::InvalidConfig(
+                    "Degraded threshold must be lower than the down threshold".to_string(),
+                ));
+            }
+        }
+
         Ok(())
     }
 
@@ -73,14 +456,255 @@ impl HttpMonitorConfig {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TcpTlsMode {
+    #[default]
+    Off,
+    On,
+    /// Connect in plaintext, negotiate with `send`/`expect`, then upgrade.
+    #[serde(rename = "starttls")]
+    #[strum(serialize = "starttls")]
+    StartTls,
+}
+
+impl From<TcpTlsMode> for SecureTransport {
+    fn from(mode: TcpTlsMode) -> Self {
+        match mode {
+            TcpTlsMode::Off => SecureTransport::Off,
+            TcpTlsMode::On => SecureTransport::On,
+            TcpTlsMode::StartTls => SecureTransport::StartTls,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TcpMonitorConfig {
+    pub host: String,
+    pub port: u16,
+    pub interval: i64,
+    pub timeout: i64,
+    #[serde(default)]
+    pub tls: TcpTlsMode,
+    /// Written once the connection is open.
+    #[serde(default)]
+    pub send: Option<String>,
+    /// Must appear in the data read back before the check passes.
+    #[serde(default)]
+    pub expect: Option<String>,
+    /// Consecutive `Down` checks before an incident is opened.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Consecutive passing checks before the open incident is closed.
+    #[serde(default = "default_recovery_threshold")]
+    pub recovery_threshold: u32,
+    /// With `all` placement, regions that must report `Down` for the same run before the
+    /// monitor is marked down. Unset means any one region.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub down_quorum: Option<u32>,
+    /// Extra attempts after a failed check before it is recorded as `Down`.
+    #[serde(default)]
+    pub retries: u32,
+    /// Pause between attempts.
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: i64,
+}
+
+impl TcpMonitorConfig {
+    pub fn validate(&self) -> Result<(), MonitorError> {
+        let host = self.host.trim();
+        if host.is_empty() || host.contains("://") || host.contains('/') {
+            return Err(MonitorError::InvalidConfig(
+                "Host must be a bare hostname or IP address".to_string(),
+            ));
+        }
+
+        if self.port == 0 {
+            return Err(MonitorError::InvalidConfig(
+                "Port must be between 1 and 65535".to_string(),
+            ));
+        }
+
+        if self.interval < 15 {
+            return Err(MonitorError::InvalidConfig(
+                "Interval must be at least 15 seconds".to_string(),
+            ));
+        }
+
+        if self.timeout < 1000 {
+            return Err(MonitorError::InvalidConfig(
+                "Timeout must be at least 1000 ms".to_string(),
+            ));
+        }
+
+        for (label, value) in [("Send", &self.send), ("Expect", &self.expect)] {
+            let Some(value) = value else {
+                continue;
+            };
+            if value.is_empty() {
+                return Err(MonitorError::InvalidConfig(format!(
+                    "{label} must not be empty"
+                )));
+            }
+            if value.len() > MAX_TCP_PAYLOAD_BYTES {
+                return Err(MonitorError::InvalidConfig(format!(
+                    "{label} must be at most {MAX_TCP_PAYLOAD_BYTES} bytes"
+                )));
+            }
+        }
+
+        if self.tls == TcpTlsMode::StartTls && self.expect.is_none() {
+            return Err(MonitorError::InvalidConfig(
+                "STARTTLS requires an expect value confirming the upgrade".to_string(),
+            ));
+        }
+
+        validate_incident_thresholds(self.failure_threshold, self.recovery_threshold)?;
+        validate_down_quorum(self.down_quorum)?;
+        validate_retries(self.retries, self.retry_delay_ms)
+    }
+
+    pub fn to_json(&self) -> Result<String, MonitorError> {
+        serde_json::to_string(self)
+            .map_err(|err| MonitorError::InvalidConfig(format!("invalid tcp config: {err}")))
+    }
+
+    pub fn from_json(raw: &str) -> Result<Self, MonitorError> {
+        serde_json::from_str(raw)
+            .map_err(|err| MonitorError::InvalidConfig(format!("invalid tcp config: {err}")))
+    }
+}
+
+/// Kind-specific configuration stored in `monitors.config_json`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum MonitorConfig {
+    Http(HttpMonitorConfig),
+    Tcp(TcpMonitorConfig),
+}
+
+impl MonitorConfig {
+    pub fn from_json(kind: &MonitorKind, raw: &str) -> Result<Self, MonitorError> {
+        match kind {
+            MonitorKind::Http => HttpMonitorConfig::from_json(raw).map(MonitorConfig::Http),
+            MonitorKind::Tcp => TcpMonitorConfig::from_json(raw).map(MonitorConfig::Tcp),
+            MonitorKind::Udp => Err(MonitorError::InvalidConfig(format!(
+                "unsupported monitor kind: {kind}"
+            ))),
+        }
+    }
+
+    pub fn kind(&self) -> MonitorKind {
+        match self {
+            MonitorConfig::Http(_) => MonitorKind::Http,
+            MonitorConfig::Tcp(_) => MonitorKind::Tcp,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), MonitorError> {
+        match self {
+            MonitorConfig::Http(config) => config.validate(),
+            MonitorConfig::Tcp(config) => config.validate(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, MonitorError> {
+        match self {
+            MonitorConfig::Http(config) => config.to_json(),
+            MonitorConfig::Tcp(config) => config.to_json(),
+        }
+    }
+
+    /// Seconds between checks.
+    pub fn interval(&self) -> i64 {
+        match self {
+            MonitorConfig::Http(config) => config.interval,
+            MonitorConfig::Tcp(config) => config.interval,
+        }
+    }
+
+    pub fn timeout(&self) -> i64 {
+        match self {
+            MonitorConfig::Http(config) => config.timeout,
+            MonitorConfig::Tcp(config) => config.timeout,
+        }
+    }
+
+    pub fn failure_threshold(&self) -> u32 {
+        match self {
+            MonitorConfig::Http(config) => config.failure_threshold,
+            MonitorConfig::Tcp(config) => config.failure_threshold,
+        }
+    }
+
+    pub fn recovery_threshold(&self) -> u32 {
+        match self {
+            MonitorConfig::Http(config) => config.recovery_threshold,
+            MonitorConfig::Tcp(config) => config.recovery_threshold,
+        }
+    }
+
+    pub fn down_quorum(&self) -> Option<u32> {
+        match self {
+            MonitorConfig::Http(config) => config.down_quorum,
+            MonitorConfig::Tcp(config) => config.down_quorum,
+        }
+    }
+
+    pub fn retries(&self) -> u32 {
+        match self {
+            MonitorConfig::Http(config) => config.retries,
+            MonitorConfig::Tcp(config) => config.retries,
+        }
+    }
+
+    pub fn retry_delay_ms(&self) -> i64 {
+        match self {
+            MonitorConfig::Http(config) => config.retry_delay_ms,
+            MonitorConfig::Tcp(config) => config.retry_delay_ms,
+        }
+    }
+
+    /// Human-readable target: the URL for HTTP, `host:port` for TCP.
+    pub fn target(&self) -> String {
+        match self {
+            MonitorConfig::Http(config) => config.url.clone(),
+            MonitorConfig::Tcp(config) => format!("{}:{}", config.host, config.port),
+        }
+    }
+}
+
+/// How a monitor's runs are spread over its relays.
+#[derive(
+    Debug, Default, Serialize, Deserialize, Clone, Copy, Display, PartialEq, Eq, EnumString,
+)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+#[serde(rename_all = "snake_case")]
+pub enum Placement {
+    /// Every run goes to the one assigned relay.
+    #[default]
+    Single,
+    /// Each run goes to the next relay in `position` order.
+    RoundRobin,
+    /// Every run fans out to all relays in parallel.
+    All,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub struct CreateMonitor {
     pub name: String,
     #[serde(default = "default_monitor_kind")]
     pub kind: MonitorKind,
-    pub config: HttpMonitorConfig,
+    pub config: MonitorConfig,
+    /// Shorthand for a one-relay `relay_ids`.
+    #[serde(default)]
     pub relay_id: String,
+    #[serde(default)]
+    pub relay_ids: Vec<String>,
+    #[serde(default)]
+    pub placement: Placement,
 }
 
 #[derive(
@@ -115,20 +739,73 @@ pub struct Monitor {
     pub name: String,
     pub kind: MonitorKind,
     pub enabled: i64,
-    pub config: HttpMonitorConfig,
+    pub config: MonitorConfig,
     pub status: String,
     pub last_checked_at: Option<i64>,
     pub last_failed_at: Option<i64>,
     pub first_checked_at: Option<i64>,
     pub rt_ms: Option<i64>,
     pub region: Option<String>,
+    /// First of `relay_ids`, kept for clients that predate multi-relay placement.
     pub relay_id: Option<String>,
+    pub relay_ids: Vec<String>,
+    pub placement: Placement,
+    /// Latest result per fan-out slot; `status` above is the aggregate across them.
+    pub regions: Vec<RegionVerdict>,
     pub last_error: Option<String>,
     pub next_run_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+/// One slot's last reported result. `relay_id` is empty for single and round-robin placement,
+/// which share one slot.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct RegionVerdict {
+    pub relay_id: String,
+    pub region: Option<String>,
+    pub colo: Option<String>,
+    pub status: String,
+    /// `scheduled_for_ts` of the run the result belongs to.
+    pub window_ts: Option<i64>,
+    pub latency_ms: Option<i64>,
+    pub error: Option<String>,
+    pub checked_at: Option<i64>,
+}
+
+impl From<crate::d1c::queries::monitor_dispatches::ListRegionVerdictsForOrgRow> for RegionVerdict {
+    fn from(row: crate::d1c::queries::monitor_dispatches::ListRegionVerdictsForOrgRow) -> Self {
+        RegionVerdict {
+            relay_id: row.relay_id,
+            region: row.verdict_region,
+            colo: row.verdict_colo,
+            status: row.verdict.unwrap_or_default(),
+            window_ts: row.verdict_window_ts,
+            latency_ms: row.verdict_latency_ms,
+            error: row.verdict_error,
+            checked_at: row.verdict_at,
+        }
+    }
+}
+
+impl From<crate::d1c::queries::monitor_dispatches::ListRegionVerdictsForMonitorRow>
+    for RegionVerdict
+{
+    fn from(row: crate::d1c::queries::monitor_dispatches::ListRegionVerdictsForMonitorRow) -> Self {
+        RegionVerdict {
+            relay_id: row.relay_id,
+            region: row.verdict_region,
+            colo: row.verdict_colo,
+            status: row.verdict.unwrap_or_default(),
+            window_ts: row.verdict_window_ts,
+            latency_ms: row.verdict_latency_ms,
+            error: row.verdict_error,
+            checked_at: row.verdict_at,
+        }
+    }
+}
+
 impl TryFrom<crate::d1c::queries::monitors::GetMonitorByIdRow> for Monitor {
     type Error = MonitorError;
 
@@ -139,14 +816,7 @@ impl TryFrom<crate::d1c::queries::monitors::GetMonitorByIdRow> for Monitor {
         let kind = MonitorKind::from_str(&raw_kind).map_err(|_| {
             MonitorError::InvalidConfig(format!("unsupported monitor kind: {raw_kind}"))
         })?;
-        let config = match kind {
-            MonitorKind::Http => HttpMonitorConfig::from_json(&row.config_json)?,
-            _ => {
-                return Err(MonitorError::InvalidConfig(format!(
-                    "unsupported monitor kind: {kind}"
-                )))
-            }
-        };
+        let config = MonitorConfig::from_json(&kind, &row.config_json)?;
 
         Ok(Monitor {
             id: row.id.unwrap_or_default(),
@@ -161,7 +831,10 @@ impl TryFrom<crate::d1c::queries::monitors::GetMonitorByIdRow> for Monitor {
             first_checked_at: row.first_checked_at,
             rt_ms: row.rt_ms,
             region: row.region,
+            relay_ids: row.relay_id.clone().into_iter().collect(),
             relay_id: row.relay_id,
+            placement: Placement::from_str(&row.placement).unwrap_or_default(),
+            regions: Vec::new(),
             last_error: row.last_error,
             next_run_at: row.next_run_at,
             created_at: row.created_at,
@@ -180,14 +853,7 @@ impl TryFrom<crate::d1c::queries::monitors::GetMonitorsByOrgIdRow> for Monitor {
         let kind = MonitorKind::from_str(&raw_kind).map_err(|_| {
             MonitorError::InvalidConfig(format!("unsupported monitor kind: {raw_kind}"))
         })?;
-        let config = match kind {
-            MonitorKind::Http => HttpMonitorConfig::from_json(&row.config_json)?,
-            _ => {
-                return Err(MonitorError::InvalidConfig(format!(
-                    "unsupported monitor kind: {kind}"
-                )))
-            }
-        };
+        let config = MonitorConfig::from_json(&kind, &row.config_json)?;
 
         Ok(Monitor {
             id: row.id.unwrap_or_default(),
@@ -202,7 +868,10 @@ impl TryFrom<crate::d1c::queries::monitors::GetMonitorsByOrgIdRow> for Monitor {
             first_checked_at: row.first_checked_at,
             rt_ms: row.rt_ms,
             region: row.region,
+            relay_ids: row.relay_id.clone().into_iter().collect(),
             relay_id: row.relay_id,
+            placement: Placement::from_str(&row.placement).unwrap_or_default(),
+            regions: Vec::new(),
             last_error: row.last_error,
             next_run_at: row.next_run_at,
             created_at: row.created_at,
@@ -224,6 +893,7 @@ pub enum MonitorError {
     Bootstrap(BootstrapError),
     Membership(MembershipError),
     NoFieldsToUpdate,
+    Conflict(String),
     // pub colo: String,
     // pub extra: Option<serde_json::Value>,
 }
@@ -277,6 +947,10 @@ impl From<MonitorError> for axum::http::StatusCode {
                 console_error!("monitors.invalid.config: {reason}");
                 axum::http::StatusCode::BAD_REQUEST
             }
+            MonitorError::Conflict(reason) => {
+                console_error!("monitors.conflict: {reason}");
+                axum::http::StatusCode::CONFLICT
+            }
         }
     }
 }
@@ -294,6 +968,7 @@ impl From<MonitorError> for String {
             MonitorError::Bootstrap(err) => format!("monitors.bootstrap: {err:?}"),
             MonitorError::Membership(err) => format!("monitors.membership: {err:?}"),
             MonitorError::NoFieldsToUpdate => format!("monitors.no.fields.to.update"),
+            MonitorError::Conflict(reason) => format!("monitors.conflict: {reason}"),
         }
     }
 }
@@ -303,12 +978,14 @@ impl From<MonitorError> for String {
 pub struct UpdateMonitor {
     pub name: Option<String>,
     pub kind: Option<MonitorKind>,
-    pub config: Option<HttpMonitorConfig>,
+    pub config: Option<MonitorConfig>,
     pub enabled: Option<bool>,
     pub relay_id: Option<String>,
+    pub relay_ids: Option<Vec<String>>,
+    pub placement: Option<Placement>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HeartbeatResult {
     // Identity
     pub monitor_id: String,
@@ -328,6 +1005,17 @@ pub struct HeartbeatResult {
     pub error: Option<String>,
 
     pub code: Option<u16>,
+
+    /// R2 key of the captured failure evidence, set after a failed HTTP check.
+    #[serde(default)]
+    pub evidence_key: Option<String>,
+
+    /// Attempts made, including retries; the result above is the last one.
+    #[serde(default = "default_attempts")]
+    pub attempts: u32,
+    /// Error of each failed attempt, in order.
+    #[serde(default)]
+    pub attempt_errors: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -335,4 +1023,17 @@ pub struct MonitorStatusSnapshot {
     pub status: MonitorStatus,
     pub first_checked_at: Option<i64>,
     pub last_failed_at: Option<i64>,
+    pub down_quorum: u32,
+}
+
+impl MonitorStatusSnapshot {
+    /// Under a down quorum `Degraded` only means some regions failed, so the monitor is not
+    /// down until the aggregate says `Down`.
+    pub fn counts_as_down(&self, status: &MonitorStatus) -> bool {
+        if self.down_quorum > 1 {
+            *status == MonitorStatus::Down
+        } else {
+            status.is_down()
+        }
+    }
 }
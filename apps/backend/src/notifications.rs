@@ -0,0 +1,19 @@
+use crate::router::AppState;
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+pub mod handlers;
+pub mod service;
+pub mod types;
+pub mod webhook;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/{id}/deliveries", get(handlers::list_deliveries_handler))
+        .route(
+            "/{id}/deliveries/{delivery_id}/replay",
+            post(handlers::replay_delivery_handler),
+        )
+}
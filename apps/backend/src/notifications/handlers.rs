@@ -0,0 +1,130 @@
+use crate::auth::membership::load_membership;
+use crate::cloudflare::d1::AppDb;
+use crate::notifications::service::{
+    create_notification_for_org, delete_notification_for_org, list_deliveries_for_org,
+    list_notifications_for_org, replay_delivery_for_org, update_notification_for_org,
+};
+use crate::notifications::types::{
+    CreateNotification, ListDeliveriesQuery, NotificationChannel, NotificationDelivery,
+    UpdateNotification,
+};
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::Result,
+    Json,
+};
+use hb_auth::User;
+
+#[worker::send]
+#[tracing::instrument(
+    name = "notifications.http.list",
+    skip(d1),
+    fields(monitor_id = %monitor_id)
+)]
+pub async fn list_notifications_handler(
+    Path(monitor_id): Path<String>,
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<Json<Vec<NotificationChannel>>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match list_notifications_for_org(&d1, &org_id, &monitor_id).await {
+        Ok(channels) => Ok(Json(channels)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "notifications.http.create",
+    skip(d1, notification),
+    fields(monitor_id = %monitor_id, identity_id = %auth.sub())
+)]
+pub async fn create_notification_handler(
+    Path(monitor_id): Path<String>,
+    AppDb(d1): AppDb,
+    auth: User,
+    Json(notification): Json<CreateNotification>,
+) -> Result<Json<NotificationChannel>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match create_notification_for_org(&d1, &org_id, &monitor_id, notification).await {
+        Ok(channel) => Ok(Json(channel)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "notifications.http.update",
+    skip(d1, notification),
+    fields(monitor_id = %monitor_id, notification_id = %notification_id, identity_id = %auth.sub())
+)]
+pub async fn update_notification_handler(
+    Path((monitor_id, notification_id)): Path<(String, String)>,
+    AppDb(d1): AppDb,
+    auth: User,
+    Json(notification): Json<UpdateNotification>,
+) -> Result<Json<NotificationChannel>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match update_notification_for_org(&d1, &org_id, &monitor_id, &notification_id, notification)
+        .await
+    {
+        Ok(channel) => Ok(Json(channel)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "notifications.http.delete",
+    skip(d1),
+    fields(monitor_id = %monitor_id, notification_id = %notification_id, identity_id = %auth.sub())
+)]
+pub async fn delete_notification_handler(
+    Path((monitor_id, notification_id)): Path<(String, String)>,
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<StatusCode, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match delete_notification_for_org(&d1, &org_id, &monitor_id, &notification_id).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "notifications.http.list_deliveries",
+    skip(d1, query),
+    fields(notification_id = %notification_id)
+)]
+pub async fn list_deliveries_handler(
+    Path(notification_id): Path<String>,
+    Query(query): Query<ListDeliveriesQuery>,
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<Json<Vec<NotificationDelivery>>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match list_deliveries_for_org(&d1, &org_id, &notification_id, query).await {
+        Ok(deliveries) => Ok(Json(deliveries)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "notifications.http.replay_delivery",
+    skip(d1),
+    fields(notification_id = %notification_id, event_id = %event_id, identity_id = %auth.sub())
+)]
+pub async fn replay_delivery_handler(
+    Path((notification_id, event_id)): Path<(String, String)>,
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<Json<NotificationDelivery>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match replay_delivery_for_org(&d1, &org_id, &notification_id, &event_id).await {
+        Ok(delivery) => Ok(Json(delivery)),
+        Err(err) => Err(err.into()),
+    }
+}
@@ -0,0 +1,550 @@
+use std::str::FromStr;
+
+use cuid2::create_id;
+use futures::future::join_all;
+use worker::{console_error, console_warn, D1Database, Env};
+
+use crate::cloudflare::d1::get_d1;
+
+use crate::d1c::queries::incidents::get_incident_for_org;
+use crate::d1c::queries::monitors::get_monitor_by_id;
+use crate::d1c::queries::notifications::{
+    claim_delivery_retry, delete_notification, get_latest_delivery_attempt,
+    get_notification_event_for_org, get_notification_for_monitor, get_notification_owner,
+    insert_notification, insert_notification_event, list_due_delivery_retries,
+    list_notification_events_for_org, list_notifications_by_kind, list_notifications_for_monitor,
+    update_notification, GetNotificationForMonitorRow, ListDueDeliveryRetriesRow,
+};
+use crate::incidents::types::{Incident, IncidentActor};
+use crate::notifications::types::{
+    validate_events, validate_webhook_secret, validate_webhook_target, CreateNotification,
+    DeliveryStatus, ListDeliveriesQuery, NotificationChannel, NotificationDelivery,
+    NotificationError, NotificationEvent, NotificationKind, UpdateNotification, WebhookConfig,
+};
+use crate::notifications::webhook::{self, WebhookActor, WebhookPayload, WEBHOOK_PAYLOAD_VERSION};
+use crate::utils::date::now_ms;
+
+const DEFAULT_DELIVERY_PAGE_SIZE: i64 = 100;
+const MAX_DELIVERY_PAGE_SIZE: i64 = 500;
+const MAX_RETRIES_PER_RUN: i64 = 100;
+
+fn generate_secret() -> Result<String, NotificationError> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|err| NotificationError::Secret(err.to_string()))?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+#[tracing::instrument(
+    name = "notifications.list_for_monitor",
+    skip(d1),
+    fields(org_id = %org_id, monitor_id = %monitor_id)
+)]
+pub async fn list_notifications_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    monitor_id: &str,
+) -> Result<Vec<NotificationChannel>, NotificationError> {
+    ensure_monitor(d1, org_id, monitor_id).await?;
+    list_notifications_for_monitor(d1, monitor_id, org_id)
+        .await?
+        .into_iter()
+        .map(NotificationChannel::try_from)
+        .collect()
+}
+
+#[tracing::instrument(
+    name = "notifications.create_for_monitor",
+    skip(d1, input),
+    fields(org_id = %org_id, monitor_id = %monitor_id)
+)]
+pub async fn create_notification_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    monitor_id: &str,
+    input: CreateNotification,
+) -> Result<NotificationChannel, NotificationError> {
+    ensure_monitor(d1, org_id, monitor_id).await?;
+
+    let target = input.target.trim().to_string();
+    validate_webhook_target(&target)?;
+    let events = input.events.unwrap_or_else(NotificationEvent::all);
+    validate_events(&events)?;
+    let secret = match input.secret {
+        Some(secret) => {
+            validate_webhook_secret(&secret)?;
+            secret
+        }
+        None => generate_secret()?,
+    };
+
+    let config = WebhookConfig { secret, events };
+    let id = create_id().to_string();
+    let now = now_ms();
+    insert_notification(
+        d1,
+        &id,
+        monitor_id,
+        input.kind.to_string().as_str(),
+        &target,
+        &config.to_json()?,
+        now,
+        now,
+    )
+    .await?;
+
+    Ok(NotificationChannel {
+        id,
+        monitor_id: monitor_id.to_string(),
+        kind: input.kind,
+        target,
+        events: config.events,
+        secret: Some(config.secret),
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+#[tracing::instrument(
+    name = "notifications.update_for_monitor",
+    skip(d1, input),
+    fields(org_id = %org_id, monitor_id = %monitor_id, notification_id = %notification_id)
+)]
+pub async fn update_notification_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    monitor_id: &str,
+    notification_id: &str,
+    input: UpdateNotification,
+) -> Result<NotificationChannel, NotificationError> {
+    if input.target.is_none() && input.events.is_none() && !input.rotate_secret {
+        return Err(NotificationError::NoFieldsToUpdate);
+    }
+
+    let row = load_notification(d1, org_id, monitor_id, notification_id).await?;
+    let mut config = WebhookConfig::from_json(row.config_json.as_deref())?;
+    let mut target = row.target.clone();
+
+    if let Some(next_target) = input.target {
+        let next_target = next_target.trim().to_string();
+        validate_webhook_target(&next_target)?;
+        target = next_target;
+    }
+
+    if let Some(events) = input.events {
+        validate_events(&events)?;
+        config.events = events;
+    }
+
+    if input.rotate_secret {
+        config.secret = generate_secret()?;
+    }
+
+    let now = now_ms();
+    update_notification(d1, &target, &config.to_json()?, now, notification_id).await?;
+
+    let mut channel = NotificationChannel::try_from(row)?;
+    channel.target = target;
+    channel.events = config.events;
+    channel.updated_at = now;
+    if input.rotate_secret {
+        channel.secret = Some(config.secret);
+    }
+
+    Ok(channel)
+}
+
+#[tracing::instrument(
+    name = "notifications.delete_for_monitor",
+    skip(d1),
+    fields(org_id = %org_id, monitor_id = %monitor_id, notification_id = %notification_id)
+)]
+pub async fn delete_notification_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    monitor_id: &str,
+    notification_id: &str,
+) -> Result<(), NotificationError> {
+    load_notification(d1, org_id, monitor_id, notification_id).await?;
+    delete_notification(d1, notification_id).await?;
+    Ok(())
+}
+
+/// Fans an incident transition out to every webhook on the incident's monitor.
+///
+/// Each channel gets one attempt here; retryable failures are left for the retry cron (see
+/// [`run_due_retries`]). Delivery failures are logged rather than returned: a broken receiver
+/// must never block the dispatch or the dashboard action that caused the transition.
+#[tracing::instrument(
+    name = "notifications.notify_incident_event",
+    skip(d1, actor),
+    fields(org_id = %org_id, incident_id = %incident_id, event = %event)
+)]
+pub async fn notify_incident_event(
+    d1: &D1Database,
+    org_id: &str,
+    incident_id: &str,
+    event: NotificationEvent,
+    actor: Option<&IncidentActor>,
+) {
+    let incident = match get_incident_for_org(d1, incident_id, org_id).await {
+        Ok(Some(row)) => match Incident::try_from(row) {
+            Ok(incident) => incident,
+            Err(err) => {
+                console_error!("notifications.incident.parse: {err:?}");
+                return;
+            }
+        },
+        Ok(None) => {
+            console_warn!("notifications.incident.not_found: {incident_id}");
+            return;
+        }
+        Err(err) => {
+            console_error!("notifications.incident.load: {err:?}");
+            return;
+        }
+    };
+
+    let channels = match list_notifications_by_kind(
+        d1,
+        &incident.monitor_id,
+        NotificationKind::Webhook.to_string().as_str(),
+    )
+    .await
+    {
+        Ok(rows) => rows,
+        Err(err) => {
+            console_error!("notifications.channels.load: {err:?}");
+            return;
+        }
+    };
+
+    let deliveries = channels.into_iter().filter_map(|row| {
+        let channel_id = row.id.clone().unwrap_or_default();
+        let config = match WebhookConfig::from_json(row.config_json.as_deref()) {
+            Ok(config) => config,
+            Err(err) => {
+                console_error!("notifications.channel.config: id={channel_id} {err:?}");
+                return None;
+            }
+        };
+        if !config.subscribes_to(event) {
+            return None;
+        }
+
+        let delivery_id = create_id().to_string();
+        let payload = WebhookPayload {
+            version: WEBHOOK_PAYLOAD_VERSION,
+            delivery_id: &delivery_id,
+            event,
+            occurred_at: now_ms(),
+            org_id,
+            incident: &incident,
+            actor: actor.map(|actor| WebhookActor {
+                id: &actor.id,
+                email: &actor.email,
+            }),
+        };
+        let body = match serde_json::to_string(&payload) {
+            Ok(body) => body,
+            Err(err) => {
+                console_error!("notifications.payload.serialize: {err:?}");
+                return None;
+            }
+        };
+
+        let delivery = PendingDelivery {
+            notification_id: channel_id,
+            incident_id: Some(incident.id.clone()),
+            target: row.target,
+            secret: config.secret,
+            event,
+            delivery_id,
+            payload: body,
+        };
+        Some(async move {
+            match deliver_and_record(d1, &delivery, 1, true).await {
+                Ok(last) if last.status == DeliveryStatus::Failed => {
+                    console_error!(
+                        "notifications.webhook.failed: id={} attempts={} status={:?} error={:?}",
+                        delivery.notification_id,
+                        last.attempt,
+                        last.http_status,
+                        last.error
+                    );
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    console_error!(
+                        "notifications.webhook.record: id={} {err:?}",
+                        delivery.notification_id
+                    );
+                }
+            }
+        })
+    });
+
+    join_all(deliveries).await;
+}
+
+#[tracing::instrument(
+    name = "notifications.list_deliveries",
+    skip(d1, query),
+    fields(org_id = %org_id, notification_id = %notification_id)
+)]
+pub async fn list_deliveries_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    notification_id: &str,
+    query: ListDeliveriesQuery,
+) -> Result<Vec<NotificationDelivery>, NotificationError> {
+    get_notification_owner(d1, notification_id, org_id)
+        .await?
+        .ok_or(NotificationError::NotFound)?;
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DELIVERY_PAGE_SIZE)
+        .clamp(1, MAX_DELIVERY_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0).max(0);
+    list_notification_events_for_org(d1, notification_id, org_id, limit, offset)
+        .await?
+        .into_iter()
+        .map(NotificationDelivery::try_from)
+        .collect()
+}
+
+/// Re-sends the payload of a failed delivery to the channel's current target and secret.
+/// A replay is one attempt, numbered after the original ones under the same delivery id; if
+/// it fails the delivery is left `failed` for the caller to replay again.
+#[tracing::instrument(
+    name = "notifications.replay_delivery",
+    skip(d1),
+    fields(org_id = %org_id, notification_id = %notification_id, event_id = %event_id)
+)]
+pub async fn replay_delivery_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    notification_id: &str,
+    event_id: &str,
+) -> Result<NotificationDelivery, NotificationError> {
+    let row = get_notification_event_for_org(d1, event_id, notification_id, org_id)
+        .await?
+        .ok_or(NotificationError::NotFound)?;
+
+    let latest = get_latest_delivery_attempt(d1, &row.delivery_id).await?;
+    let last_attempt = match latest {
+        Some(latest) if latest.status == DeliveryStatus::Delivered.to_string() => {
+            return Err(NotificationError::Conflict(format!(
+                "delivery {} already succeeded",
+                row.delivery_id
+            )));
+        }
+        Some(latest) if latest.next_retry_at.is_some() => {
+            return Err(NotificationError::Conflict(format!(
+                "delivery {} already has a retry scheduled",
+                row.delivery_id
+            )));
+        }
+        Some(latest) => latest.attempt,
+        None => row.attempt,
+    };
+
+    let event = NotificationEvent::from_str(&row.event).map_err(|_| {
+        NotificationError::InvalidConfig(format!("unknown notification event: {}", row.event))
+    })?;
+    let config = WebhookConfig::from_json(row.config_json.as_deref())?;
+    let delivery = PendingDelivery {
+        notification_id: row.notification_id,
+        incident_id: row.incident_id,
+        target: row.target,
+        secret: config.secret,
+        event,
+        delivery_id: row.delivery_id,
+        payload: row.payload,
+    };
+
+    deliver_and_record(d1, &delivery, last_attempt as u32 + 1, false).await
+}
+
+/// Cron entry point: sends every retry whose `next_retry_at` has passed, claiming each one
+/// first so an overlapping run cannot send it twice. Returns the number of attempts sent.
+#[tracing::instrument(name = "notifications.run_due_retries", skip(env))]
+pub async fn run_due_retries(env: &Env) -> Result<usize, NotificationError> {
+    let d1 = get_d1(env)?;
+    let due = list_due_delivery_retries(&d1, now_ms(), MAX_RETRIES_PER_RUN).await?;
+
+    let retries = due.into_iter().map(|row| {
+        let d1 = &d1;
+        async move {
+            let Some(event_id) = row.id.clone() else {
+                return false;
+            };
+            match claim_delivery_retry(d1, &event_id).await {
+                Ok(Some(_)) => {}
+                Ok(None) => return false,
+                Err(err) => {
+                    console_error!("notifications.retry.claim: id={event_id} {err:?}");
+                    return false;
+                }
+            }
+
+            let retry = match DueRetry::try_from(row) {
+                Ok(retry) => retry,
+                Err(err) => {
+                    console_error!("notifications.retry.load: id={event_id} {err:?}");
+                    return false;
+                }
+            };
+            match deliver_and_record(d1, &retry.delivery, retry.attempt, true).await {
+                Ok(last) if last.status == DeliveryStatus::Failed => {
+                    console_error!(
+                        "notifications.webhook.failed: id={} attempts={} status={:?} error={:?}",
+                        last.notification_id,
+                        last.attempt,
+                        last.http_status,
+                        last.error
+                    );
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    console_error!(
+                        "notifications.webhook.record: id={} {err:?}",
+                        retry.delivery.notification_id
+                    );
+                }
+            }
+            true
+        }
+    });
+
+    Ok(join_all(retries)
+        .await
+        .into_iter()
+        .filter(|sent| *sent)
+        .count())
+}
+
+struct PendingDelivery {
+    notification_id: String,
+    incident_id: Option<String>,
+    target: String,
+    secret: String,
+    event: NotificationEvent,
+    delivery_id: String,
+    payload: String,
+}
+
+/// A due retry loaded by the cron pass, with the attempt number it is about to send.
+struct DueRetry {
+    attempt: u32,
+    delivery: PendingDelivery,
+}
+
+impl TryFrom<ListDueDeliveryRetriesRow> for DueRetry {
+    type Error = NotificationError;
+
+    fn try_from(row: ListDueDeliveryRetriesRow) -> Result<Self, Self::Error> {
+        let event = NotificationEvent::from_str(&row.event).map_err(|_| {
+            NotificationError::InvalidConfig(format!("unknown notification event: {}", row.event))
+        })?;
+        let config = WebhookConfig::from_json(row.config_json.as_deref())?;
+        Ok(DueRetry {
+            attempt: row.attempt as u32 + 1,
+            delivery: PendingDelivery {
+                notification_id: row.notification_id,
+                incident_id: row.incident_id,
+                target: row.target,
+                secret: config.secret,
+                event,
+                delivery_id: row.delivery_id,
+                payload: row.payload,
+            },
+        })
+    }
+}
+
+/// Sends one attempt and logs it to `notification_events`. With `schedule_retry`, a retryable
+/// failure before `MAX_ATTEMPTS` is recorded as `retrying` with a `next_retry_at` for the
+/// retry cron to pick up, so callers never wait out the backoff.
+async fn deliver_and_record(
+    d1: &D1Database,
+    delivery: &PendingDelivery,
+    attempt: u32,
+    schedule_retry: bool,
+) -> Result<NotificationDelivery, NotificationError> {
+    let result = webhook::send(
+        &delivery.target,
+        &delivery.secret,
+        delivery.event,
+        &delivery.delivery_id,
+        &delivery.payload,
+    )
+    .await;
+    let now = now_ms();
+    let (status, next_retry_at) = if result.delivered() {
+        (DeliveryStatus::Delivered, None)
+    } else if schedule_retry && result.retryable() && attempt < webhook::MAX_ATTEMPTS {
+        (
+            DeliveryStatus::Retrying,
+            Some(now + webhook::backoff_ms(attempt) as i64),
+        )
+    } else {
+        (DeliveryStatus::Failed, None)
+    };
+
+    let record = NotificationDelivery {
+        id: create_id().to_string(),
+        delivery_id: delivery.delivery_id.clone(),
+        notification_id: delivery.notification_id.clone(),
+        incident_id: delivery.incident_id.clone(),
+        event: delivery.event.to_string(),
+        attempt: attempt as i64,
+        status,
+        http_status: result.status_code.map(i64::from),
+        latency_ms: Some(result.latency_ms),
+        error: result.error,
+        next_retry_at,
+        created_at: now,
+    };
+    insert_notification_event(
+        d1,
+        &record.id,
+        &record.delivery_id,
+        &record.notification_id,
+        record.incident_id.as_deref(),
+        &record.event,
+        record.attempt,
+        record.status.to_string().as_str(),
+        record.http_status,
+        result.latency_ms,
+        record.error.as_deref(),
+        record.next_retry_at,
+        &delivery.payload,
+        now,
+    )
+    .await?;
+
+    Ok(record)
+}
+
+async fn ensure_monitor(
+    d1: &D1Database,
+    org_id: &str,
+    monitor_id: &str,
+) -> Result<(), NotificationError> {
+    match get_monitor_by_id(d1, monitor_id, org_id).await? {
+        Some(_) => Ok(()),
+        None => Err(NotificationError::MonitorNotFound),
+    }
+}
+
+async fn load_notification(
+    d1: &D1Database,
+    org_id: &str,
+    monitor_id: &str,
+    notification_id: &str,
+) -> Result<GetNotificationForMonitorRow, NotificationError> {
+    get_notification_for_monitor(d1, notification_id, monitor_id, org_id)
+        .await?
+        .ok_or(NotificationError::NotFound)
+}
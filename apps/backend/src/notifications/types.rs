@@ -0,0 +1,306 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use worker::{console_error, Url};
+
+use crate::d1c::queries::notifications::{
+    GetNotificationForMonitorRow, ListNotificationEventsForOrgRow, ListNotificationsForMonitorRow,
+};
+
+const MIN_SECRET_LENGTH: usize = 16;
+const MAX_SECRET_LENGTH: usize = 256;
+
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, Default, Display, PartialEq, Eq, EnumString,
+)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    #[default]
+    Webhook,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Display, PartialEq, Eq, EnumString)]
+pub enum NotificationEvent {
+    #[serde(rename = "incident.opened")]
+    #[strum(serialize = "incident.opened")]
+    IncidentOpened,
+    #[serde(rename = "incident.acknowledged")]
+    #[strum(serialize = "incident.acknowledged")]
+    IncidentAcknowledged,
+    #[serde(rename = "incident.resolved")]
+    #[strum(serialize = "incident.resolved")]
+    IncidentResolved,
+}
+
+impl NotificationEvent {
+    pub fn all() -> Vec<NotificationEvent> {
+        vec![
+            NotificationEvent::IncidentOpened,
+            NotificationEvent::IncidentAcknowledged,
+            NotificationEvent::IncidentResolved,
+        ]
+    }
+}
+
+/// Stored in `notifications.config_json` for `webhook` channels.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebhookConfig {
+    /// HMAC-SHA256 key used to sign every delivery.
+    pub secret: String,
+    #[serde(default = "NotificationEvent::all")]
+    pub events: Vec<NotificationEvent>,
+}
+
+impl WebhookConfig {
+    pub fn to_json(&self) -> Result<String, NotificationError> {
+        serde_json::to_string(self).map_err(|err| {
+            NotificationError::InvalidConfig(format!("invalid webhook config: {err}"))
+        })
+    }
+
+    pub fn from_json(raw: Option<&str>) -> Result<Self, NotificationError> {
+        let raw = raw.ok_or_else(|| {
+            NotificationError::InvalidConfig("webhook config is missing".to_string())
+        })?;
+        serde_json::from_str(raw).map_err(|err| {
+            NotificationError::InvalidConfig(format!("invalid webhook config: {err}"))
+        })
+    }
+
+    pub fn subscribes_to(&self, event: NotificationEvent) -> bool {
+        self.events.contains(&event)
+    }
+}
+
+pub fn validate_webhook_target(target: &str) -> Result<(), NotificationError> {
+    let url = Url::parse(target)
+        .map_err(|err| NotificationError::InvalidConfig(format!("Invalid webhook URL: {err}")))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(NotificationError::InvalidConfig(
+            "Webhook URL must start with http:// or https://".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn validate_webhook_secret(secret: &str) -> Result<(), NotificationError> {
+    if !(MIN_SECRET_LENGTH..=MAX_SECRET_LENGTH).contains(&secret.len()) {
+        return Err(NotificationError::InvalidConfig(format!(
+            "Secret must be between {MIN_SECRET_LENGTH} and {MAX_SECRET_LENGTH} characters"
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn validate_events(events: &[NotificationEvent]) -> Result<(), NotificationError> {
+    if events.is_empty() {
+        return Err(NotificationError::InvalidConfig(
+            "At least one event is required".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct NotificationChannel {
+    pub id: String,
+    pub monitor_id: String,
+    pub kind: NotificationKind,
+    pub target: String,
+    pub events: Vec<NotificationEvent>,
+    /// Only returned when the secret is created or rotated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl TryFrom<ListNotificationsForMonitorRow> for NotificationChannel {
+    type Error = NotificationError;
+
+    fn try_from(row: ListNotificationsForMonitorRow) -> Result<Self, Self::Error> {
+        let kind = NotificationKind::from_str(&row.kind)
+            .map_err(|_| NotificationError::UnsupportedKind(row.kind.clone()))?;
+        let config = WebhookConfig::from_json(row.config_json.as_deref())?;
+
+        Ok(NotificationChannel {
+            id: row.id.unwrap_or_default(),
+            monitor_id: row.monitor_id,
+            kind,
+            target: row.target,
+            events: config.events,
+            secret: None,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+impl TryFrom<GetNotificationForMonitorRow> for NotificationChannel {
+    type Error = NotificationError;
+
+    fn try_from(row: GetNotificationForMonitorRow) -> Result<Self, Self::Error> {
+        let kind = NotificationKind::from_str(&row.kind)
+            .map_err(|_| NotificationError::UnsupportedKind(row.kind.clone()))?;
+        let config = WebhookConfig::from_json(row.config_json.as_deref())?;
+
+        Ok(NotificationChannel {
+            id: row.id.unwrap_or_default(),
+            monitor_id: row.monitor_id,
+            kind,
+            target: row.target,
+            events: config.events,
+            secret: None,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct CreateNotification {
+    #[serde(default)]
+    pub kind: NotificationKind,
+    pub target: String,
+    pub events: Option<Vec<NotificationEvent>>,
+    /// Generated when omitted.
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct UpdateNotification {
+    pub target: Option<String>,
+    pub events: Option<Vec<NotificationEvent>>,
+    #[serde(default)]
+    pub rotate_secret: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Display, PartialEq, Eq, EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Delivered,
+    /// Failed, another attempt is scheduled at `next_retry_at`.
+    Retrying,
+    /// Failed with no attempts left; can be replayed by hand.
+    Failed,
+}
+
+/// One row of the delivery log (a single attempt).
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct NotificationDelivery {
+    pub id: String,
+    pub delivery_id: String,
+    pub notification_id: String,
+    pub incident_id: Option<String>,
+    pub event: String,
+    pub attempt: i64,
+    pub status: DeliveryStatus,
+    pub http_status: Option<i64>,
+    pub latency_ms: Option<i64>,
+    pub error: Option<String>,
+    pub next_retry_at: Option<i64>,
+    pub created_at: i64,
+}
+
+impl TryFrom<ListNotificationEventsForOrgRow> for NotificationDelivery {
+    type Error = NotificationError;
+
+    fn try_from(row: ListNotificationEventsForOrgRow) -> Result<Self, Self::Error> {
+        let status = DeliveryStatus::from_str(&row.status)
+            .map_err(|_| NotificationError::InvalidStatus(row.status.clone()))?;
+
+        Ok(NotificationDelivery {
+            id: row.id.unwrap_or_default(),
+            delivery_id: row.delivery_id,
+            notification_id: row.notification_id,
+            incident_id: row.incident_id,
+            event: row.event,
+            attempt: row.attempt,
+            status,
+            http_status: row.http_status,
+            latency_ms: row.latency_ms,
+            error: row.error,
+            next_retry_at: row.next_retry_at,
+            created_at: row.created_at,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDeliveriesQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug)]
+pub enum NotificationError {
+    DbRun(worker::Error),
+    NotFound,
+    MonitorNotFound,
+    UnsupportedKind(String),
+    InvalidConfig(String),
+    InvalidStatus(String),
+    Secret(String),
+    NoFieldsToUpdate,
+    Conflict(String),
+}
+
+impl From<worker::Error> for NotificationError {
+    fn from(err: worker::Error) -> Self {
+        NotificationError::DbRun(err)
+    }
+}
+
+impl From<NotificationError> for axum::http::StatusCode {
+    fn from(err: NotificationError) -> axum::http::StatusCode {
+        match err {
+            NotificationError::DbRun(err) => {
+                console_error!("notifications.db.run: {err:?}");
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+            NotificationError::NotFound => {
+                console_error!("notifications.not.found");
+                axum::http::StatusCode::NOT_FOUND
+            }
+            NotificationError::MonitorNotFound => {
+                console_error!("notifications.monitor.not.found");
+                axum::http::StatusCode::NOT_FOUND
+            }
+            NotificationError::UnsupportedKind(kind) => {
+                console_error!("notifications.unsupported.kind: {kind}");
+                axum::http::StatusCode::BAD_REQUEST
+            }
+            NotificationError::InvalidConfig(reason) => {
+                console_error!("notifications.invalid.config: {reason}");
+                axum::http::StatusCode::BAD_REQUEST
+            }
+            NotificationError::InvalidStatus(status) => {
+                console_error!("notifications.invalid.status: {status}");
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+            NotificationError::Secret(reason) => {
+                console_error!("notifications.secret: {reason}");
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+            NotificationError::NoFieldsToUpdate => {
+                console_error!("notifications.no.fields.to.update");
+                axum::http::StatusCode::BAD_REQUEST
+            }
+            NotificationError::Conflict(reason) => {
+                console_error!("notifications.conflict: {reason}");
+                axum::http::StatusCode::CONFLICT
+            }
+        }
+    }
+}
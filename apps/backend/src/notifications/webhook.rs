@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use futures::{future::select, future::Either, pin_mut};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use worker::wasm_bindgen::JsValue;
+use worker::{AbortController, Delay, Fetch, Method, Request, RequestInit};
+
+use crate::incidents::types::Incident;
+use crate::notifications::types::NotificationEvent;
+use crate::utils::date::now_ms;
+
+/// Bumped whenever the payload shape changes incompatibly.
+pub const WEBHOOK_PAYLOAD_VERSION: u32 = 1;
+
+pub const SIGNATURE_HEADER: &str = "X-Saavy-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Saavy-Timestamp";
+pub const EVENT_HEADER: &str = "X-Saavy-Event";
+pub const DELIVERY_HEADER: &str = "X-Saavy-Delivery";
+
+pub const MAX_ATTEMPTS: u32 = 4;
+const BASE_BACKOFF_MS: u64 = 60_000;
+
+/// Cron schedule (see `[triggers]` in `wrangler.toml`) that sends retries once they fall due.
+pub const DELIVERY_RETRY_CRON: &str = "* * * * *";
+const REQUEST_TIMEOUT_MS: u64 = 10_000;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookActor<'a> {
+    pub id: &'a str,
+    pub email: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload<'a> {
+    pub version: u32,
+    pub delivery_id: &'a str,
+    pub event: NotificationEvent,
+    pub occurred_at: i64,
+    pub org_id: &'a str,
+    pub incident: &'a Incident,
+    /// `None` when the transition was made by the incident engine.
+    pub actor: Option<WebhookActor<'a>>,
+}
+
+/// Result of a single POST to the receiver.
+#[derive(Debug, Clone)]
+pub struct AttemptResult {
+    pub status_code: Option<u16>,
+    pub latency_ms: i64,
+    pub error: Option<String>,
+}
+
+impl AttemptResult {
+    pub fn delivered(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// 5xx responses, timeouts and network errors are retried; other failures are permanent.
+    pub fn retryable(&self) -> bool {
+        match self.status_code {
+            Some(code) => code >= 500,
+            None => self.error.is_some(),
+        }
+    }
+}
+
+/// `sha256=<hex>` over `"{timestamp}.{body}"`, so receivers can reject replayed deliveries.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> Result<String, String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|err| format!("Invalid signing secret: {err}"))?;
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    Ok(format!("sha256={hex}"))
+}
+
+/// Delay before `attempt + 1`, doubling from one minute to match the retry cron's cadence.
+pub fn backoff_ms(attempt: u32) -> u64 {
+    BASE_BACKOFF_MS << attempt.saturating_sub(1).min(16)
+}
+
+#[tracing::instrument(
+    name = "notifications.webhook.send",
+    skip(target, secret, body),
+    fields(event = %event, delivery_id = %delivery_id)
+)]
+pub async fn send(
+    target: &str,
+    secret: &str,
+    event: NotificationEvent,
+    delivery_id: &str,
+    body: &str,
+) -> AttemptResult {
+    let start = now_ms();
+    let result = send_once(target, secret, event, delivery_id, body).await;
+    let latency_ms = now_ms() - start;
+
+    match result {
+        Ok(code) if (200..=299).contains(&code) => AttemptResult {
+            status_code: Some(code),
+            latency_ms,
+            error: None,
+        },
+        Ok(code) => AttemptResult {
+            status_code: Some(code),
+            latency_ms,
+            error: Some(format!("Webhook responded with HTTP {code}")),
+        },
+        Err(err) => AttemptResult {
+            status_code: None,
+            latency_ms,
+            error: Some(err),
+        },
+    }
+}
+
+async fn send_once(
+    target: &str,
+    secret: &str,
+    event: NotificationEvent,
+    delivery_id: &str,
+    body: &str,
+) -> Result<u16, String> {
+    let timestamp = now_ms();
+    let mut init = RequestInit::new();
+    init.with_method(Method::Post);
+    init.with_body(Some(JsValue::from_str(body)));
+
+    let mut req = Request::new_with_init(target, &init)
+        .map_err(|err| format!("Invalid webhook request: {err}"))?;
+    let headers = req
+        .headers_mut()
+        .map_err(|err| format!("Invalid webhook request: {err}"))?;
+    for (name, value) in [
+        ("Content-Type", "application/json".to_string()),
+        ("User-Agent", "saavy-uptime-webhooks/1".to_string()),
+        (EVENT_HEADER, event.to_string()),
+        (DELIVERY_HEADER, delivery_id.to_string()),
+        (TIMESTAMP_HEADER, timestamp.to_string()),
+        (SIGNATURE_HEADER, sign(secret, timestamp, body)?),
+    ] {
+        headers
+            .set(name, &value)
+            .map_err(|err| format!("Invalid webhook header {name}: {err}"))?;
+    }
+
+    let controller = AbortController::default();
+    let signal = controller.signal();
+    let fetch = Fetch::Request(req);
+    let fetch_future = fetch.send_with_signal(&signal);
+    let timeout_future = Delay::from(Duration::from_millis(REQUEST_TIMEOUT_MS));
+
+    pin_mut!(fetch_future, timeout_future);
+
+    match select(fetch_future, timeout_future).await {
+        Either::Left((Ok(response), _)) => Ok(response.status_code()),
+        Either::Left((Err(err), _)) => Err(format!("Webhook request failed: {err}")),
+        Either::Right((_unit, _)) => {
+            controller.abort();
+            Err(format!(
+                "Webhook request timed out after {REQUEST_TIMEOUT_MS} ms"
+            ))
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use axum::http::StatusCode;
 use worker::console_error;
 
@@ -8,6 +10,7 @@ pub enum RelayError {
         message: String,
     },
     Conflict(&'static str),
+    NotFound,
     Database {
         context: &'static str,
         source: worker::Error,
@@ -47,6 +50,19 @@ impl RelayError {
     }
 }
 
+impl fmt::Display for RelayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelayError::Validation { field, message } => write!(f, "{field}: {message}"),
+            RelayError::Conflict(field) => write!(f, "conflict on {field}"),
+            RelayError::NotFound => write!(f, "relay not found"),
+            RelayError::Database { context, source }
+            | RelayError::DurableObject { context, source } => write!(f, "{context}: {source}"),
+            RelayError::Serialization { context, source } => write!(f, "{context}: {source}"),
+        }
+    }
+}
+
 impl From<RelayError> for StatusCode {
     fn from(err: RelayError) -> Self {
         match &err {
@@ -58,6 +74,10 @@ impl From<RelayError> for StatusCode {
                 console_error!("relay.conflict: field={}", field);
                 StatusCode::CONFLICT
             }
+            RelayError::NotFound => {
+                console_error!("relay.not.found");
+                StatusCode::NOT_FOUND
+            }
             RelayError::Database { context, source } => {
                 console_error!("relay.db: {}: {:?}", context, source);
                 StatusCode::INTERNAL_SERVER_ERROR
@@ -1,18 +1,24 @@
 use cuid2::create_id;
-use serde::Serialize;
-use worker::{wasm_bindgen::JsValue, D1Database, Method, Request, RequestInit, Stub};
+use serde::{Deserialize, Serialize};
+use worker::{
+    console_warn, wasm_bindgen::JsValue, D1Database, D1PreparedStatement, Method, Request,
+    RequestInit, Stub,
+};
 
 use crate::cloudflare::durable_objects::location_hint::{
     DurableObjectJurisdiction, DurableObjectLocationHint,
 };
 use crate::cloudflare::durable_objects::relay::AppRelays;
 use crate::d1c::queries::relays::{
-    find_relay_by_id as find_relay_by_id_query, find_relay_by_slug as find_relay_by_slug_query,
-    insert_relay as insert_relay_query, list_relays as list_relays_query, FindRelayByIdRow,
-    ListRelaysRow,
+    count_relay_monitors, count_relay_open_incidents, delete_relay_assignments_stmt,
+    delete_relay_hot_rows_stmt, delete_relay_stmt, find_relay_by_id as find_relay_by_id_query,
+    find_relay_by_slug as find_relay_by_slug_query, insert_relay as insert_relay_query,
+    list_relays as list_relays_query, reassign_monitor_relays_stmt, reassign_primary_relay_stmt,
+    record_relay_bootstrapped, record_relay_health, update_relay_stmt, FindRelayByIdRow,
+    ListRelaysRow, UpdateRelayRow,
 };
 use crate::relays::errors::RelayError;
-use crate::relays::types::{RegisterRelayPayload, RelayRecord};
+use crate::relays::types::{RegisterRelayPayload, RelayHealth, RelayRecord, UpdateRelayPayload};
 use crate::utils::date::now_ms;
 
 #[derive(Serialize)]
@@ -183,9 +189,19 @@ async fn bootstrap_relay(
         .set("Content-Type", "application/json")
         .map_err(|err| RelayError::durable_object("relays.bootstrap.headers", err))?;
 
-    stub.fetch_with_request(req)
+    let response = stub
+        .fetch_with_request(req)
         .await
         .map_err(|err| RelayError::durable_object("relays.bootstrap.fetch", err))?;
+    if !(200..300).contains(&response.status_code()) {
+        return Err(RelayError::durable_object(
+            "relays.bootstrap.status",
+            worker::Error::RustError(format!(
+                "relay answered bootstrap with {}",
+                response.status_code()
+            )),
+        ));
+    }
 
     Ok(())
 }
@@ -236,6 +252,24 @@ impl From<FindRelayByIdRow> for RelayRecord {
     }
 }
 
+impl From<UpdateRelayRow> for RelayRecord {
+    fn from(row: UpdateRelayRow) -> Self {
+        relay_record_from_row(
+            row.id,
+            row.slug,
+            row.name,
+            row.location_hint,
+            row.jurisdiction,
+            row.durable_object_id,
+            row.enabled,
+            row.last_bootstrapped_at,
+            row.last_error,
+            row.created_at,
+            row.updated_at,
+        )
+    }
+}
+
 impl From<ListRelaysRow> for RelayRecord {
     fn from(row: ListRelaysRow) -> Self {
         relay_record_from_row(
@@ -265,6 +299,250 @@ pub async fn get_relay_by_id(d1: &D1Database, relay_id: &str) -> Result<RelayRec
     let row = find_relay_by_id_query(d1, relay_id)
         .await
         .map_err(|err| RelayError::database("relays.find", err))?;
-    row.map(Into::into)
-        .ok_or_else(|| RelayError::validation("relayId", "relay not found"))
+    row.map(Into::into).ok_or(RelayError::NotFound)
+}
+
+/// What the Relay object reports from `/internal/status`; only the fields the probe checks.
+#[derive(Deserialize)]
+struct RelayStatus {
+    relay_id: Option<String>,
+    bootstrapped_at: Option<i64>,
+    colo: Option<String>,
+}
+
+fn relay_stub(relays: &AppRelays, relay: &RelayRecord) -> Result<Stub, RelayError> {
+    relays
+        .namespace()
+        .id_from_string(&relay.durable_object_id)
+        .and_then(|object_id| object_id.get_stub_with_location_hint(&relay.location_hint))
+        .map_err(|err| RelayError::durable_object("relays.stub", err))
+}
+
+/// Applies the rename/enable change, moving the relay's monitors to `reassign_to` in the same
+/// batch so a failed update leaves them where they were.
+pub async fn update_relay(
+    d1: &D1Database,
+    relay_id: &str,
+    payload: UpdateRelayPayload,
+) -> Result<RelayRecord, RelayError> {
+    let name = payload.name.as_deref().map(normalize_name).transpose()?;
+    let mut statements = match payload.reassign_to.as_deref() {
+        Some(target) => reassign_statements(d1, relay_id, target).await?,
+        None => Vec::new(),
+    };
+    statements.push(
+        update_relay_stmt(
+            d1,
+            name.as_deref(),
+            payload.enabled.map(i64::from),
+            now_ms(),
+            relay_id,
+        )
+        .map_err(|err| RelayError::database("relays.update.prepare", err))?,
+    );
+
+    // The relay update runs last, so its RETURNING row is the relay after every change.
+    d1.batch(statements)
+        .await
+        .map_err(|err| RelayError::database("relays.update", err))?
+        .pop()
+        .map(|result| result.results::<UpdateRelayRow>())
+        .transpose()
+        .map_err(|err| RelayError::database("relays.update.result", err))?
+        .and_then(|rows| rows.into_iter().next())
+        .map(Into::into)
+        .ok_or(RelayError::NotFound)
+}
+
+/// Dropping a relay's hot rows loses the counters and verdicts an open incident on one of its
+/// monitors needs to recover, so that has to be resolved first.
+async fn ensure_no_open_incidents(d1: &D1Database, relay_id: &str) -> Result<(), RelayError> {
+    let open = count_relay_open_incidents(d1, relay_id)
+        .await
+        .map_err(|err| RelayError::database("relays.open_incidents", err))?
+        .unwrap_or(0);
+    if open > 0 {
+        return Err(RelayError::conflict("incidents"));
+    }
+    Ok(())
+}
+
+/// Moves every monitor on `from` to `to`, keeping its position in the monitor's relay order.
+/// `from`'s hot rows go with it; `to` starts fresh ones on its first run.
+async fn reassign_statements(
+    d1: &D1Database,
+    from: &str,
+    to: &str,
+) -> Result<Vec<D1PreparedStatement>, RelayError> {
+    if from == to {
+        return Err(RelayError::validation(
+            "reassignTo",
+            "cannot reassign monitors to the same relay",
+        ));
+    }
+    get_relay_by_id(d1, from).await?;
+    ensure_no_open_incidents(d1, from).await?;
+    let target = match get_relay_by_id(d1, to).await {
+        Err(RelayError::NotFound) => {
+            return Err(RelayError::validation("reassignTo", "relay not found"))
+        }
+        result => result?,
+    };
+    if !target.enabled {
+        return Err(RelayError::validation(
+            "reassignTo",
+            "cannot reassign monitors to a disabled relay",
+        ));
+    }
+
+    let db = |err| RelayError::database("relays.reassign.prepare", err);
+    Ok(vec![
+        reassign_monitor_relays_stmt(d1, to, from).map_err(db)?,
+        reassign_primary_relay_stmt(d1, to, now_ms(), from).map_err(db)?,
+        delete_relay_assignments_stmt(d1, from).map_err(db)?,
+        delete_relay_hot_rows_stmt(d1, from).map_err(db)?,
+    ])
+}
+
+/// Deletes the relay, first moving its monitors to `reassign_to`. Without a target the relay
+/// must have no monitors left.
+pub async fn delete_relay(
+    relays: &AppRelays,
+    d1: &D1Database,
+    relay_id: &str,
+    reassign_to: Option<&str>,
+) -> Result<(), RelayError> {
+    let relay = get_relay_by_id(d1, relay_id).await?;
+    let mut statements = match reassign_to {
+        Some(target) => reassign_statements(d1, relay_id, target).await?,
+        None => {
+            let assigned = count_relay_monitors(d1, relay_id)
+                .await
+                .map_err(|err| RelayError::database("relays.delete.count", err))?
+                .unwrap_or(0);
+            if assigned > 0 {
+                return Err(RelayError::conflict("monitors"));
+            }
+            ensure_no_open_incidents(d1, relay_id).await?;
+            vec![delete_relay_hot_rows_stmt(d1, relay_id)
+                .map_err(|err| RelayError::database("relays.delete.prepare", err))?]
+        }
+    };
+    statements.push(
+        delete_relay_stmt(d1, relay_id)
+            .map_err(|err| RelayError::database("relays.delete.prepare", err))?,
+    );
+    d1.batch(statements)
+        .await
+        .map_err(|err| RelayError::database("relays.delete", err))?;
+
+    // The row is gone either way; a leftover object only costs its (tiny) storage.
+    if let Err(err) = reset_relay_object(relays, &relay).await {
+        console_warn!("relays.delete.reset: relay_id={relay_id} {err}");
+    }
+    Ok(())
+}
+
+async fn reset_relay_object(relays: &AppRelays, relay: &RelayRecord) -> Result<(), RelayError> {
+    let mut init = RequestInit::new();
+    init.with_method(Method::Delete);
+    let req = Request::new_with_init("https://relay/internal/state", &init)
+        .map_err(|err| RelayError::durable_object("relays.reset.request", err))?;
+    relay_stub(relays, relay)?
+        .fetch_with_request(req)
+        .await
+        .map_err(|err| RelayError::durable_object("relays.reset.fetch", err))?;
+    Ok(())
+}
+
+/// Re-sends the bootstrap payload, e.g. after registration left the object unconfigured.
+/// Success clears `last_error`; failure records it.
+pub async fn rebootstrap_relay(
+    relays: &AppRelays,
+    d1: &D1Database,
+    relay_id: &str,
+) -> Result<RelayRecord, RelayError> {
+    let relay = get_relay_by_id(d1, relay_id).await?;
+    let bootstrapped = match relay_stub(relays, &relay) {
+        Ok(stub) => {
+            bootstrap_relay(
+                &stub,
+                &RelayBootstrapPayload {
+                    relay_id: &relay.id,
+                    slug: &relay.slug,
+                    name: &relay.name,
+                    location_hint: &relay.location_hint,
+                    jurisdiction: &relay.jurisdiction,
+                },
+            )
+            .await
+        }
+        Err(err) => Err(err),
+    };
+
+    let now = now_ms();
+    match bootstrapped {
+        Ok(()) => record_relay_bootstrapped(d1, now, now, relay_id)
+            .await
+            .map_err(|err| RelayError::database("relays.rebootstrap.record", err))?,
+        Err(err) => {
+            record_relay_health(d1, Some(err.to_string().as_str()), now, relay_id)
+                .await
+                .map_err(|err| RelayError::database("relays.rebootstrap.record", err))?;
+            return Err(err);
+        }
+    }
+
+    get_relay_by_id(d1, relay_id).await
+}
+
+/// Asks the Relay object for its status and stores the verdict in `last_error`: cleared when
+/// the object answers and knows which relay it is, set otherwise.
+pub async fn probe_relay_health(
+    relays: &AppRelays,
+    d1: &D1Database,
+    relay_id: &str,
+) -> Result<RelayHealth, RelayError> {
+    let relay = get_relay_by_id(d1, relay_id).await?;
+    let status = fetch_relay_status(relays, &relay).await;
+    let error = match &status {
+        Ok(status) if status.relay_id.as_deref() == Some(relay.id.as_str()) => None,
+        Ok(_) => Some("Relay object is not bootstrapped".to_string()),
+        Err(err) => Some(err.to_string()),
+    };
+
+    let checked_at = now_ms();
+    record_relay_health(d1, error.as_deref(), checked_at, relay_id)
+        .await
+        .map_err(|err| RelayError::database("relays.health.record", err))?;
+
+    let status = status.ok();
+    Ok(RelayHealth {
+        relay_id: relay.id,
+        healthy: error.is_none(),
+        error,
+        colo: status.as_ref().and_then(|status| status.colo.clone()),
+        bootstrapped_at: status.and_then(|status| status.bootstrapped_at),
+        checked_at,
+    })
+}
+
+async fn fetch_relay_status(
+    relays: &AppRelays,
+    relay: &RelayRecord,
+) -> Result<RelayStatus, RelayError> {
+    let mut response = relay_stub(relays, relay)?
+        .fetch_with_str("https://relay/internal/status")
+        .await
+        .map_err(|err| RelayError::durable_object("relays.health.fetch", err))?;
+    if !(200..300).contains(&response.status_code()) {
+        return Err(RelayError::durable_object(
+            "relays.health.status",
+            worker::Error::RustError(format!("relay status answered {}", response.status_code())),
+        ));
+    }
+    response
+        .json::<RelayStatus>()
+        .await
+        .map_err(|err| RelayError::durable_object("relays.health.decode", err))
 }
@@ -23,3 +23,33 @@ pub struct RegisterRelayPayload {
     pub name: String,
     pub location_hint: String,
 }
+
+/// Omitted fields keep their value. `reassign_to` moves every monitor on this relay to
+/// another one, e.g. before disabling it.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRelayPayload {
+    pub name: Option<String>,
+    pub enabled: Option<bool>,
+    pub reassign_to: Option<String>,
+}
+
+/// A relay with monitors can only be deleted once they have somewhere else to go.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteRelayQuery {
+    pub reassign_to: Option<String>,
+}
+
+/// Outcome of probing a Relay object's `/internal/status`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayHealth {
+    pub relay_id: String,
+    pub healthy: bool,
+    pub error: Option<String>,
+    /// Colo the object reported running in, once it has run a check.
+    pub colo: Option<String>,
+    pub bootstrapped_at: Option<i64>,
+    pub checked_at: i64,
+}
@@ -1,4 +1,7 @@
-use crate::{bootstrap, internal, monitors, organizations};
+use crate::{
+    archive, bootstrap, incidents, internal, monitors, notifications, organizations, stats,
+    status_pages,
+};
 use axum::{
     body::Body,
     http::Request,
@@ -55,6 +58,11 @@ pub fn create_router(env: &Env) -> WorkerResult<Router> {
 
     let api_router = Router::new()
         .nest("/monitors", monitors::router())
+        .nest("/incidents", incidents::router())
+        .nest("/notifications", notifications::router())
+        .nest("/stats", stats::router())
+        .nest("/status-pages", status_pages::router())
+        .nest("/archive", archive::router())
         .nest("/organizations", organizations::router())
         .nest("/bootstrap", bootstrap::router())
         .nest("/internal", internal::router())
@@ -63,6 +71,11 @@ pub fn create_router(env: &Env) -> WorkerResult<Router> {
 
     Ok(Router::new()
         .route("/api/health", get(|| async { "ok" }))
+        // Public, unauthenticated; `{slug}` may carry a `.json` suffix.
+        .route(
+            "/status/{slug}",
+            get(status_pages::handlers::public_status_page_handler),
+        )
         .nest("/api", api_router)
         .with_state(app_state))
 }
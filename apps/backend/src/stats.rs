@@ -0,0 +1,12 @@
+use crate::router::AppState;
+use axum::{routing::get, Router};
+
+pub mod handlers;
+pub mod service;
+pub mod types;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/summary", get(handlers::get_stats_summary_handler))
+        .route("/locations", get(handlers::get_stats_locations_handler))
+}
@@ -0,0 +1,44 @@
+use crate::analytics::extractor::AppAnalytics;
+use crate::auth::membership::load_membership;
+use crate::cloudflare::d1::AppDb;
+use crate::stats::service::{locations_for_org, summary_for_org};
+use crate::stats::types::{LocationBreakdown, LocationsQuery, StatsSummary};
+use axum::{extract::Query, http::StatusCode, response::Result, Json};
+use hb_auth::User;
+
+#[worker::send]
+#[tracing::instrument(
+    name = "stats.http.summary",
+    skip(d1, analytics),
+    fields(subject = %auth.sub())
+)]
+pub async fn get_stats_summary_handler(
+    AppDb(d1): AppDb,
+    AppAnalytics(analytics): AppAnalytics,
+    auth: User,
+) -> Result<Json<StatsSummary>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match summary_for_org(&d1, &analytics, &org_id).await {
+        Ok(summary) => Ok(Json(summary)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "stats.http.locations",
+    skip(d1, analytics, query),
+    fields(subject = %auth.sub())
+)]
+pub async fn get_stats_locations_handler(
+    AppDb(d1): AppDb,
+    AppAnalytics(analytics): AppAnalytics,
+    auth: User,
+    Query(query): Query<LocationsQuery>,
+) -> Result<Json<LocationBreakdown>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match locations_for_org(&d1, &analytics, &org_id, query).await {
+        Ok(breakdown) => Ok(Json(breakdown)),
+        Err(err) => Err(err.into()),
+    }
+}
@@ -0,0 +1,107 @@
+use futures::future::try_join;
+use worker::D1Database;
+
+use crate::analytics::backend::HeartbeatStore;
+use crate::analytics::monitor_health::TimeWindow;
+use crate::analytics::stats::{fill_windows, location_stats, window_stats, StatsScope};
+use crate::d1c::queries::monitors::{get_monitor_by_id, get_monitors_by_org_id};
+use crate::d1c::queries::relays::list_relays;
+use crate::stats::types::{
+    LocationBreakdown, LocationBreakdownItem, LocationsQuery, MonitorSummary, RelayRef, StatsError,
+    StatsSummary,
+};
+use crate::utils::date::now_ms;
+
+#[tracing::instrument(
+    name = "stats.summary_for_org",
+    skip(d1, analytics),
+    fields(org_id = %org_id)
+)]
+pub async fn summary_for_org(
+    d1: &D1Database,
+    analytics: &impl HeartbeatStore,
+    org_id: &str,
+) -> Result<StatsSummary, StatsError> {
+    let monitors = get_monitors_by_org_id(d1, org_id).await?;
+    let now = now_ms();
+    let (org_rows, monitor_rows) = try_join(
+        window_stats(analytics, org_id, StatsScope::Org, now),
+        window_stats(analytics, org_id, StatsScope::PerMonitor, now),
+    )
+    .await
+    .map_err(StatsError::Analytics)?;
+
+    let monitors = monitors
+        .into_iter()
+        .map(|monitor| {
+            let monitor_id = monitor.id.unwrap_or_default();
+            let windows = monitor_rows
+                .iter()
+                .filter(|(id, _)| id.as_deref() == Some(monitor_id.as_str()))
+                .map(|(_, stats)| stats.clone());
+            MonitorSummary {
+                windows: fill_windows(windows, now),
+                monitor_id,
+                name: monitor.name,
+                status: monitor.status,
+            }
+        })
+        .collect();
+
+    Ok(StatsSummary {
+        generated_at: now,
+        windows: fill_windows(org_rows.into_iter().map(|(_, stats)| stats), now),
+        monitors,
+    })
+}
+
+#[tracing::instrument(
+    name = "stats.locations_for_org",
+    skip(d1, analytics, query),
+    fields(org_id = %org_id, monitor_id = ?query.monitor_id)
+)]
+pub async fn locations_for_org(
+    d1: &D1Database,
+    analytics: &impl HeartbeatStore,
+    org_id: &str,
+    query: LocationsQuery,
+) -> Result<LocationBreakdown, StatsError> {
+    if let Some(monitor_id) = query.monitor_id.as_deref() {
+        get_monitor_by_id(d1, monitor_id, org_id)
+            .await?
+            .ok_or(StatsError::MonitorNotFound)?;
+    }
+
+    let hours = query.window_hours.unwrap_or(24).clamp(1, 720);
+    let window = TimeWindow::last_hours(hours, now_ms());
+    let groups = location_stats(analytics, org_id, query.monitor_id.as_deref(), &window)
+        .await
+        .map_err(StatsError::Analytics)?;
+    let relays = list_relays(d1).await?;
+
+    let items = groups
+        .into_iter()
+        .map(|stats| {
+            let relay = stats.relay_id.as_deref().and_then(|relay_id| {
+                relays
+                    .iter()
+                    .find(|relay| relay.id.as_deref() == Some(relay_id))
+                    .map(|relay| RelayRef {
+                        id: relay_id.to_string(),
+                        slug: relay.slug.clone(),
+                        name: relay.name.clone(),
+                        location_hint: relay.location_hint.clone(),
+                    })
+            });
+            LocationBreakdownItem { stats, relay }
+        })
+        .collect();
+
+    Ok(LocationBreakdown {
+        monitor_id: query.monitor_id,
+        since_ms: window.since_ms,
+        until_ms: window.until_ms,
+        hours,
+        items,
+    })
+}
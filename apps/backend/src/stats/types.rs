@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use worker::console_error;
+
+use crate::analytics::stats::{LocationStats, WindowStats};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct MonitorSummary {
+    pub monitor_id: String,
+    pub name: String,
+    pub status: String,
+    pub windows: Vec<WindowStats>,
+}
+
+/// Org-wide dashboard cards plus the per-monitor breakdown behind them.
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct StatsSummary {
+    pub generated_at: i64,
+    pub windows: Vec<WindowStats>,
+    pub monitors: Vec<MonitorSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationsQuery {
+    /// Org-wide when omitted.
+    pub monitor_id: Option<String>,
+    pub window_hours: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct RelayRef {
+    pub id: String,
+    pub slug: String,
+    pub name: String,
+    pub location_hint: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct LocationBreakdownItem {
+    #[serde(flatten)]
+    pub stats: LocationStats,
+    /// `None` for heartbeats without a relay id or whose relay has since been removed.
+    pub relay: Option<RelayRef>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct LocationBreakdown {
+    pub monitor_id: Option<String>,
+    pub since_ms: i64,
+    pub until_ms: i64,
+    pub hours: i64,
+    pub items: Vec<LocationBreakdownItem>,
+}
+
+#[derive(Debug)]
+pub enum StatsError {
+    DbRun(worker::Error),
+    Analytics(worker::Error),
+    MonitorNotFound,
+}
+
+impl From<worker::Error> for StatsError {
+    fn from(err: worker::Error) -> Self {
+        StatsError::DbRun(err)
+    }
+}
+
+impl From<StatsError> for axum::http::StatusCode {
+    fn from(err: StatsError) -> axum::http::StatusCode {
+        match err {
+            StatsError::DbRun(err) => {
+                console_error!("stats.db.run: {err:?}");
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+            StatsError::Analytics(err) => {
+                console_error!("stats.ae: {err:?}");
+                axum::http::StatusCode::BAD_GATEWAY
+            }
+            StatsError::MonitorNotFound => {
+                console_error!("stats.monitor.not.found");
+                axum::http::StatusCode::NOT_FOUND
+            }
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use crate::router::AppState;
+use axum::{routing::get, Router};
+
+pub mod handlers;
+pub mod render;
+pub mod service;
+pub mod types;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(handlers::list_status_pages_handler).post(handlers::create_status_page_handler),
+        )
+        .route(
+            "/{id}",
+            get(handlers::get_status_page_handler)
+                .patch(handlers::update_status_page_handler)
+                .delete(handlers::delete_status_page_handler),
+        )
+}
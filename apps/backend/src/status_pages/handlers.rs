@@ -0,0 +1,159 @@
+use crate::analytics::backend::AnalyticsBackend;
+use crate::auth::membership::load_membership;
+use crate::cloudflare::d1::AppDb;
+use crate::router::AppState;
+use crate::status_pages::render::render_status_page;
+use crate::status_pages::service::{
+    create_status_page_for_org, delete_status_page_for_org, get_status_page_detail_for_org,
+    list_status_pages_for_org_all, load_public_status_page, update_status_page_for_org,
+};
+use crate::status_pages::types::{CreateStatusPage, StatusPage, StatusPageError, UpdateStatusPage};
+use axum::{
+    extract::{Path, State},
+    http::{StatusCode, Uri},
+    response::{Response, Result},
+    Json,
+};
+use hb_auth::User;
+use worker::{console_error, Cache};
+
+/// Edge cache lifetime for public pages. Edits show up once the cached copy expires.
+const PUBLIC_CACHE_TTL_SECONDS: u32 = 60;
+
+#[worker::send]
+#[tracing::instrument(name = "status_pages.http.list", skip(d1), fields(subject = %auth.sub()))]
+pub async fn list_status_pages_handler(
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<Json<Vec<StatusPage>>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match list_status_pages_for_org_all(&d1, &org_id).await {
+        Ok(pages) => Ok(Json(pages)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(name = "status_pages.http.get", skip(d1), fields(status_page_id = %id))]
+pub async fn get_status_page_handler(
+    Path(id): Path<String>,
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<Json<StatusPage>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match get_status_page_detail_for_org(&d1, &org_id, &id).await {
+        Ok(page) => Ok(Json(page)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "status_pages.http.create",
+    skip(d1, page),
+    fields(identity_id = %auth.sub())
+)]
+pub async fn create_status_page_handler(
+    AppDb(d1): AppDb,
+    auth: User,
+    Json(page): Json<CreateStatusPage>,
+) -> Result<Json<StatusPage>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match create_status_page_for_org(&d1, &org_id, page).await {
+        Ok(page) => Ok(Json(page)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "status_pages.http.update",
+    skip(d1, page),
+    fields(status_page_id = %id, identity_id = %auth.sub())
+)]
+pub async fn update_status_page_handler(
+    Path(id): Path<String>,
+    AppDb(d1): AppDb,
+    auth: User,
+    Json(page): Json<UpdateStatusPage>,
+) -> Result<Json<StatusPage>, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match update_status_page_for_org(&d1, &org_id, &id, page).await {
+        Ok(page) => Ok(Json(page)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[worker::send]
+#[tracing::instrument(
+    name = "status_pages.http.delete",
+    skip(d1),
+    fields(status_page_id = %id, identity_id = %auth.sub())
+)]
+pub async fn delete_status_page_handler(
+    Path(id): Path<String>,
+    AppDb(d1): AppDb,
+    auth: User,
+) -> Result<StatusCode, StatusCode> {
+    let org_id = load_membership(&d1, auth.sub()).await?.organization_id;
+    match delete_status_page_for_org(&d1, &org_id, &id).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Serves `/status/{slug}` (HTML) and `/status/{slug}.json` without authentication.
+#[worker::send]
+#[tracing::instrument(name = "status_pages.http.public", skip(state, uri), fields(slug = %slug))]
+pub async fn public_status_page_handler(
+    Path(slug): Path<String>,
+    State(state): State<AppState>,
+    AppDb(d1): AppDb,
+    uri: Uri,
+) -> Result<Response, StatusCode> {
+    let cache = Cache::default();
+    let cache_key = uri.to_string();
+    match cache.get(&cache_key, false).await {
+        Ok(Some(cached)) => return Ok(cached.into()),
+        Ok(None) => {}
+        Err(err) => console_error!("status_pages.cache.get: {err:?}"),
+    }
+
+    let (slug, as_json) = match slug.strip_suffix(".json") {
+        Some(slug) => (slug, true),
+        None => (slug.as_str(), false),
+    };
+    let analytics = match AnalyticsBackend::from_env(&state.env()) {
+        Ok(analytics) => Some(analytics),
+        Err(err) => {
+            console_error!("status_pages.analytics: {err:?}");
+            None
+        }
+    };
+    let page = load_public_status_page(&d1, analytics.as_ref(), slug).await?;
+
+    let response = if as_json {
+        worker::Response::from_json(&page)
+    } else {
+        worker::Response::from_html(render_status_page(&page))
+    };
+    let mut response = response.map_err(|err| StatusPageError::Render(format!("{err:?}")))?;
+    response
+        .headers_mut()
+        .set(
+            "Cache-Control",
+            &format!("public, max-age={PUBLIC_CACHE_TTL_SECONDS}"),
+        )
+        .map_err(|err| StatusPageError::Render(format!("{err:?}")))?;
+
+    match response.cloned() {
+        Ok(copy) => {
+            if let Err(err) = cache.put(&cache_key, copy).await {
+                console_error!("status_pages.cache.put: {err:?}");
+            }
+        }
+        Err(err) => console_error!("status_pages.cache.clone: {err:?}"),
+    }
+
+    Ok(response.into())
+}
@@ -0,0 +1,123 @@
+use std::fmt::Write;
+
+use worker::wasm_bindgen::JsValue;
+
+use crate::status_pages::types::{ComponentStatus, PublicComponent, PublicStatusPage};
+
+const STYLE: &str = r#"
+body{margin:0;font-family:system-ui,-apple-system,sans-serif;background:#f7f7f8;color:#18181b}
+main{max-width:760px;margin:0 auto;padding:40px 20px}
+h1{font-size:1.6rem;margin:0 0 24px}
+.banner{padding:16px 20px;border-radius:8px;color:#fff;font-weight:600;margin-bottom:32px}
+.component{background:#fff;border:1px solid #e4e4e7;border-radius:8px;padding:16px 20px;margin-bottom:12px}
+.row{display:flex;justify-content:space-between;align-items:center;gap:12px}
+.name{font-weight:600}
+.bars{display:flex;gap:2px;margin-top:12px;height:28px}
+.bar{flex:1;border-radius:2px}
+.meta{display:flex;justify-content:space-between;font-size:.75rem;color:#71717a;margin-top:6px}
+footer{font-size:.75rem;color:#71717a;margin-top:32px;text-align:center}
+.operational{background:#16a34a}.degraded{background:#d97706}.outage{background:#dc2626}.unknown{background:#a1a1aa}
+.text-operational{color:#16a34a}.text-degraded{color:#d97706}.text-outage{color:#dc2626}.text-unknown{color:#71717a}
+"#;
+
+/// Server-rendered page for `/status/{slug}`. Self-contained so it works without the SPA.
+pub fn render_status_page(page: &PublicStatusPage) -> String {
+    let title = escape_html(&page.title);
+    let mut html = String::with_capacity(4096);
+    html.push_str("<!doctype html><html lang=\"en\"><head><meta charset=\"utf-8\">");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width,initial-scale=1\">");
+    let _ = write!(html, "<title>{title}</title><style>{STYLE}</style></head>");
+    let _ = write!(html, "<body><main><h1>{title}</h1>");
+    let _ = write!(
+        html,
+        "<div class=\"banner {class}\">{label}</div>",
+        class = page.status,
+        label = banner_label(page.status),
+    );
+
+    for component in &page.components {
+        render_component(&mut html, component);
+    }
+
+    let _ = write!(
+        html,
+        "<footer>Updated <time datetime=\"{ts}\">{ts}</time> · <a href=\"{slug}.json\">JSON</a></footer>",
+        ts = iso_timestamp(page.generated_at),
+        slug = escape_html(&page.slug),
+    );
+    html.push_str("</main></body></html>");
+    html
+}
+
+fn render_component(html: &mut String, component: &PublicComponent) {
+    let _ = write!(
+        html,
+        "<section class=\"component\"><div class=\"row\"><span class=\"name\">{name}</span>\
+         <span class=\"text-{class}\">{label}</span></div>",
+        name = escape_html(&component.name),
+        class = component.status,
+        label = component.status.label(),
+    );
+
+    if !component.days.is_empty() {
+        html.push_str("<div class=\"bars\">");
+        for day in &component.days {
+            let (class, tip) = match day.uptime_pct {
+                Some(pct) => (bar_class(pct), format!("{pct:.2}% uptime")),
+                None => (ComponentStatus::Unknown, "No data".to_string()),
+            };
+            let _ = write!(html, "<div class=\"bar {class}\" title=\"{tip}\"></div>");
+        }
+        html.push_str("</div>");
+
+        let uptime = component
+            .uptime_pct
+            .map(|pct| format!("{pct:.2}% uptime"))
+            .unwrap_or_else(|| "No data".to_string());
+        let _ = write!(
+            html,
+            "<div class=\"meta\"><span>{days} days ago</span><span>{uptime}</span><span>Today</span></div>",
+            days = component.days.len(),
+        );
+    }
+
+    html.push_str("</section>");
+}
+
+fn banner_label(status: ComponentStatus) -> &'static str {
+    match status {
+        ComponentStatus::Operational => "All systems operational",
+        ComponentStatus::Degraded => "Some systems are degraded",
+        ComponentStatus::Outage => "Some systems are experiencing an outage",
+        ComponentStatus::Unknown => "Status unavailable",
+    }
+}
+
+fn bar_class(uptime_pct: f64) -> ComponentStatus {
+    if uptime_pct >= 99.9 {
+        ComponentStatus::Operational
+    } else if uptime_pct >= 95.0 {
+        ComponentStatus::Degraded
+    } else {
+        ComponentStatus::Outage
+    }
+}
+
+fn iso_timestamp(ts_ms: i64) -> String {
+    String::from(js_sys::Date::new(&JsValue::from_f64(ts_ms as f64)).to_iso_string())
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
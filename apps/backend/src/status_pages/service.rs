@@ -0,0 +1,321 @@
+use std::collections::HashSet;
+
+use cuid2::create_id;
+use worker::{console_error, D1Database};
+
+use crate::analytics::backend::HeartbeatStore;
+use crate::analytics::monitor_health::{daily_uptime, DailyUptime, TimeWindow, DAY_MS};
+use crate::d1c::queries::monitors::get_monitor_by_id;
+use crate::d1c::queries::status_pages::{
+    clear_status_page_monitors, delete_status_page, get_status_page_by_slug,
+    get_status_page_for_org, insert_status_page, insert_status_page_monitor,
+    list_status_page_components, list_status_pages_for_org, update_status_page,
+};
+use crate::status_pages::types::{
+    normalize_display_name, normalize_slug, normalize_title, ComponentStatus, CreateStatusPage,
+    PublicComponent, PublicStatusPage, StatusPage, StatusPageError, StatusPageMonitor,
+    StatusPageMonitorInput, UpdateStatusPage, UptimeBar, MAX_PAGE_MONITORS,
+};
+use crate::utils::date::now_ms;
+
+/// Number of daily bars shown per component, today included.
+pub const UPTIME_BAR_DAYS: i64 = 30;
+
+#[tracing::instrument(name = "status_pages.list_for_org", skip(d1), fields(org_id = %org_id))]
+pub async fn list_status_pages_for_org_all(
+    d1: &D1Database,
+    org_id: &str,
+) -> Result<Vec<StatusPage>, StatusPageError> {
+    let mut pages = Vec::new();
+    for row in list_status_pages_for_org(d1, org_id).await? {
+        let mut page = StatusPage::from(row);
+        page.monitors = load_page_monitors(d1, &page.id).await?;
+        pages.push(page);
+    }
+
+    Ok(pages)
+}
+
+#[tracing::instrument(
+    name = "status_pages.get_for_org",
+    skip(d1),
+    fields(org_id = %org_id, status_page_id = %status_page_id)
+)]
+pub async fn get_status_page_detail_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    status_page_id: &str,
+) -> Result<StatusPage, StatusPageError> {
+    let mut page = load_status_page(d1, org_id, status_page_id).await?;
+    page.monitors = load_page_monitors(d1, &page.id).await?;
+    Ok(page)
+}
+
+#[tracing::instrument(
+    name = "status_pages.create_for_org",
+    skip(d1, input),
+    fields(org_id = %org_id)
+)]
+pub async fn create_status_page_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    input: CreateStatusPage,
+) -> Result<StatusPage, StatusPageError> {
+    let slug = normalize_slug(&input.slug)?;
+    let title = normalize_title(&input.title)?;
+    ensure_slug_available(d1, &slug, None).await?;
+    let monitors = resolve_monitors(d1, org_id, input.monitors).await?;
+
+    let id = create_id().to_string();
+    let now = now_ms();
+    insert_status_page(d1, &id, org_id, &slug, &title, now, now).await?;
+    replace_page_monitors(d1, &id, &monitors).await?;
+
+    get_status_page_detail_for_org(d1, org_id, &id).await
+}
+
+#[tracing::instrument(
+    name = "status_pages.update_for_org",
+    skip(d1, input),
+    fields(org_id = %org_id, status_page_id = %status_page_id)
+)]
+pub async fn update_status_page_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    status_page_id: &str,
+    input: UpdateStatusPage,
+) -> Result<StatusPage, StatusPageError> {
+    if input.slug.is_none() && input.title.is_none() && input.monitors.is_none() {
+        return Err(StatusPageError::NoFieldsToUpdate);
+    }
+
+    let page = load_status_page(d1, org_id, status_page_id).await?;
+    let slug = match input.slug {
+        Some(slug) => {
+            let slug = normalize_slug(&slug)?;
+            ensure_slug_available(d1, &slug, Some(&page.id)).await?;
+            slug
+        }
+        None => page.slug,
+    };
+    let title = match input.title {
+        Some(title) => normalize_title(&title)?,
+        None => page.title,
+    };
+    let monitors = match input.monitors {
+        Some(monitors) => Some(resolve_monitors(d1, org_id, monitors).await?),
+        None => None,
+    };
+
+    update_status_page(d1, &slug, &title, now_ms(), &page.id, org_id).await?;
+    if let Some(monitors) = monitors {
+        clear_status_page_monitors(d1, &page.id).await?;
+        replace_page_monitors(d1, &page.id, &monitors).await?;
+    }
+
+    get_status_page_detail_for_org(d1, org_id, &page.id).await
+}
+
+#[tracing::instrument(
+    name = "status_pages.delete_for_org",
+    skip(d1),
+    fields(org_id = %org_id, status_page_id = %status_page_id)
+)]
+pub async fn delete_status_page_for_org(
+    d1: &D1Database,
+    org_id: &str,
+    status_page_id: &str,
+) -> Result<(), StatusPageError> {
+    let page = load_status_page(d1, org_id, status_page_id).await?;
+    delete_status_page(d1, &page.id, org_id).await?;
+    Ok(())
+}
+
+/// Builds the unauthenticated view of a page. Uptime bars are best effort: when the heartbeat
+/// store is unavailable the page still renders with current statuses only.
+#[tracing::instrument(name = "status_pages.load_public", skip(d1, analytics), fields(slug = %slug))]
+pub async fn load_public_status_page(
+    d1: &D1Database,
+    analytics: Option<&impl HeartbeatStore>,
+    slug: &str,
+) -> Result<PublicStatusPage, StatusPageError> {
+    let page = get_status_page_by_slug(d1, slug)
+        .await?
+        .ok_or(StatusPageError::NotFound)?;
+    let page_id = page.id.unwrap_or_default();
+    let rows = list_status_page_components(d1, &page_id).await?;
+    let mut components = rows
+        .iter()
+        .map(PublicComponent::from_row)
+        .collect::<Vec<_>>();
+
+    let now = now_ms();
+    let first_day = (now / DAY_MS - (UPTIME_BAR_DAYS - 1)) * DAY_MS;
+    if let Some(analytics) = analytics {
+        let monitor_ids = rows
+            .iter()
+            .map(|row| row.monitor_id.clone())
+            .collect::<Vec<_>>();
+        let window = TimeWindow {
+            since_ms: first_day,
+            until_ms: now,
+        };
+        match daily_uptime(analytics, &page.org_id, &monitor_ids, &window).await {
+            Ok(days) => {
+                for (component, row) in components.iter_mut().zip(rows.iter()) {
+                    attach_uptime(component, &row.monitor_id, &days, first_day);
+                }
+            }
+            Err(err) => console_error!("status_pages.uptime.analytics: {err:?}"),
+        }
+    }
+
+    Ok(PublicStatusPage {
+        slug: page.slug,
+        title: page.title,
+        status: overall_status(&components),
+        components,
+        generated_at: now,
+    })
+}
+
+fn attach_uptime(
+    component: &mut PublicComponent,
+    monitor_id: &str,
+    days: &[DailyUptime],
+    first_day: i64,
+) {
+    let (mut checks, mut up) = (0.0, 0.0);
+    component.days = (0..UPTIME_BAR_DAYS)
+        .map(|offset| {
+            let day_start_ms = first_day + offset * DAY_MS;
+            let day = days
+                .iter()
+                .find(|day| day.monitor_id == monitor_id && day.day_start_ms == day_start_ms);
+            let uptime_pct = day.and_then(|day| {
+                checks += day.checks;
+                up += day.up;
+                uptime_pct(day.up, day.checks)
+            });
+            UptimeBar {
+                day_start_ms,
+                uptime_pct,
+            }
+        })
+        .collect();
+    component.uptime_pct = uptime_pct(up, checks);
+}
+
+fn uptime_pct(up: f64, checks: f64) -> Option<f64> {
+    if checks > 0.0 {
+        Some((up / checks * 100.0).clamp(0.0, 100.0))
+    } else {
+        None
+    }
+}
+
+/// Worst known component status; `Unknown` only when nothing has reported yet.
+fn overall_status(components: &[PublicComponent]) -> ComponentStatus {
+    let statuses = components
+        .iter()
+        .map(|component| component.status)
+        .collect::<Vec<_>>();
+    if statuses.contains(&ComponentStatus::Outage) {
+        ComponentStatus::Outage
+    } else if statuses.contains(&ComponentStatus::Degraded) {
+        ComponentStatus::Degraded
+    } else if statuses.contains(&ComponentStatus::Operational) {
+        ComponentStatus::Operational
+    } else {
+        ComponentStatus::Unknown
+    }
+}
+
+async fn load_status_page(
+    d1: &D1Database,
+    org_id: &str,
+    status_page_id: &str,
+) -> Result<StatusPage, StatusPageError> {
+    match get_status_page_for_org(d1, status_page_id, org_id).await? {
+        Some(row) => Ok(StatusPage::from(row)),
+        None => Err(StatusPageError::NotFound),
+    }
+}
+
+async fn load_page_monitors(
+    d1: &D1Database,
+    status_page_id: &str,
+) -> Result<Vec<StatusPageMonitor>, StatusPageError> {
+    Ok(list_status_page_components(d1, status_page_id)
+        .await?
+        .into_iter()
+        .map(StatusPageMonitor::from)
+        .collect())
+}
+
+async fn ensure_slug_available(
+    d1: &D1Database,
+    slug: &str,
+    current_id: Option<&str>,
+) -> Result<(), StatusPageError> {
+    match get_status_page_by_slug(d1, slug).await? {
+        Some(existing) if existing.id.as_deref() != current_id => Err(StatusPageError::Conflict(
+            format!("slug {slug} is already taken"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks every monitor belongs to the org and fills in default display names.
+async fn resolve_monitors(
+    d1: &D1Database,
+    org_id: &str,
+    inputs: Vec<StatusPageMonitorInput>,
+) -> Result<Vec<(String, String)>, StatusPageError> {
+    if inputs.len() > MAX_PAGE_MONITORS {
+        return Err(StatusPageError::InvalidInput(format!(
+            "A status page can show at most {MAX_PAGE_MONITORS} monitors"
+        )));
+    }
+
+    let mut seen = HashSet::new();
+    let mut monitors = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        if !seen.insert(input.monitor_id.clone()) {
+            return Err(StatusPageError::InvalidInput(format!(
+                "Monitor {} is listed more than once",
+                input.monitor_id
+            )));
+        }
+
+        let monitor = get_monitor_by_id(d1, &input.monitor_id, org_id)
+            .await?
+            .ok_or_else(|| StatusPageError::MonitorNotFound(input.monitor_id.clone()))?;
+        let display_name = match input.display_name {
+            Some(name) => normalize_display_name(&name)?,
+            None => normalize_display_name(&monitor.name)?,
+        };
+        monitors.push((input.monitor_id, display_name));
+    }
+
+    Ok(monitors)
+}
+
+async fn replace_page_monitors(
+    d1: &D1Database,
+    status_page_id: &str,
+    monitors: &[(String, String)],
+) -> Result<(), StatusPageError> {
+    for (position, (monitor_id, display_name)) in monitors.iter().enumerate() {
+        insert_status_page_monitor(
+            d1,
+            status_page_id,
+            monitor_id,
+            display_name,
+            position as i64,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
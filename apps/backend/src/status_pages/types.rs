@@ -0,0 +1,266 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use strum::Display;
+use worker::console_error;
+
+use crate::d1c::queries::status_pages::{
+    GetStatusPageForOrgRow, ListStatusPageComponentsRow, ListStatusPagesForOrgRow,
+};
+use crate::monitors::types::MonitorStatus;
+
+const MIN_SLUG_LENGTH: usize = 2;
+const MAX_SLUG_LENGTH: usize = 48;
+const MAX_TITLE_LENGTH: usize = 120;
+const MAX_DISPLAY_NAME_LENGTH: usize = 80;
+pub const MAX_PAGE_MONITORS: usize = 50;
+
+/// Public-facing status. Deliberately coarser than `MonitorStatus`.
+#[derive(Debug, Serialize, Clone, Copy, Display, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    Operational,
+    Unknown,
+    Degraded,
+    Outage,
+}
+
+impl From<MonitorStatus> for ComponentStatus {
+    fn from(status: MonitorStatus) -> Self {
+        match status {
+            MonitorStatus::Up => ComponentStatus::Operational,
+            MonitorStatus::Degraded => ComponentStatus::Degraded,
+            MonitorStatus::Down => ComponentStatus::Outage,
+            MonitorStatus::Pending => ComponentStatus::Unknown,
+        }
+    }
+}
+
+impl ComponentStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ComponentStatus::Operational => "Operational",
+            ComponentStatus::Unknown => "No data",
+            ComponentStatus::Degraded => "Degraded performance",
+            ComponentStatus::Outage => "Outage",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct StatusPageMonitor {
+    pub monitor_id: String,
+    pub display_name: String,
+    pub position: i64,
+}
+
+impl From<ListStatusPageComponentsRow> for StatusPageMonitor {
+    fn from(row: ListStatusPageComponentsRow) -> Self {
+        StatusPageMonitor {
+            monitor_id: row.monitor_id,
+            display_name: row.display_name,
+            position: row.position,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct StatusPage {
+    pub id: String,
+    pub slug: String,
+    pub title: String,
+    pub monitors: Vec<StatusPageMonitor>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<ListStatusPagesForOrgRow> for StatusPage {
+    fn from(row: ListStatusPagesForOrgRow) -> Self {
+        StatusPage {
+            id: row.id.unwrap_or_default(),
+            slug: row.slug,
+            title: row.title,
+            monitors: Vec::new(),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl From<GetStatusPageForOrgRow> for StatusPage {
+    fn from(row: GetStatusPageForOrgRow) -> Self {
+        StatusPage {
+            id: row.id.unwrap_or_default(),
+            slug: row.slug,
+            title: row.title,
+            monitors: Vec::new(),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct StatusPageMonitorInput {
+    pub monitor_id: String,
+    /// Defaults to the monitor name.
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct CreateStatusPage {
+    pub slug: String,
+    pub title: String,
+    #[serde(default)]
+    pub monitors: Vec<StatusPageMonitorInput>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct UpdateStatusPage {
+    pub slug: Option<String>,
+    pub title: Option<String>,
+    /// Replaces the full component list, order included.
+    pub monitors: Option<Vec<StatusPageMonitorInput>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct UptimeBar {
+    pub day_start_ms: i64,
+    /// `None` when no heartbeats were recorded that day.
+    pub uptime_pct: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct PublicComponent {
+    pub name: String,
+    pub status: ComponentStatus,
+    pub last_checked_at: Option<i64>,
+    pub uptime_pct: Option<f64>,
+    pub days: Vec<UptimeBar>,
+}
+
+impl PublicComponent {
+    pub fn from_row(row: &ListStatusPageComponentsRow) -> Self {
+        let status = MonitorStatus::from_str(&row.status)
+            .map(ComponentStatus::from)
+            .unwrap_or(ComponentStatus::Unknown);
+
+        PublicComponent {
+            name: row.display_name.clone(),
+            status,
+            last_checked_at: row.last_checked_at,
+            uptime_pct: None,
+            days: Vec::new(),
+        }
+    }
+}
+
+/// What `/status/{slug}` renders and `/status/{slug}.json` returns. Never exposes monitor
+/// ids, targets or errors.
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct PublicStatusPage {
+    pub slug: String,
+    pub title: String,
+    pub status: ComponentStatus,
+    pub components: Vec<PublicComponent>,
+    pub generated_at: i64,
+}
+
+pub fn normalize_slug(slug: &str) -> Result<String, StatusPageError> {
+    let normalized = slug.trim().to_ascii_lowercase();
+    if !(MIN_SLUG_LENGTH..=MAX_SLUG_LENGTH).contains(&normalized.len()) {
+        return Err(StatusPageError::InvalidInput(format!(
+            "Slug must be between {MIN_SLUG_LENGTH} and {MAX_SLUG_LENGTH} characters"
+        )));
+    }
+    if !normalized
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        return Err(StatusPageError::InvalidInput(
+            "Slug may only contain lowercase letters, numbers, or hyphens".to_string(),
+        ));
+    }
+
+    Ok(normalized)
+}
+
+pub fn normalize_title(title: &str) -> Result<String, StatusPageError> {
+    let trimmed = title.trim();
+    if trimmed.is_empty() || trimmed.chars().count() > MAX_TITLE_LENGTH {
+        return Err(StatusPageError::InvalidInput(format!(
+            "Title must be between 1 and {MAX_TITLE_LENGTH} characters"
+        )));
+    }
+
+    Ok(trimmed.to_string())
+}
+
+pub fn normalize_display_name(name: &str) -> Result<String, StatusPageError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() || trimmed.chars().count() > MAX_DISPLAY_NAME_LENGTH {
+        return Err(StatusPageError::InvalidInput(format!(
+            "Display name must be between 1 and {MAX_DISPLAY_NAME_LENGTH} characters"
+        )));
+    }
+
+    Ok(trimmed.to_string())
+}
+
+#[derive(Debug)]
+pub enum StatusPageError {
+    DbRun(worker::Error),
+    NotFound,
+    MonitorNotFound(String),
+    InvalidInput(String),
+    Conflict(String),
+    NoFieldsToUpdate,
+    Render(String),
+}
+
+impl From<worker::Error> for StatusPageError {
+    fn from(err: worker::Error) -> Self {
+        StatusPageError::DbRun(err)
+    }
+}
+
+impl From<StatusPageError> for axum::http::StatusCode {
+    fn from(err: StatusPageError) -> axum::http::StatusCode {
+        match err {
+            StatusPageError::DbRun(err) => {
+                console_error!("status_pages.db.run: {err:?}");
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+            StatusPageError::NotFound => axum::http::StatusCode::NOT_FOUND,
+            StatusPageError::MonitorNotFound(monitor_id) => {
+                console_error!("status_pages.monitor.not.found: {monitor_id}");
+                axum::http::StatusCode::BAD_REQUEST
+            }
+            StatusPageError::InvalidInput(reason) => {
+                console_error!("status_pages.invalid.input: {reason}");
+                axum::http::StatusCode::BAD_REQUEST
+            }
+            StatusPageError::Conflict(reason) => {
+                console_error!("status_pages.conflict: {reason}");
+                axum::http::StatusCode::CONFLICT
+            }
+            StatusPageError::NoFieldsToUpdate => {
+                console_error!("status_pages.no.fields.to.update");
+                axum::http::StatusCode::BAD_REQUEST
+            }
+            StatusPageError::Render(reason) => {
+                console_error!("status_pages.render: {reason}");
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
@@ -1,2 +1,3 @@
 pub mod date;
+pub mod gzip;
 pub mod wasm_types;
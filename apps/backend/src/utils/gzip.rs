@@ -0,0 +1,32 @@
+//! Gzip through the runtime's `CompressionStream` / `DecompressionStream`. web-sys only exposes
+//! those behind `web_sys_unstable_apis`, so they are looked up on the global scope instead.
+
+use js_sys::{Array, Function, Reflect, Uint8Array};
+use worker::wasm_bindgen::{JsCast, JsValue};
+use worker::web_sys::{Blob, ReadableStream};
+use worker::{Response, ResponseBody, Result};
+
+pub async fn gzip(bytes: &[u8]) -> Result<Vec<u8>> {
+    transform(bytes, "CompressionStream").await
+}
+
+pub async fn gunzip(bytes: &[u8]) -> Result<Vec<u8>> {
+    transform(bytes, "DecompressionStream").await
+}
+
+async fn transform(bytes: &[u8], class: &str) -> Result<Vec<u8>> {
+    let constructor =
+        Reflect::get(&js_sys::global(), &JsValue::from_str(class))?.dyn_into::<Function>()?;
+    let transform = Reflect::construct(&constructor, &Array::of1(&JsValue::from_str("gzip")))?;
+
+    let source = Blob::new_with_u8_array_sequence(&Array::of1(&Uint8Array::from(bytes)))?.stream();
+    let pipe_through =
+        Reflect::get(&source, &JsValue::from_str("pipeThrough"))?.dyn_into::<Function>()?;
+    let output = pipe_through
+        .call1(&source, &transform)?
+        .unchecked_into::<ReadableStream>();
+
+    Response::from_body(ResponseBody::Stream(output))?
+        .bytes()
+        .await
+}